@@ -0,0 +1,745 @@
+use std::{collections::HashMap, fmt::Display};
+
+use fst::*;
+use num::{BigInt, ToPrimitive, Zero};
+
+use crate::{
+    visit::{walk_expression, walk_file, walk_statement, Visitor},
+    AnalysisError, Definition, LocatedAnalysisError, Resolution,
+};
+
+/// The number of loop iterations and calls a `const` block may take before it is given up on
+const MAX_STEPS: usize = 100_000;
+/// The number of nested calls to `#[cte]` functions, deeper recursion is reported as an error
+const MAX_CALL_DEPTH: usize = 128;
+/// The number of bits of the largest Int `**` may compute, a larger power is reported as an error
+/// instead of computing it
+const MAX_POWER_BITS: u64 = 1 << 20;
+
+/// Evaluates every `const { ... }` block in a file and reports the ones that fail, at the
+/// location of the block.
+///
+/// Blocks may call the functions marked `#[cte]` and the builtin `assert(condition)`, and read
+/// the immutable `let`s at the top of the file whose values can be evaluated, the ones the
+/// `resolution` of the file resolves their names to. A block using something the evaluator does
+/// not support, such as a closure, a method call or an imported name, is skipped, as whether it
+/// fails can not be known.
+pub fn evaluate_const_blocks(file: &File, resolution: &Resolution) -> Vec<LocatedAnalysisError> {
+    let mut functions = CteFunctions::default();
+    walk_file(&mut functions, file);
+    let globals = globals(file, &functions.functions, resolution);
+    let mut blocks = ConstBlocks {
+        functions: functions.functions,
+        globals,
+        resolution,
+        errors: Vec::new(),
+    };
+    walk_file(&mut blocks, file);
+    blocks.errors
+}
+
+/// Collects the functions of a file by their name, with the closure of those marked `#[cte]`,
+/// which can be called at compile time
+#[derive(Default)]
+struct CteFunctions<'a> {
+    functions: HashMap<String, Option<&'a Closure>>,
+}
+
+impl<'a> CteFunctions<'a> {
+    fn collect(&mut self, statement: &'a Located<Statement>) {
+        if let Statement::Function {
+            attributes,
+            name,
+            closure,
+            ..
+        } = &statement.value
+        {
+            let is_cte = attributes
+                .iter()
+                .flat_map(|list| list.attributes.values())
+                .any(|attribute| attribute.value.name == "cte");
+            self.functions
+                .insert(name.clone(), is_cte.then_some(&closure.value));
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for CteFunctions<'a> {
+    fn visit_statement(&mut self, statement: &'a Located<Statement>) {
+        self.collect(statement);
+        walk_statement(self, statement);
+    }
+}
+
+/// The values of the top level `let a = ...;` declarations that can be evaluated, by the
+/// location of their name
+fn globals(
+    file: &File,
+    functions: &HashMap<String, Option<&Closure>>,
+    resolution: &Resolution,
+) -> HashMap<SourceSpan, Value> {
+    let mut globals = HashMap::new();
+    for (statement, _) in &file.statements {
+        let Statement::Expression { expr, .. } = &statement.value else {
+            continue;
+        };
+        let Expression::Declaration {
+            creation,
+            initializer: Some(initializer),
+            ..
+        } = &expr.value
+        else {
+            continue;
+        };
+        let VariableCreation::Identifier { mutable: None, .. } = &creation.value else {
+            continue;
+        };
+        let mut evaluator = Evaluator::new(functions, &globals, resolution);
+        if let Ok(value) = evaluator.expression(&initializer.value) {
+            globals.insert(creation.source_span, value);
+        }
+    }
+    globals
+}
+
+struct ConstBlocks<'a, 'r> {
+    functions: HashMap<String, Option<&'a Closure>>,
+    globals: HashMap<SourceSpan, Value>,
+    resolution: &'r Resolution,
+    errors: Vec<LocatedAnalysisError>,
+}
+
+impl<'a> Visitor<'a> for ConstBlocks<'a, '_> {
+    fn visit_expression(&mut self, expression: &'a Located<Expression>) {
+        if let Expression::Const { block, .. } = &expression.value {
+            let mut evaluator = Evaluator::new(&self.functions, &self.globals, self.resolution);
+            if let Err(Interrupt::Failure(error)) = evaluator.block(block) {
+                self.errors
+                    .push(Located::new(error, expression.source_span));
+            }
+            // nested blocks are evaluated as part of this one
+            return;
+        }
+        walk_expression(self, expression);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Int(BigInt),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    Array(Vec<Value>),
+    Unit,
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "Int",
+            Value::Float(_) => "Float",
+            Value::Bool(_) => "Bool",
+            Value::String(_) => "String",
+            Value::Array(_) => "Array",
+            Value::Unit => "()",
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Int(int) => write!(f, "{}", int),
+            Value::Float(float) => write!(f, "{}", float),
+            Value::Bool(boolean) => write!(f, "{}", boolean),
+            Value::String(string) => write!(f, "{}", string),
+            Value::Array(elements) => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            Value::Unit => write!(f, "()"),
+        }
+    }
+}
+
+/// Why the evaluation of an expression stopped before producing a value
+enum Interrupt {
+    Return(Value),
+    Break(Value),
+    Continue,
+    Failure(AnalysisError),
+    /// The expression uses something the evaluator can not evaluate, which is not an error
+    Unsupported,
+}
+
+type Evaluation<T = Value> = Result<T, Interrupt>;
+
+fn fail<T>(message: impl Into<String>) -> Evaluation<T> {
+    Err(Interrupt::Failure(AnalysisError::ConstEvaluation(
+        message.into(),
+    )))
+}
+
+fn unknown<T>(name: impl Into<String>) -> Evaluation<T> {
+    Err(Interrupt::Failure(AnalysisError::UnknownConst(name.into())))
+}
+
+fn unsupported<T>() -> Evaluation<T> {
+    Err(Interrupt::Unsupported)
+}
+
+struct Evaluator<'a, 'f> {
+    functions: &'f HashMap<String, Option<&'a Closure>>,
+    globals: &'f HashMap<SourceSpan, Value>,
+    resolution: &'f Resolution,
+    // the innermost scope is last, a call starts over with the scope of its parameters
+    scopes: Vec<HashMap<String, Value>>,
+    steps: usize,
+    depth: usize,
+}
+
+impl<'a, 'f> Evaluator<'a, 'f> {
+    fn new(
+        functions: &'f HashMap<String, Option<&'a Closure>>,
+        globals: &'f HashMap<SourceSpan, Value>,
+        resolution: &'f Resolution,
+    ) -> Self {
+        Evaluator {
+            functions,
+            globals,
+            resolution,
+            scopes: vec![HashMap::new()],
+            steps: 0,
+            depth: 0,
+        }
+    }
+
+    fn step(&mut self) -> Evaluation<()> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return fail(format!("did not finish within {} steps", MAX_STEPS));
+        }
+        Ok(())
+    }
+
+    fn lookup(&mut self, name: &str) -> Option<&mut Value> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+    }
+
+    fn declare(&mut self, name: &str, value: Value) {
+        self.scopes
+            .last_mut()
+            .expect("the evaluator always has a scope")
+            .insert(name.to_string(), value);
+    }
+
+    fn scoped<T>(&mut self, f: impl FnOnce(&mut Self) -> Evaluation<T>) -> Evaluation<T> {
+        self.scopes.push(HashMap::new());
+        let result = f(self);
+        self.scopes.pop();
+        result
+    }
+
+    /// The value of a block is the value of its last statement when it has no semicolon
+    fn block(&mut self, block: &Block) -> Evaluation {
+        self.scoped(|evaluator| {
+            let mut value = Value::Unit;
+            for (statement, _) in &block.statements {
+                value = evaluator.statement(&statement.value)?;
+            }
+            Ok(value)
+        })
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Evaluation {
+        match statement {
+            Statement::Expression { expr, semi, .. } => {
+                let value = self.expression(expr)?;
+                Ok(if semi.is_some() { Value::Unit } else { value })
+            }
+            Statement::Return(label_expression) => {
+                Err(Interrupt::Return(self.label_expression(label_expression)?))
+            }
+            Statement::Break(label_expression) => {
+                Err(Interrupt::Break(self.label_expression(label_expression)?))
+            }
+            Statement::Continue(SpacedLabel { label: None, .. }) => Err(Interrupt::Continue),
+            Statement::Continue(_) => unsupported(),
+            // items don't run, the functions that can be called are collected up front
+            _ => Ok(Value::Unit),
+        }
+    }
+
+    fn label_expression(&mut self, label_expression: &LabelExpression) -> Evaluation {
+        match label_expression {
+            LabelExpression::WithExpression {
+                label: None, expr, ..
+            } => self.expression(expr),
+            LabelExpression::NoExpression(SpacedLabel { label: None, .. }) => Ok(Value::Unit),
+            _ => unsupported(),
+        }
+    }
+
+    fn expression(&mut self, expression: &Located<Expression>) -> Evaluation {
+        match &expression.value {
+            Expression::Literal { value } => Ok(literal(value)),
+            Expression::InterpolatedString { parts, .. } => {
+                let mut string = String::new();
+                for part in parts {
+                    match part {
+                        InterpolatedPart::Text { value, .. } => string.push_str(value),
+                        InterpolatedPart::Expression(expr) => {
+                            let value = self.expression(&expr.value)?;
+                            string.push_str(&value.to_string());
+                        }
+                    }
+                }
+                Ok(Value::String(string))
+            }
+            Expression::Variable { identifier } => {
+                // the top level `let`s are found by their definition, as a later `let` of the
+                // file may reuse the name
+                let global = match self.resolution.definition(expression) {
+                    Some(Definition::Local(definition)) => self.globals.get(&definition),
+                    _ => None,
+                };
+                match global.or(self.lookup(identifier).map(|value| &*value)) {
+                    Some(value) => Ok(value.clone()),
+                    None => unsupported(),
+                }
+            }
+            Expression::Parenthesized { expr, .. } => self.expression(expr),
+            Expression::SingleOperation {
+                operation: UnaryOperation::Call { arguments },
+                operand,
+                ..
+            } => self.call(&operand.value, arguments),
+            Expression::SingleOperation {
+                operation, operand, ..
+            } => {
+                let value = self.expression(operand)?;
+                self.unary(operation, value)
+            }
+            Expression::Operation {
+                left,
+                operator: Operator::Assignment,
+                right,
+                ..
+            } => {
+                let Expression::Variable { identifier } = &left.value else {
+                    return unsupported();
+                };
+                let value = self.expression(right)?;
+                match self.lookup(identifier) {
+                    Some(variable) => *variable = value,
+                    None => return unsupported(),
+                }
+                Ok(Value::Unit)
+            }
+            Expression::Operation {
+                left,
+                operator,
+                right,
+                ..
+            } => {
+                let left = self.expression(left)?;
+                // `&&` and `||` only evaluate their right side when it decides the result
+                match (operator, &left) {
+                    (Operator::And, Value::Bool(false)) => return Ok(Value::Bool(false)),
+                    (Operator::Or, Value::Bool(true)) => return Ok(Value::Bool(true)),
+                    _ => {}
+                }
+                let right = self.expression(right)?;
+                binary(*operator, left, right)
+            }
+            Expression::Array { elements } => {
+                let elements = elements
+                    .values()
+                    .map(|element| self.expression(element))
+                    .collect::<Evaluation<_>>()?;
+                Ok(Value::Array(elements))
+            }
+            Expression::Declaration {
+                creation,
+                initializer,
+                ..
+            } => {
+                let VariableCreation::Identifier { name, .. } = &creation.value else {
+                    return unsupported();
+                };
+                let value = match initializer {
+                    Some(initializer) => self.expression(&initializer.value)?,
+                    None => Value::Unit,
+                };
+                self.declare(name, value);
+                Ok(Value::Unit)
+            }
+            Expression::Block {
+                environment: None,
+                block,
+            }
+            | Expression::Unsafe { block, .. }
+            | Expression::Const { block, .. } => self.block(block),
+            Expression::If {
+                if_block,
+                else_if_blocks,
+                else_block,
+            } => {
+                let else_ifs = else_if_blocks.iter().map(|else_if| &else_if.value);
+                for if_block in std::iter::once(if_block).chain(else_ifs) {
+                    if self.condition(&if_block.condition)? {
+                        return self.block(&if_block.block);
+                    }
+                }
+                match else_block {
+                    Some(else_block) => self.block(&else_block.value),
+                    None => Ok(Value::Unit),
+                }
+            }
+            Expression::While {
+                label: None,
+                condition,
+                body,
+                else_block,
+                ..
+            } => {
+                while self.condition(condition)? {
+                    self.step()?;
+                    match self.expression(body) {
+                        Ok(_) | Err(Interrupt::Continue) => {}
+                        Err(Interrupt::Break(value)) => return Ok(value),
+                        Err(interrupt) => return Err(interrupt),
+                    }
+                }
+                match else_block {
+                    Some(else_block) => self.expression(&else_block.value),
+                    None => Ok(Value::Unit),
+                }
+            }
+            Expression::Loop {
+                label: None, body, ..
+            } => loop {
+                self.step()?;
+                match self.expression(body) {
+                    Ok(_) | Err(Interrupt::Continue) => {}
+                    Err(Interrupt::Break(value)) => return Ok(value),
+                    Err(interrupt) => return Err(interrupt),
+                }
+            },
+            // labels, environment blocks, closures, struct literals, match and for
+            _ => unsupported(),
+        }
+    }
+
+    fn condition(&mut self, condition: &Located<Expression>) -> Evaluation<bool> {
+        match self.expression(condition)? {
+            Value::Bool(condition) => Ok(condition),
+            other => fail(format!(
+                "expected a Bool condition, got {}",
+                other.type_name()
+            )),
+        }
+    }
+
+    fn unary(&mut self, operation: &UnaryOperation, value: Value) -> Evaluation {
+        match (operation, value) {
+            (UnaryOperation::Not, Value::Bool(boolean)) => Ok(Value::Bool(!boolean)),
+            (UnaryOperation::Negate, Value::Int(int)) => Ok(Value::Int(-int)),
+            (UnaryOperation::Negate, Value::Float(float)) => Ok(Value::Float(-float)),
+            (UnaryOperation::Positive, value @ (Value::Int(_) | Value::Float(_))) => Ok(value),
+            (UnaryOperation::Get { property, .. }, Value::Array(elements)) => {
+                match self.expression(property)? {
+                    Value::Int(index) => match index.to_usize().and_then(|i| elements.get(i)) {
+                        Some(element) => Ok(element.clone()),
+                        None => fail(format!(
+                            "index {} is out of bounds for an Array of length {}",
+                            index,
+                            elements.len()
+                        )),
+                    },
+                    other => fail(format!("expected an Int index, got {}", other.type_name())),
+                }
+            }
+            (UnaryOperation::Not | UnaryOperation::Negate | UnaryOperation::Positive, value) => {
+                fail(format!("invalid operand {}", value.type_name()))
+            }
+            // properties and methods
+            _ => unsupported(),
+        }
+    }
+
+    fn call(&mut self, callee: &Expression, arguments: &List<Located<Expression>>) -> Evaluation {
+        self.step()?;
+        let Expression::Variable { identifier } = callee else {
+            return unsupported();
+        };
+        if identifier == "assert" {
+            let [condition] = arguments.values().collect::<Vec<_>>()[..] else {
+                return fail("`assert` takes a single condition");
+            };
+            return match self.condition(condition)? {
+                true => Ok(Value::Unit),
+                false => Err(Interrupt::Failure(AnalysisError::ConstAssertion)),
+            };
+        }
+        let function = match self.functions.get(identifier) {
+            Some(Some(function)) => *function,
+            // a function of the file that is not marked `#[cte]`
+            Some(None) => return unknown(identifier),
+            None => return unsupported(),
+        };
+        let params = &function.closure_signature.params;
+        if arguments.values().count() != params.values().count() {
+            return fail(format!(
+                "`{}` takes {} arguments, got {}",
+                identifier,
+                params.values().count(),
+                arguments.values().count()
+            ));
+        }
+        let mut scope = HashMap::new();
+        for (param, argument) in params.values().zip(arguments.values()) {
+            let VariableCreation::Identifier { name, .. } = &param.creation.value else {
+                return unsupported();
+            };
+            let value = self.expression(argument)?;
+            scope.insert(name.clone(), value);
+        }
+        if self.depth == MAX_CALL_DEPTH {
+            return fail(format!("calls are nested deeper than {}", MAX_CALL_DEPTH));
+        }
+        let caller_scopes = std::mem::replace(&mut self.scopes, vec![scope]);
+        self.depth += 1;
+        let result = self.expression(&function.body);
+        self.depth -= 1;
+        self.scopes = caller_scopes;
+        match result {
+            Ok(value) | Err(Interrupt::Return(value)) => Ok(value),
+            Err(Interrupt::Break(_) | Interrupt::Continue) => fail("`break` outside of a loop"),
+            Err(failure) => Err(failure),
+        }
+    }
+}
+
+fn literal(literal: &Literal) -> Value {
+    match literal {
+        Literal::Number(NumberLiteral {
+            value: Number::Integer(int),
+            ..
+        }) => Value::Int(int.clone()),
+        Literal::Number(NumberLiteral {
+            value: Number::Float(float),
+            ..
+        }) => Value::Float(*float),
+        Literal::String(string) | Literal::RawString(string) => Value::String(string.value.clone()),
+        Literal::Boolean(boolean) => Value::Bool(*boolean),
+    }
+}
+
+fn binary(operator: Operator, left: Value, right: Value) -> Evaluation {
+    use Operator::*;
+    let value = match (operator, left, right) {
+        (Equals, left, right) => Value::Bool(left == right),
+        (NotEquals, left, right) => Value::Bool(left != right),
+        (And, Value::Bool(left), Value::Bool(right)) => Value::Bool(left && right),
+        (Or, Value::Bool(left), Value::Bool(right)) => Value::Bool(left || right),
+        (Add | WrappingAdd, Value::String(left), Value::String(right)) => {
+            Value::String(left + &right)
+        }
+        (Add | WrappingAdd, Value::Array(left), Value::Array(right)) => {
+            Value::Array(left.into_iter().chain(right).collect())
+        }
+        (operator, Value::Int(left), Value::Int(right)) => match operator {
+            Add | WrappingAdd => Value::Int(left + right),
+            Subtract | WrappingSubtract => Value::Int(left - right),
+            Multiply | WrappingMultiply => Value::Int(left * right),
+            Divide | Modulo if right.is_zero() => return fail("division by zero"),
+            Divide => Value::Int(left / right),
+            Modulo => Value::Int(left % right),
+            Power => match right.to_u64() {
+                // the power has at least `exponent` times the bits of `left` after its first one
+                Some(exponent)
+                    if left.bits().saturating_sub(1).saturating_mul(exponent) > MAX_POWER_BITS =>
+                {
+                    return fail(format!(
+                        "the power with the exponent {} is too large",
+                        right
+                    ))
+                }
+                Some(exponent) => Value::Int(num::pow(left, exponent as usize)),
+                None => return fail(format!("invalid exponent {}", right)),
+            },
+            LessThan => Value::Bool(left < right),
+            LessThanOrEquals => Value::Bool(left <= right),
+            GreaterThan => Value::Bool(left > right),
+            GreaterThanOrEquals => Value::Bool(left >= right),
+            _ => return unsupported(),
+        },
+        (operator, Value::Float(left), Value::Float(right)) => match operator {
+            Add | WrappingAdd => Value::Float(left + right),
+            Subtract | WrappingSubtract => Value::Float(left - right),
+            Multiply | WrappingMultiply => Value::Float(left * right),
+            Divide => Value::Float(left / right),
+            Modulo => Value::Float(left % right),
+            Power => Value::Float(left.powf(right)),
+            LessThan => Value::Bool(left < right),
+            LessThanOrEquals => Value::Bool(left <= right),
+            GreaterThan => Value::Bool(left > right),
+            GreaterThanOrEquals => Value::Bool(left >= right),
+            _ => return unsupported(),
+        },
+        (operator, Value::String(left), Value::String(right)) => match operator {
+            LessThan => Value::Bool(left < right),
+            LessThanOrEquals => Value::Bool(left <= right),
+            GreaterThan => Value::Bool(left > right),
+            GreaterThanOrEquals => Value::Bool(left >= right),
+            _ => return unsupported(),
+        },
+        (_, left, right) => {
+            return fail(format!(
+                "invalid operands {} and {}",
+                left.type_name(),
+                right.type_name()
+            ))
+        }
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{import_names, resolve_names, test_utils::errors};
+
+    fn evaluate(code: &str) -> Vec<AnalysisError> {
+        let pass = |file: &File| {
+            let resolution = resolve_names(file, &[], |path| import_names(&path.value));
+            evaluate_const_blocks(file, &resolution)
+        };
+        errors(code, pass, |error| Some(error.clone()))
+    }
+
+    #[test]
+    fn test_passing_blocks() {
+        for code in [
+            "const { assert(1 + 2 * 3 == 7) };",
+            "const { let mut a = 0; while a < 10 { a = a + 1; }; assert(a == 10) };",
+            "const { assert(\"a\" + \"b\" == \"ab\" && [1, 2][1] == 2) };",
+            "const { assert(if 2 ** 10 > 1000 { true } else { false }) };",
+            "const { assert('{1 + 1} items' == \"2 items\") };",
+            "let a = const { 5 / 2 };",
+        ] {
+            assert_eq!(evaluate(code), vec![], "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_failing_blocks() {
+        assert_eq!(
+            evaluate("const { assert(1 > 2) }; const { assert(true) };"),
+            vec![AnalysisError::ConstAssertion]
+        );
+        assert_eq!(
+            evaluate("const { assert(true, false) };"),
+            vec![AnalysisError::ConstEvaluation(
+                "`assert` takes a single condition".to_string()
+            )]
+        );
+        assert_eq!(
+            evaluate("const { 1 / 0 };"),
+            vec![AnalysisError::ConstEvaluation(
+                "division by zero".to_string()
+            )]
+        );
+        assert_eq!(
+            evaluate("const { 2 ** 4000000000 };"),
+            vec![AnalysisError::ConstEvaluation(
+                "the power with the exponent 4000000000 is too large".to_string()
+            )]
+        );
+        assert_eq!(evaluate("const { assert(1 ** 4000000000 == 1) };"), vec![]);
+        assert_eq!(
+            evaluate("const { loop {} };"),
+            vec![AnalysisError::ConstEvaluation(format!(
+                "did not finish within {} steps",
+                MAX_STEPS
+            ))]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_blocks_are_skipped() {
+        for code in [
+            "const { assert(docs.map(|doc| doc.name).are_unique()) };",
+            "const { let f = |x| x; assert(f(1) == 2) };",
+            "const { assert([1, 2].len() == 3) };",
+            "const { assert(imported(1)) };",
+            "let mut a = 1;\nconst { assert(a == 2) };",
+        ] {
+            assert_eq!(evaluate(code), vec![], "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_top_level_lets() {
+        assert_eq!(evaluate("let a = 5;\nconst { assert(a == 5) };"), vec![]);
+        assert_eq!(
+            evaluate("let a = 2;\nlet b = a * 3;\nconst { assert(b == 5) };"),
+            vec![AnalysisError::ConstAssertion]
+        );
+        // the value of `a` can not be evaluated, so it is not the one of the first `a`
+        assert_eq!(
+            evaluate("let a = 1;\nlet a = f.g;\nconst { assert(a == 2) };"),
+            vec![]
+        );
+        // a block only sees the `let`s in front of it
+        assert_eq!(
+            evaluate("let a = 1;\nconst { assert(a == 1) };\nlet a = 2;"),
+            vec![]
+        );
+        assert_eq!(
+            evaluate("let a = 1;\nfn f() {\n    let a = 2;\n}\nconst { assert(a == 2) };"),
+            vec![AnalysisError::ConstAssertion]
+        );
+    }
+
+    #[test]
+    fn test_cte_functions() {
+        let factorial = "#[cte] fn factorial(n: Int) -> Int {
+            if n == 0 { return 1; }
+            n * factorial(n - 1)
+        }";
+        assert_eq!(
+            evaluate(&format!(
+                "{} const {{ assert(factorial(5) == 120) }};",
+                factorial
+            )),
+            vec![]
+        );
+        assert_eq!(
+            evaluate(&format!(
+                "{} const {{ assert(factorial(3) == 5) }};",
+                factorial
+            )),
+            vec![AnalysisError::ConstAssertion]
+        );
+        assert_eq!(
+            evaluate("#[cte] fn area(w, h) { w * h } const { assert(area(3, 2) == 6) };"),
+            vec![]
+        );
+        assert_eq!(
+            evaluate("fn f() { 1 } const { f() };"),
+            vec![AnalysisError::UnknownConst("f".to_string())]
+        );
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+
+use fst::*;
+
+use crate::{
+    visit::{walk_expression, walk_file, walk_statement, Visitor},
+    AnalysisError, LocatedAnalysisError,
+};
+
+/// Reports the `match` expressions whose arms do not cover every option of the matched enum.
+///
+/// The enum of a pattern is the enum declared in the file with the name in front of the option,
+/// `Color` in `Color.Red`, or the only enum with an option named `Red` for a pattern without a
+/// path. Arms with a guard never count as covering a value, and
+/// matches over enums that are not declared in the file are not checked.
+pub fn check_exhaustiveness(file: &File) -> Vec<LocatedAnalysisError> {
+    let mut declarations = EnumDeclarations::default();
+    walk_file(&mut declarations, file);
+    let mut checker = Matches {
+        enums: declarations.enums,
+        errors: Vec::new(),
+    };
+    walk_file(&mut checker, file);
+    checker.errors
+}
+
+/// Collects the options of the enums declared in a file by the name of the enum
+#[derive(Default)]
+struct EnumDeclarations {
+    enums: HashMap<String, Vec<String>>,
+}
+
+impl Visitor<'_> for EnumDeclarations {
+    fn visit_statement(&mut self, statement: &Located<Statement>) {
+        if let Statement::Enum { name, options, .. } = &statement.value {
+            let options = options
+                .values()
+                .map(|option| option.value.0.clone())
+                .collect();
+            self.enums.insert(name.clone(), options);
+        }
+        walk_statement(self, statement);
+    }
+}
+
+enum Coverage {
+    Complete,
+    // the values that are not matched, `Color.Red` or `_` when no option can be named
+    Missing(Vec<String>),
+    // the patterns match an enum that is not declared in the file
+    Unknown,
+}
+
+/// What a pattern requires the value to start with
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Constructor<'a> {
+    /// An option of an enum declared in the file, by the name of the enum and of the option
+    Option(&'a str, &'a str),
+    Bool(bool),
+    /// A literal or range of a type with more values than can be listed
+    Other,
+    /// An option of an enum that is not declared in the file
+    Unresolved,
+}
+
+/// The patterns of the arms still to be checked, one row per arm and one column per value
+type Rows<'p> = Vec<Vec<&'p Pattern>>;
+
+static WILDCARD: Pattern = Pattern::Wildcard;
+
+/// The rows with the alternatives in their first column replaced by a row for each alternative
+fn expand(rows: Rows) -> Rows {
+    let mut expanded = Vec::new();
+    for row in rows {
+        match row[0] {
+            Pattern::Alternatives(alternatives) => {
+                let rows = alternatives
+                    .iter()
+                    .map(|alternative| {
+                        let mut row = row.clone();
+                        row[0] = &alternative.value.value;
+                        row
+                    })
+                    .collect();
+                expanded.extend(expand(rows));
+            }
+            _ => expanded.push(row),
+        }
+    }
+    expanded
+}
+
+/// The patterns of the values of a variant, struct fields are always destructured so they match
+/// every value
+fn values(pattern: &Pattern) -> Vec<&Pattern> {
+    match pattern {
+        Pattern::Variant {
+            values: VariantPattern::Tuple { values, .. },
+            ..
+        } => values.values().map(|value| &value.value).collect(),
+        _ => Vec::new(),
+    }
+}
+
+struct Matches {
+    enums: HashMap<String, Vec<String>>,
+    errors: Vec<LocatedAnalysisError>,
+}
+
+impl Matches {
+    /// The enum and option named by a variant pattern
+    fn resolve<'a>(&'a self, path: &'a [Spaced<String>]) -> Option<(&'a str, &'a str)> {
+        let option = &path.last()?.value;
+        let has_option = |options: &Vec<String>| options.contains(option);
+        match path {
+            [.., enum_name, _] => {
+                let options = self.enums.get(&enum_name.value)?;
+                has_option(options).then_some((enum_name.value.as_str(), option.as_str()))
+            }
+            _ => {
+                let mut candidates = self.enums.iter().filter(|(_, options)| has_option(options));
+                match (candidates.next(), candidates.next()) {
+                    (Some((enum_name, _)), None) => Some((enum_name.as_str(), option.as_str())),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// The constructor of a pattern, `None` for the patterns that match every value
+    fn constructor<'a>(&'a self, pattern: &'a Pattern) -> Option<Constructor<'a>> {
+        match pattern {
+            Pattern::Wildcard | Pattern::Binding { .. } => None,
+            Pattern::Literal(Literal::Boolean(value)) => Some(Constructor::Bool(*value)),
+            Pattern::Literal(_) | Pattern::Range { .. } => Some(Constructor::Other),
+            Pattern::Variant { path, .. } => Some(match self.resolve(path) {
+                Some((enum_name, option)) => Constructor::Option(enum_name, option),
+                None => Constructor::Unresolved,
+            }),
+            Pattern::Alternatives(_) => unreachable!("alternatives are expanded first"),
+        }
+    }
+
+    /// The distinct constructors of the first column
+    fn constructors<'a>(&'a self, rows: &'a Rows) -> Vec<Constructor<'a>> {
+        let mut constructors = Vec::new();
+        for constructor in rows.iter().filter_map(|row| self.constructor(row[0])) {
+            if !constructors.contains(&constructor) {
+                constructors.push(constructor);
+            }
+        }
+        constructors
+    }
+
+    /// Whether every value starts with one of the constructors
+    fn is_complete(&self, constructors: &[Constructor]) -> bool {
+        match constructors.first() {
+            Some(Constructor::Option(enum_name, _)) => {
+                self.enums[*enum_name].iter().all(|option| {
+                    constructors.contains(&Constructor::Option(enum_name, option.as_str()))
+                })
+            }
+            Some(Constructor::Bool(_)) => {
+                constructors.contains(&Constructor::Bool(true))
+                    && constructors.contains(&Constructor::Bool(false))
+            }
+            _ => false,
+        }
+    }
+
+    /// The number of values of a constructor, as written in the patterns of the first column
+    fn arity(&self, rows: &Rows, constructor: Constructor) -> usize {
+        rows.iter()
+            .filter(|row| self.constructor(row[0]) == Some(constructor))
+            .map(|row| values(row[0]).len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The rows that match values starting with the constructor, with the first column replaced
+    /// by the patterns of the values of the constructor
+    fn specialize<'p>(&self, rows: &Rows<'p>, constructor: Constructor, arity: usize) -> Rows<'p> {
+        rows.iter()
+            .filter_map(|row| {
+                let mut specialized = match self.constructor(row[0]) {
+                    None => Vec::new(),
+                    Some(head) if head == constructor => values(row[0]),
+                    Some(_) => return None,
+                };
+                specialized.resize(arity, &WILDCARD);
+                specialized.extend_from_slice(&row[1..]);
+                Some(specialized)
+            })
+            .collect()
+    }
+
+    /// Whether the rows match every sequence of values, `None` if that depends on an enum that is
+    /// not declared in the file.
+    ///
+    /// When the first column lists every constructor of its type, the rows cover all values if
+    /// they do so for the values starting with each constructor. Otherwise some constructor is
+    /// only matched by the rows starting with a wildcard, which have to cover the other columns.
+    fn covers(&self, rows: Rows) -> Option<bool> {
+        let Some(width) = rows.first().map(Vec::len) else {
+            return Some(false);
+        };
+        if width == 0 {
+            return Some(true);
+        }
+        let rows = expand(rows);
+        let constructors = self.constructors(&rows);
+        if self.is_complete(&constructors) {
+            let mut covered = Some(true);
+            for &constructor in &constructors {
+                let arity = self.arity(&rows, constructor);
+                match self.covers(self.specialize(&rows, constructor, arity)) {
+                    Some(false) => return Some(false),
+                    None => covered = None,
+                    Some(true) => {}
+                }
+            }
+            return covered;
+        }
+        let wildcards = rows
+            .iter()
+            .filter(|row| self.constructor(row[0]).is_none())
+            .map(|row| row[1..].to_vec())
+            .collect();
+        match self.covers(wildcards) {
+            // the options of an undeclared enum could all be listed
+            Some(false) if constructors.contains(&Constructor::Unresolved) => None,
+            covered => covered,
+        }
+    }
+
+    fn coverage(&self, patterns: &[&Pattern]) -> Coverage {
+        let rows = expand(patterns.iter().map(|pattern| vec![*pattern]).collect());
+        let enum_name = self
+            .constructors(&rows)
+            .into_iter()
+            .find_map(|constructor| match constructor {
+                Constructor::Option(enum_name, _) => Some(enum_name),
+                _ => None,
+            });
+        let Some(enum_name) = enum_name else {
+            return match self.covers(rows) {
+                Some(true) => Coverage::Complete,
+                Some(false) => Coverage::Missing(vec!["_".to_string()]),
+                None => Coverage::Unknown,
+            };
+        };
+
+        let mut missing = Vec::new();
+        let mut unknown = false;
+        for option in &self.enums[enum_name] {
+            let constructor = Constructor::Option(enum_name, option);
+            let arity = self.arity(&rows, constructor);
+            match self.covers(self.specialize(&rows, constructor, arity)) {
+                Some(false) => missing.push(format!("{}.{}", enum_name, option)),
+                None => unknown = true,
+                Some(true) => {}
+            }
+        }
+        if !missing.is_empty() {
+            Coverage::Missing(missing)
+        } else if unknown {
+            Coverage::Unknown
+        } else {
+            Coverage::Complete
+        }
+    }
+}
+
+impl Visitor<'_> for Matches {
+    fn visit_expression(&mut self, expression: &Located<Expression>) {
+        if let Expression::Match { arms, .. } = &expression.value {
+            let patterns: Vec<_> = arms
+                .values()
+                .filter(|arm| arm.value.guard.is_none())
+                .map(|arm| &arm.value.pattern.value)
+                .collect();
+            if let Coverage::Missing(missing) = self.coverage(&patterns) {
+                self.errors.push(Located::new(
+                    AnalysisError::NonExhaustiveMatch(missing),
+                    expression.source_span,
+                ));
+            }
+        }
+        walk_expression(self, expression);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::errors;
+
+    const ENUMS: &str = "enum Color { Red, Green, Blue }\nenum Shape { Circle(Int), Square { side: Int } }\nenum Pair { Both(Color, Color) }\n";
+
+    fn missing(code: &str) -> Vec<Vec<String>> {
+        let code = format!("{}{}", ENUMS, code);
+        errors(&code, check_exhaustiveness, |error| match error {
+            AnalysisError::NonExhaustiveMatch(missing) => Some(missing.clone()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_exhaustive_matches() {
+        assert!(missing("match c { Color.Red => 1, Color.Green | Color.Blue => 2 }").is_empty());
+        assert!(missing("match c { Color.Red => 1, _ => 2 }").is_empty());
+        assert!(missing("match s { Circle(r) => r, Square { side } => side }").is_empty());
+        assert!(missing("match b { true => 1, false => 0 }").is_empty());
+        assert!(missing("match p { Both(Color.Red, _) => 1, Both(_, c) => 2 }").is_empty());
+        // enums that are not declared in the file are not checked
+        assert!(missing("match o { Some(a) => a }").is_empty());
+    }
+
+    #[test]
+    fn test_missing_options() {
+        assert_eq!(
+            missing("match c { Color.Red => 1, Color.Green => 2 }"),
+            vec![vec!["Color.Blue"]]
+        );
+        assert_eq!(
+            missing("match s { Circle(1) => 1, Circle(a) if a > 1 => a }"),
+            vec![vec!["Shape.Circle", "Shape.Square"]]
+        );
+        assert_eq!(
+            missing("match p { Both(Color.Red, Color.Red) => 1 }"),
+            vec![vec!["Pair.Both"]]
+        );
+        assert_eq!(missing("let a = match n { 1..10 => 1 };"), vec![vec!["_"]]);
+    }
+
+    #[test]
+    fn test_nested_values() {
+        assert!(missing(
+            "match p {
+                Both(Color.Red, _) => 1,
+                Both(Color.Green, _) => 2,
+                Both(Color.Blue, Color.Red) => 3,
+                Both(Color.Blue, Color.Green) => 4,
+                Both(Color.Blue, Color.Blue) => 5,
+            }"
+        )
+        .is_empty());
+        assert!(missing(
+            "match p { Both(Color.Red | Color.Green, _) => 1, Both(_, Color.Blue) | Both(Color.Blue, _) => 2 }"
+        )
+        .is_empty());
+        assert_eq!(
+            missing(
+                "match p {
+                    Both(Color.Red, _) => 1,
+                    Both(Color.Blue, Color.Red) => 3,
+                    Both(Color.Blue, Color.Blue) => 5,
+                    Both(_, Color.Green) => 5,
+                }"
+            ),
+            vec![vec!["Pair.Both"]]
+        );
+        // the options of an enum that is not declared could all be listed
+        assert!(missing("match p { Both(Some(a), _) => 1, Both(_, Color.Red) => 2 }").is_empty());
+    }
+
+    #[test]
+    fn test_nested_matches() {
+        assert_eq!(
+            missing("match c { _ => match c { Color.Red => 1 } }"),
+            vec![vec!["Color.Green", "Color.Blue"]]
+        );
+    }
+}
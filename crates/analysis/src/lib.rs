@@ -0,0 +1,65 @@
+//! Passes that check a parsed file beyond its syntax.
+
+mod compile_time;
+mod exhaustiveness;
+mod resolve;
+#[cfg(test)]
+mod test_utils;
+mod type_aliases;
+mod unsafety;
+pub mod visit;
+
+use fst::Located;
+use thiserror::Error;
+
+pub use compile_time::evaluate_const_blocks;
+pub use exhaustiveness::check_exhaustiveness;
+pub use resolve::{check_names, import_names, resolve_names, Definition, Resolution};
+pub use type_aliases::{check_type_aliases, TypeAliases};
+pub use unsafety::check_unsafety;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum AnalysisError {
+    #[error("call to unsafe function `{0}` requires an unsafe block or function")]
+    UnsafeCall(String),
+    #[error("compile time assertion failed")]
+    ConstAssertion,
+    #[error("`{0}` is not known at compile time")]
+    UnknownConst(String),
+    #[error("compile time evaluation failed: {0}")]
+    ConstEvaluation(String),
+    #[error("match does not cover {}", .0.join(", "))]
+    NonExhaustiveMatch(Vec<String>),
+    #[error("type alias `{}` refers to itself through {}", .0[0], .0.join(" -> "))]
+    TypeAliasCycle(Vec<String>),
+    #[error("unresolved name `{0}`")]
+    UnresolvedName(String),
+}
+
+pub type LocatedAnalysisError = Located<AnalysisError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{location, parse};
+
+    #[test]
+    fn test_error_locations() {
+        let file = parse("unsafe fn f() {}\nlet a = 1 + f(2);");
+        assert_eq!(location(&check_unsafety(&file)[0]), ((1, 12), (1, 16)));
+
+        let file = parse("let a = 1;\nconst { assert(a == 2) };");
+        let resolution = resolve_names(&file, &[], |path| import_names(&path.value));
+        let errors = evaluate_const_blocks(&file, &resolution);
+        assert_eq!(errors[0].value, AnalysisError::ConstAssertion);
+        assert_eq!(location(&errors[0]), ((1, 0), (1, 24)));
+
+        let file = parse("let a = 1;\ntype A = A;");
+        let errors = check_type_aliases(&file);
+        assert_eq!(location(&errors[0]).0, (1, 0));
+        assert_eq!(
+            errors[0].value.to_string(),
+            "type alias `A` refers to itself through A -> A"
+        );
+    }
+}
@@ -0,0 +1,545 @@
+use std::collections::HashMap;
+
+use fst::*;
+
+use crate::{
+    visit::{walk_expression, walk_statement, Visitor},
+    AnalysisError, LocatedAnalysisError,
+};
+
+/// The names that are defined everywhere, the builtin types, `_` for an inferred type and
+/// `assert`, the number types of the literal suffixes are builtin as well. Everything else a
+/// module uses without an import comes from the std prelude.
+const BUILTINS: &[&str] = &["_", "int", "float", "string", "bool", "any", "assert"];
+
+/// What a name refers to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Definition {
+    /// A `let`, a parameter, a `for` destructure, a match binding or `self`, at the name or
+    /// destructure property that binds it. `self` is bound by the closure of its method.
+    Local(SourceSpan),
+    /// A function, struct, enum, trait, type alias or module, at its statement. `Self` refers to
+    /// the `impl` or trait it is used in.
+    Item(SourceSpan),
+    /// A generic parameter of the item at the span
+    Generic(SourceSpan),
+    /// A name brought into scope by the import statement at the span
+    Import(SourceSpan),
+    /// An export of the std prelude
+    Prelude,
+    Builtin,
+}
+
+/// The definitions of the variables of a file by the location of the variable
+#[derive(Debug, Default)]
+pub struct Resolution {
+    pub definitions: HashMap<SourceSpan, Definition>,
+    pub errors: Vec<LocatedAnalysisError>,
+}
+
+impl Resolution {
+    pub fn definition(&self, variable: &Located<Expression>) -> Option<Definition> {
+        self.definitions.get(&variable.source_span).copied()
+    }
+}
+
+/// Reports the names that are not defined, the names of an import are taken from the import
+/// itself, so names from `import a.*` are unknown and nothing in its scope is reported. The file
+/// is checked without the std prelude.
+pub fn check_names(file: &File) -> Vec<LocatedAnalysisError> {
+    resolve_names(file, &[], |path| import_names(&path.value)).errors
+}
+
+/// The names `import a.b`, `import a.{b, c}` or `import a.*` bring into scope without knowing
+/// the imported modules, `None` for a glob
+pub fn import_names(path: &ImportPath) -> Option<Vec<String>> {
+    match &path.segments.last()?.value {
+        ImportSegment::Name(name) => Some(vec![name.clone()]),
+        ImportSegment::List(names) => Some(names.values().cloned().collect()),
+        ImportSegment::Glob => None,
+    }
+}
+
+/// Resolves every variable of a file to its definition.
+///
+/// Scopes are lexical: blocks, closures, `for` loops, match arms and `mod` blocks each start a
+/// scope. Items and imports can be used anywhere in their scope, a `let` only after it, and a
+/// later `let` with the same name shadows the earlier one. `prelude` are the exports of the std
+/// prelude, which the file can shadow. `imported` gives the names an import without an extract
+/// brings into scope, `None` if they are not known.
+pub fn resolve_names(
+    file: &File,
+    prelude: &[String],
+    imported: impl Fn(&Located<ImportPath>) -> Option<Vec<String>>,
+) -> Resolution {
+    let builtins = Scope {
+        names: BUILTINS
+            .iter()
+            .copied()
+            .chain(NumberSuffix::ALL.iter().map(NumberSuffix::name))
+            .map(|name| (name.to_string(), Definition::Builtin))
+            .collect(),
+        open: false,
+    };
+    let prelude = Scope {
+        names: prelude
+            .iter()
+            .map(|name| (name.clone(), Definition::Prelude))
+            .collect(),
+        open: false,
+    };
+    let mut resolver = Resolver {
+        scopes: vec![builtins, prelude],
+        imported,
+        resolution: Resolution::default(),
+    };
+    resolver.statements(&file.statements);
+    resolver.resolution
+}
+
+#[derive(Default)]
+struct Scope {
+    names: HashMap<String, Definition>,
+    // an import with unknown names is in scope, so any name could be defined
+    open: bool,
+}
+
+struct Resolver<F> {
+    scopes: Vec<Scope>,
+    imported: F,
+    resolution: Resolution,
+}
+
+impl<F: Fn(&Located<ImportPath>) -> Option<Vec<String>>> Resolver<F> {
+    fn scoped(&mut self, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(Scope::default());
+        f(self);
+        self.scopes.pop();
+    }
+
+    fn bind(&mut self, name: &str, definition: Definition) {
+        let scope = self.scopes.last_mut().unwrap();
+        scope.names.insert(name.to_string(), definition);
+    }
+
+    fn lookup(&mut self, name: &str, source_span: SourceSpan) {
+        let mut open = false;
+        for scope in self.scopes.iter().rev() {
+            if let Some(definition) = scope.names.get(name) {
+                self.resolution.definitions.insert(source_span, *definition);
+                return;
+            }
+            open |= scope.open;
+        }
+        if !open {
+            self.resolution.errors.push(Located::new(
+                AnalysisError::UnresolvedName(name.to_string()),
+                source_span,
+            ));
+        }
+    }
+
+    /// The statements of a file, block or module in the current scope, after their items and
+    /// imports are declared
+    fn statements(&mut self, statements: &[(Located<Statement>, Whitespace0)]) {
+        for (statement, _) in statements {
+            self.declare(statement);
+        }
+        for (statement, _) in statements {
+            self.visit_statement(statement);
+        }
+    }
+
+    fn declare(&mut self, statement: &Located<Statement>) {
+        let item = Definition::Item(statement.source_span);
+        match &statement.value {
+            Statement::Function { name, .. }
+            | Statement::Struct { name, .. }
+            | Statement::Enum { name, .. }
+            | Statement::TypeAlias { name, .. }
+            | Statement::Trait { name, .. }
+            | Statement::Module { name, .. } => self.bind(name, item),
+            Statement::Import { path, extract, .. } => {
+                let names = match extract {
+                    Some((_, _, extract)) => {
+                        let mut names = Vec::new();
+                        extract_names(extract, &mut names);
+                        Some(
+                            names
+                                .into_iter()
+                                .map(|(name, _)| name.to_string())
+                                .collect(),
+                        )
+                    }
+                    None => (self.imported)(path),
+                };
+                match names {
+                    Some(names) => {
+                        for name in names {
+                            self.bind(&name, Definition::Import(statement.source_span));
+                        }
+                    }
+                    None => self.scopes.last_mut().unwrap().open = true,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn generics(&mut self, generics: &Option<GenericParameters>, item: SourceSpan) {
+        for param in generics
+            .iter()
+            .flat_map(|generics| generics.params.values())
+        {
+            self.bind(&param.name, Definition::Generic(item));
+        }
+    }
+
+    /// The parameters are bound in a scope of their own, their types are resolved outside of it
+    fn closure(&mut self, closure: &Closure, source_span: SourceSpan) {
+        let signature = &closure.closure_signature;
+        for param in signature.params.values() {
+            if let Some(value_type) = &param.value_type {
+                self.visit_expression(&value_type.value_type);
+            }
+        }
+        if let Some(return_type) = &signature.return_type {
+            self.visit_expression(&return_type.value_type);
+        }
+        self.scoped(|resolver| {
+            if signature.receiver.is_some() {
+                resolver.bind("self", Definition::Local(source_span));
+            }
+            for param in signature.params.values() {
+                resolver.creation(&param.creation);
+            }
+            resolver.visit_expression(&closure.body);
+        });
+    }
+
+    fn creation(&mut self, creation: &Located<VariableCreation>) {
+        match &creation.value {
+            VariableCreation::Identifier { name, .. } => {
+                self.bind(name, Definition::Local(creation.source_span))
+            }
+            VariableCreation::Destructure { destructure } => self.destructure(destructure),
+        }
+    }
+
+    fn destructure(&mut self, destructure: &MutableDestructure) {
+        let mut names = Vec::new();
+        for property in destructure.values() {
+            property_names(property, &mut names);
+        }
+        for (name, source_span) in names {
+            self.bind(name, Definition::Local(source_span));
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Located<Pattern>) {
+        match &pattern.value {
+            Pattern::Binding { name, .. } => {
+                self.bind(name, Definition::Local(pattern.source_span))
+            }
+            Pattern::Variant { values, .. } => match values {
+                VariantPattern::Tuple { values, .. } => {
+                    for value in values.values() {
+                        self.pattern(value);
+                    }
+                }
+                VariantPattern::Struct { fields, .. } => self.destructure(fields),
+                VariantPattern::Unit => {}
+            },
+            Pattern::Alternatives(alternatives) => {
+                for alternative in alternatives {
+                    self.pattern(&alternative.value);
+                }
+            }
+            Pattern::Wildcard | Pattern::Literal(_) | Pattern::Range { .. } => {}
+        }
+    }
+
+    fn block(&mut self, block: &Block) {
+        self.scoped(|resolver| resolver.statements(&block.statements));
+    }
+}
+
+/// The names bound by an extract, with the location of the property that binds them
+fn extract_names<'a>(extract: &'a MutableExtract, names: &mut Vec<(&'a str, SourceSpan)>) {
+    match extract {
+        MutableExtract::Destructured(destructure) => {
+            for property in destructure.values() {
+                property_names(property, names);
+            }
+        }
+        MutableExtract::DirectProperty(property) => property_names(property, names),
+    }
+}
+
+fn property_names<'a>(
+    property: &'a Located<MutableDestructureProperty>,
+    names: &mut Vec<(&'a str, SourceSpan)>,
+) {
+    match &property.value {
+        MutableDestructureProperty::AliasedSubProperties { alias, .. }
+        | MutableDestructureProperty::Property {
+            alias: Some(alias), ..
+        } => names.push((&alias.alias.alias, property.source_span)),
+        MutableDestructureProperty::Property { property_name, .. } => {
+            names.push((property_name, property.source_span))
+        }
+        MutableDestructureProperty::UnaliasedSubProperties { extract, .. } => {
+            extract_names(&extract.extract, names)
+        }
+        // `mut a.b` binds `b`
+        MutableDestructureProperty::MutablePropertyChain { property_chain } => {
+            if let Some(name) = property_chain.last() {
+                names.push((&name.value, property.source_span));
+            }
+        }
+    }
+}
+
+impl<'a, F: Fn(&Located<ImportPath>) -> Option<Vec<String>>> Visitor<'a> for Resolver<F> {
+    fn visit_statement(&mut self, statement: &'a Located<Statement>) {
+        let item = statement.source_span;
+        match &statement.value {
+            Statement::Function {
+                generics, closure, ..
+            } => self.scoped(|resolver| {
+                resolver.generics(generics, item);
+                resolver.closure(&closure.value, closure.source_span);
+            }),
+            Statement::Struct { generics, .. }
+            | Statement::Enum { generics, .. }
+            | Statement::TypeAlias { generics, .. } => self.scoped(|resolver| {
+                resolver.generics(generics, item);
+                walk_statement(resolver, statement);
+            }),
+            Statement::Trait { generics, .. } => self.scoped(|resolver| {
+                resolver.bind("Self", Definition::Item(item));
+                resolver.generics(generics, item);
+                walk_statement(resolver, statement);
+            }),
+            Statement::Impl {
+                generics,
+                implemented,
+                target,
+                statements,
+                ..
+            } => self.scoped(|resolver| {
+                resolver.bind("Self", Definition::Item(item));
+                resolver.generics(generics, item);
+                if let Some((implemented, _, _)) = implemented {
+                    resolver.visit_expression(implemented);
+                }
+                resolver.visit_expression(target);
+                resolver.block(statements);
+            }),
+            Statement::Module { statements, .. } => self.block(statements),
+            _ => walk_statement(self, statement),
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &'a Located<Expression>) {
+        match &expression.value {
+            Expression::Variable { identifier } => self.lookup(identifier, expression.source_span),
+            Expression::Declaration { creation, .. } => {
+                // the value is resolved before the name is bound, `let x = x + 1` uses the
+                // previous `x`
+                walk_expression(self, expression);
+                self.creation(creation);
+            }
+            Expression::Closure { closure, .. } => self.closure(closure, expression.source_span),
+            Expression::StructLiteral { fields, .. } => {
+                for field in fields.values() {
+                    if let StructLiteralField::Shorthand(name) = field {
+                        self.lookup(&name.value, name.source_span);
+                    }
+                }
+                walk_expression(self, expression);
+            }
+            Expression::Block { environment, block } => {
+                if let Some(environment) = environment {
+                    self.visit_expression(environment);
+                }
+                self.block(block);
+            }
+            Expression::Unsafe { block, .. } | Expression::Const { block, .. } => self.block(block),
+            Expression::If {
+                if_block,
+                else_if_blocks,
+                else_block,
+            } => {
+                let else_ifs = else_if_blocks.iter().map(|else_if| &else_if.value);
+                for if_block in std::iter::once(if_block).chain(else_ifs) {
+                    self.visit_expression(&if_block.condition);
+                    self.block(&if_block.block);
+                }
+                if let Some(else_block) = else_block {
+                    self.block(&else_block.value);
+                }
+            }
+            Expression::For {
+                destructure,
+                iterator,
+                body,
+                else_block,
+                ..
+            } => {
+                self.visit_expression(iterator);
+                self.scoped(|resolver| {
+                    resolver.destructure(destructure);
+                    resolver.visit_expression(body);
+                });
+                if let Some(else_block) = else_block {
+                    self.visit_expression(&else_block.value);
+                }
+            }
+            Expression::Match {
+                scrutinee, arms, ..
+            } => {
+                self.visit_expression(scrutinee);
+                for arm in arms.values() {
+                    self.scoped(|resolver| {
+                        resolver.pattern(&arm.value.pattern);
+                        if let Some(guard) = &arm.value.guard {
+                            resolver.visit_expression(&guard.condition);
+                        }
+                        resolver.visit_expression(&arm.value.body);
+                    });
+                }
+            }
+            _ => walk_expression(self, expression),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{errors, location, parse};
+
+    fn unresolved(code: &str) -> Vec<String> {
+        errors(code, check_names, |error| match error {
+            AnalysisError::UnresolvedName(name) => Some(name.clone()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_lets() {
+        assert!(unresolved("let a = 1;\nlet b = a + 1;").is_empty());
+        assert_eq!(unresolved("let b = a;\nlet a = 1;"), vec!["a"]);
+        assert_eq!(unresolved("let a = a;"), vec!["a"]);
+        assert!(unresolved("let z = 1;\nlet z: int | string = z;").is_empty());
+        assert_eq!(unresolved("{ let a = 1; };\na"), vec!["a"]);
+    }
+
+    #[test]
+    fn test_items() {
+        // items can be used before they are declared, also from inside of functions
+        assert!(unresolved("fn f() { g() }\nfn g() { f() }").is_empty());
+        assert!(unresolved("let p = Point { x: 1 };\nstruct Point { x: int }").is_empty());
+        assert_eq!(
+            unresolved("struct List<T> { value: T, next: U }"),
+            vec!["U"]
+        );
+        assert!(unresolved("mod a { fn f() {} }\nlet x = a;").is_empty());
+        assert_eq!(unresolved("mod a { fn f() {} }\nlet x = f;"), vec!["f"]);
+    }
+
+    #[test]
+    fn test_closures() {
+        assert!(unresolved("let f = |x, y| x + y;").is_empty());
+        assert!(unresolved("let f = |x: int| -> int { x };").is_empty());
+        assert_eq!(unresolved("let f = |x| y;"), vec!["y"]);
+        assert_eq!(unresolved("fn f(a) { a }\nlet b = a;"), vec!["a"]);
+        assert!(
+            unresolved("struct P { x: int }\nimpl P { fn get(self) -> Self { self } }").is_empty()
+        );
+        assert_eq!(unresolved("fn f() { self }"), vec!["self"]);
+    }
+
+    #[test]
+    fn test_destructures() {
+        assert!(unresolved("let items = [];\nfor { a, b as c } in items { a + c }").is_empty());
+        assert_eq!(
+            unresolved("let items = [];\nfor { a } in items { b }"),
+            vec!["b"]
+        );
+        assert_eq!(
+            unresolved("let { a.{ b, mut c } } = d;\nlet d = 1;"),
+            vec!["d"]
+        );
+        assert!(unresolved("let d = 1;\nlet { a.{ b, mut c } } = d;\nb + c").is_empty());
+        assert_eq!(
+            unresolved("let o = 1;\nmatch o { Some(a) if a > 0 => a, Point { x } => x, _ => a }"),
+            vec!["a"]
+        );
+    }
+
+    #[test]
+    fn test_imports() {
+        assert!(unresolved("import std.env.console;\nconsole.println(1)").is_empty());
+        assert!(unresolved("import a.{b, c};\nb(c)").is_empty());
+        assert!(unresolved("import ./* as docs;\ndocs").is_empty());
+        assert_eq!(unresolved("import a.b as { c };\nb"), vec!["b"]);
+        // any name could come from a glob import
+        assert!(unresolved("import a.*;\nb").is_empty());
+        assert_eq!(unresolved("mod m { import a.*; }\nb"), vec!["b"]);
+    }
+
+    #[test]
+    fn test_prelude() {
+        let code =
+            "const { assert(Some(1) != None) };\nlet r: Result<Option<Int>, String> = Ok(None);";
+        assert_eq!(
+            unresolved(code),
+            vec!["Some", "None", "Result", "Option", "Int", "String", "Ok", "None"]
+        );
+        let prelude: Vec<String> = ["Some", "None", "Result", "Option", "Int", "String", "Ok"]
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        let resolution = resolve_names(&parse(code), &prelude, |_| None);
+        assert!(resolution.errors.is_empty());
+        // the file can shadow the prelude
+        let resolution = resolve_names(&parse("struct Some {}\nSome"), &prelude, |_| None);
+        assert!(resolution
+            .definitions
+            .values()
+            .all(|definition| matches!(definition, Definition::Item(_))));
+    }
+
+    #[test]
+    fn test_shorthand_fields() {
+        let code = "let x = 1;\nlet p = P { x, zz, yy };\nstruct P {}";
+        let errors = check_names(&parse(code));
+        let locations: Vec<_> = errors.iter().map(location).collect();
+        assert_eq!(locations, vec![((1, 15), (1, 17)), ((1, 19), (1, 21))]);
+        let resolution = resolve_names(&parse(code), &[], |_| None);
+        let mut starts: Vec<_> = resolution
+            .definitions
+            .keys()
+            .map(|span| (span.start.line, span.start.column))
+            .collect();
+        starts.sort();
+        // `P` and the `x` field, not the whole literal
+        assert_eq!(starts, vec![(1, 8), (1, 12)]);
+    }
+
+    #[test]
+    fn test_definitions() {
+        let file = parse("fn f(a) { a }\nlet b = f;\nlet c: u8 = int;");
+        let resolution = resolve_names(&file, &[], |_| None);
+        let mut definitions: Vec<_> = resolution.definitions.into_iter().collect();
+        definitions.sort_by_key(|(span, _)| span.start);
+        let definitions: Vec<_> = definitions.into_iter().map(|(_, d)| d).collect();
+        let function = file.statements[0].0.source_span;
+        assert!(matches!(definitions[0], Definition::Local(span) if span.start.line == 0));
+        assert_eq!(definitions[1], Definition::Item(function));
+        assert_eq!(definitions[2], Definition::Builtin);
+        assert_eq!(definitions[3], Definition::Builtin);
+    }
+}
@@ -0,0 +1,35 @@
+//! Helpers shared by the tests of the passes
+
+use fst::File;
+use parser::simple_parse;
+
+use crate::{AnalysisError, LocatedAnalysisError};
+
+/// Parses code the test expects to be free of syntax errors
+pub fn parse(code: &str) -> File {
+    simple_parse(code).unwrap_or_else(|error| panic!("{}", error))
+}
+
+/// Runs a pass on `code` and returns what `expected` extracts from each error, it returns `None`
+/// for the errors the pass should not report
+pub fn errors<T>(
+    code: &str,
+    pass: impl Fn(&File) -> Vec<LocatedAnalysisError>,
+    expected: impl Fn(&AnalysisError) -> Option<T>,
+) -> Vec<T> {
+    pass(&parse(code))
+        .into_iter()
+        .map(|error| {
+            expected(&error.value).unwrap_or_else(|| panic!("Unexpected error {:?}", error.value))
+        })
+        .collect()
+}
+
+/// The start and end of an error as `(line, column)`
+pub fn location(error: &LocatedAnalysisError) -> ((usize, usize), (usize, usize)) {
+    let span = error.source_span;
+    (
+        (span.start.line, span.start.column),
+        (span.end.line, span.end.column),
+    )
+}
@@ -0,0 +1,201 @@
+use std::collections::{HashMap, HashSet};
+
+use fst::*;
+
+use crate::{
+    visit::{walk_expression, walk_file, walk_statement, Visitor},
+    AnalysisError, LocatedAnalysisError,
+};
+
+/// Reports the type aliases that refer to themselves, directly or through other aliases.
+pub fn check_type_aliases(file: &File) -> Vec<LocatedAnalysisError> {
+    TypeAliases::collect(file).cycles()
+}
+
+struct TypeAlias<'a> {
+    value_type: &'a Located<Expression>,
+    // the names used in the aliased type, other than the generic parameters of the alias
+    references: Vec<&'a str>,
+    source_span: SourceSpan,
+}
+
+/// The type aliases declared in a file by name.
+///
+/// An alias is found by its name anywhere in the file, the expansion does not depend on where
+/// it is used.
+#[derive(Default)]
+pub struct TypeAliases<'a> {
+    aliases: HashMap<&'a str, TypeAlias<'a>>,
+    // the names in the order the aliases are declared, so cycles are reported deterministically
+    order: Vec<&'a str>,
+}
+
+impl<'a> TypeAliases<'a> {
+    pub fn collect(file: &'a File) -> Self {
+        let mut aliases = TypeAliases::default();
+        walk_file(&mut aliases, file);
+        aliases
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.aliases.contains_key(name)
+    }
+
+    /// The type an alias stands for, following aliases of aliases, `None` if `name` is not an
+    /// alias or is part of a cycle
+    pub fn expand(&self, name: &str) -> Option<&'a Located<Expression>> {
+        let mut visited = HashSet::new();
+        let mut name = name;
+        loop {
+            let alias = self.aliases.get(name)?;
+            if !visited.insert(name) {
+                return None;
+            }
+            match &alias.value_type.value {
+                Expression::Variable { identifier } if self.contains(identifier) => {
+                    name = identifier;
+                }
+                _ => return Some(alias.value_type),
+            }
+        }
+    }
+
+    fn cycles(&self) -> Vec<LocatedAnalysisError> {
+        let mut stack = Vec::new();
+        let mut visited = HashSet::new();
+        let mut cycles = Vec::new();
+        for name in &self.order {
+            self.find_cycles(name, &mut stack, &mut visited, &mut cycles);
+        }
+        cycles
+            .into_iter()
+            .map(|cycle| {
+                let source_span = self.aliases[cycle[0]].source_span;
+                let names = cycle.into_iter().map(str::to_string).collect();
+                Located::new(AnalysisError::TypeAliasCycle(names), source_span)
+            })
+            .collect()
+    }
+
+    /// Depth first search from `name`, a cycle is found when an alias on the stack is reached again
+    fn find_cycles(
+        &self,
+        name: &'a str,
+        stack: &mut Vec<&'a str>,
+        visited: &mut HashSet<&'a str>,
+        cycles: &mut Vec<Vec<&'a str>>,
+    ) {
+        if let Some(start) = stack.iter().position(|on_stack| *on_stack == name) {
+            let mut cycle = stack[start..].to_vec();
+            cycle.push(name);
+            cycles.push(cycle);
+            return;
+        }
+        if !visited.insert(name) {
+            return;
+        }
+        stack.push(name);
+        for reference in &self.aliases[name].references {
+            if self.contains(reference) {
+                self.find_cycles(reference, stack, visited, cycles);
+            }
+        }
+        stack.pop();
+    }
+}
+
+impl<'a> Visitor<'a> for TypeAliases<'a> {
+    fn visit_statement(&mut self, statement: &'a Located<Statement>) {
+        if let Statement::TypeAlias {
+            name,
+            generics,
+            value_type,
+            ..
+        } = &statement.value
+        {
+            let parameters: Vec<&str> = generics
+                .iter()
+                .flat_map(|generics| generics.params.values())
+                .map(|param| param.name.as_str())
+                .collect();
+            let mut names = Names::default();
+            names.visit_expression(value_type);
+            let references = names
+                .names
+                .into_iter()
+                .filter(|name| !parameters.contains(name))
+                .collect();
+            self.aliases.insert(
+                name,
+                TypeAlias {
+                    value_type,
+                    references,
+                    source_span: statement.source_span,
+                },
+            );
+            self.order.push(name);
+        }
+        walk_statement(self, statement);
+    }
+}
+
+/// Collects the names used in an expression
+#[derive(Default)]
+struct Names<'a> {
+    names: Vec<&'a str>,
+}
+
+impl<'a> Visitor<'a> for Names<'a> {
+    fn visit_expression(&mut self, expression: &'a Located<Expression>) {
+        if let Expression::Variable { identifier } = &expression.value {
+            self.names.push(identifier);
+        }
+        walk_expression(self, expression);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{errors, parse};
+
+    fn cycles(code: &str) -> Vec<Vec<String>> {
+        errors(code, check_type_aliases, |error| match error {
+            AnalysisError::TypeAliasCycle(names) => Some(names.clone()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_cycles() {
+        assert_eq!(
+            cycles("type A = B;\ntype B = A;"),
+            vec![vec!["A", "B", "A"]]
+        );
+        assert_eq!(
+            cycles("type Tree = List<Tree>;\ntype C = Tree;"),
+            vec![vec!["Tree", "Tree"]]
+        );
+        assert_eq!(
+            cycles("type A = B | C;\ntype B = A;\ntype C = D<A>;\ntype D<T> = T;"),
+            vec![vec!["A", "B", "A"], vec!["A", "C", "A"]]
+        );
+    }
+
+    #[test]
+    fn test_no_cycles() {
+        assert!(cycles("type A = B;\ntype B = Int;\ntype C = Map<A, B>;").is_empty());
+        // generic parameters are not aliases, even when an alias has the same name
+        assert!(cycles("type T = Int;\ntype List<T> = Array<T>;").is_empty());
+    }
+
+    #[test]
+    fn test_expand() {
+        let file = parse("type A = B;\ntype B = Option<Int>;\ntype C = D;\ntype D = C;");
+        let aliases = TypeAliases::collect(&file);
+        let expanded = aliases.expand("A").unwrap();
+        assert!(matches!(expanded.value, Expression::SingleOperation { .. }));
+        assert!(aliases.expand("C").is_none());
+        assert!(aliases.expand("Int").is_none());
+    }
+}
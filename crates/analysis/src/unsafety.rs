@@ -0,0 +1,165 @@
+use std::collections::HashSet;
+
+use fst::*;
+
+use crate::{
+    visit::{walk_expression, walk_file, walk_statement, Visitor},
+    AnalysisError, LocatedAnalysisError,
+};
+
+/// Reports the calls to unsafe functions that are made outside of an unsafe block or function.
+///
+/// A call is unsafe when any function declared in the file with the same name is. Which method
+/// `a.f()` calls depends on the type of `a`, so methods and functions in modules are matched by
+/// their name alone.
+pub fn check_unsafety(file: &File) -> Vec<LocatedAnalysisError> {
+    let mut declarations = UnsafeDeclarations::default();
+    walk_file(&mut declarations, file);
+    let mut checker = UnsafeCalls {
+        unsafe_functions: declarations.names,
+        depth: 0,
+        errors: Vec::new(),
+    };
+    walk_file(&mut checker, file);
+    checker.errors
+}
+
+/// Collects the names of the unsafe functions declared in a file
+#[derive(Default)]
+struct UnsafeDeclarations {
+    names: HashSet<String>,
+}
+
+impl Visitor<'_> for UnsafeDeclarations {
+    fn visit_statement(&mut self, statement: &Located<Statement>) {
+        match &statement.value {
+            Statement::Function {
+                unsafety: Some(_),
+                name,
+                ..
+            } => {
+                self.names.insert(name.clone());
+            }
+            Statement::Trait { signatures, .. } => {
+                for (signature, _, _) in signatures {
+                    match &signature.value {
+                        Signature::Function(function) if function.unsafety.is_some() => {
+                            self.names.insert(function.name.clone());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+        walk_statement(self, statement);
+    }
+}
+
+struct UnsafeCalls {
+    unsafe_functions: HashSet<String>,
+    // the number of unsafe blocks and functions around the current node
+    depth: usize,
+    errors: Vec<LocatedAnalysisError>,
+}
+
+impl UnsafeCalls {
+    fn in_unsafe(&mut self, f: impl FnOnce(&mut Self)) {
+        self.depth += 1;
+        f(self);
+        self.depth -= 1;
+    }
+}
+
+/// The name of the function called by `callee`, `f` for both `f()` and `a.f()`
+fn called_name(callee: &Expression) -> Option<&str> {
+    match callee {
+        Expression::Variable { identifier } => Some(identifier),
+        Expression::SingleOperation {
+            operation:
+                UnaryOperation::Extract {
+                    extract: ImmutableExtract::DirectProperty(property),
+                    ..
+                },
+            ..
+        } if property.value.extract.is_none() => Some(&property.value.property_name),
+        _ => None,
+    }
+}
+
+impl Visitor<'_> for UnsafeCalls {
+    fn visit_statement(&mut self, statement: &Located<Statement>) {
+        match &statement.value {
+            Statement::Function {
+                unsafety: Some(_), ..
+            } => self.in_unsafe(|checker| walk_statement(checker, statement)),
+            _ => walk_statement(self, statement),
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &Located<Expression>) {
+        match &expression.value {
+            Expression::Unsafe { .. } => {
+                self.in_unsafe(|checker| walk_expression(checker, expression));
+                return;
+            }
+            Expression::SingleOperation {
+                operation: UnaryOperation::Call { .. },
+                operand,
+                ..
+            } if self.depth == 0 => {
+                if let Some(name) = called_name(&operand.value) {
+                    if self.unsafe_functions.contains(name) {
+                        self.errors.push(Located::new(
+                            AnalysisError::UnsafeCall(name.to_string()),
+                            expression.source_span,
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+        walk_expression(self, expression);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::errors;
+
+    fn unsafe_calls(code: &str) -> Vec<String> {
+        errors(code, check_unsafety, |error| match error {
+            AnalysisError::UnsafeCall(name) => Some(name.clone()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_calls_outside_unsafe() {
+        assert_eq!(
+            unsafe_calls("unsafe fn open() {} fn main() { open(); }"),
+            vec!["open"]
+        );
+        assert_eq!(
+            unsafe_calls("mod stdio { unsafe fn open() {} } let a = stdio.open();"),
+            vec!["open"]
+        );
+        assert_eq!(
+            unsafe_calls("trait File { unsafe fn read(&self); } f.read(); f.read;"),
+            vec!["read"]
+        );
+    }
+
+    #[test]
+    fn test_calls_inside_unsafe() {
+        assert!(unsafe_calls("unsafe fn open() {} let a = unsafe { open() };").is_empty());
+        assert!(unsafe_calls("unsafe fn open() {} unsafe fn f() { open() }").is_empty());
+        assert!(unsafe_calls("unsafe fn open() {} unsafe { let f = () -> open(); }").is_empty());
+    }
+
+    #[test]
+    fn test_safe_calls() {
+        assert!(unsafe_calls("fn open() {} open(); unsafe fn close() {}").is_empty());
+    }
+}
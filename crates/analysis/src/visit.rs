@@ -0,0 +1,283 @@
+//! Traversal of the syntax tree shared by the analysis passes.
+//!
+//! A pass implements `Visitor`, overrides the nodes it is interested in and calls the matching
+//! `walk_` function to continue into the children of a node.
+
+use fst::*;
+
+pub trait Visitor<'a> {
+    fn visit_statement(&mut self, statement: &'a Located<Statement>) {
+        walk_statement(self, statement);
+    }
+
+    fn visit_expression(&mut self, expression: &'a Located<Expression>) {
+        walk_expression(self, expression);
+    }
+}
+
+pub fn walk_file<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, file: &'a File) {
+    for (statement, _) in &file.statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_block<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, block: &'a Block) {
+    for (statement, _) in &block.statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<'a, V: Visitor<'a> + ?Sized>(
+    visitor: &mut V,
+    statement: &'a Located<Statement>,
+) {
+    match &statement.value {
+        Statement::Expression { expr, .. } => visitor.visit_expression(expr),
+        Statement::Return(label_expression) | Statement::Break(label_expression) => {
+            if let LabelExpression::WithExpression { expr, .. } = label_expression {
+                visitor.visit_expression(expr);
+            }
+        }
+        Statement::Continue(_) => {}
+        Statement::Function {
+            generics, closure, ..
+        } => {
+            walk_generics(visitor, generics);
+            walk_closure(visitor, &closure.value);
+        }
+        Statement::Struct {
+            generics, fields, ..
+        } => {
+            walk_generics(visitor, generics);
+            for field in fields.values() {
+                visitor.visit_expression(&field.field.value);
+            }
+        }
+        Statement::Enum {
+            generics, options, ..
+        } => {
+            walk_generics(visitor, generics);
+            for option in options.values() {
+                match &option.value.1 {
+                    EnumValue::Tuple { values, .. } => {
+                        for value in values.values() {
+                            visitor.visit_expression(value);
+                        }
+                    }
+                    EnumValue::Struct { fields, .. } => {
+                        for field in fields.values() {
+                            visitor.visit_expression(&field.value);
+                        }
+                    }
+                    EnumValue::Unit => {}
+                }
+            }
+        }
+        Statement::TypeAlias {
+            generics,
+            value_type,
+            ..
+        } => {
+            walk_generics(visitor, generics);
+            visitor.visit_expression(value_type);
+        }
+        Statement::Trait {
+            generics,
+            signatures,
+            ..
+        } => {
+            walk_generics(visitor, generics);
+            for (signature, _, _) in signatures {
+                match &signature.value {
+                    Signature::Function(signature) => {
+                        walk_generics(visitor, &signature.generics);
+                        walk_closure_signature(visitor, &signature.closure_signature);
+                    }
+                    Signature::Property(property) => {
+                        visitor.visit_expression(&property.value_type.value_type);
+                    }
+                }
+            }
+        }
+        Statement::Impl {
+            generics,
+            implemented,
+            target,
+            statements,
+            ..
+        } => {
+            walk_generics(visitor, generics);
+            if let Some((implemented, _, _)) = implemented {
+                visitor.visit_expression(implemented);
+            }
+            visitor.visit_expression(target);
+            walk_block(visitor, statements);
+        }
+        Statement::Import { using, .. } => {
+            if let Some(using) = using {
+                visitor.visit_expression(&using.schema);
+            }
+        }
+        Statement::Module { statements, .. } => walk_block(visitor, statements),
+        Statement::Env { expr, .. } => visitor.visit_expression(expr),
+        Statement::Error(_) => {}
+    }
+}
+
+fn walk_generics<'a, V: Visitor<'a> + ?Sized>(
+    visitor: &mut V,
+    generics: &'a Option<GenericParameters>,
+) {
+    for param in generics
+        .iter()
+        .flat_map(|generics| generics.params.values())
+    {
+        if let Some(bound) = &param.bound {
+            visitor.visit_expression(&bound.value_type);
+        }
+    }
+}
+
+fn walk_closure_signature<'a, V: Visitor<'a> + ?Sized>(
+    visitor: &mut V,
+    signature: &'a ClosureSignature,
+) {
+    for param in signature.params.values() {
+        if let Some(value_type) = &param.value_type {
+            visitor.visit_expression(&value_type.value_type);
+        }
+    }
+    if let Some(return_type) = &signature.return_type {
+        visitor.visit_expression(&return_type.value_type);
+    }
+}
+
+pub fn walk_closure<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, closure: &'a Closure) {
+    walk_closure_signature(visitor, &closure.closure_signature);
+    visitor.visit_expression(&closure.body);
+}
+
+pub fn walk_expression<'a, V: Visitor<'a> + ?Sized>(
+    visitor: &mut V,
+    expression: &'a Located<Expression>,
+) {
+    match &expression.value {
+        Expression::Literal { .. } | Expression::Variable { .. } | Expression::Foreign { .. } => {}
+        Expression::InterpolatedString { parts, .. } => {
+            for part in parts {
+                if let InterpolatedPart::Expression(expr) = part {
+                    visitor.visit_expression(&expr.value);
+                }
+            }
+        }
+        Expression::Parenthesized { expr, .. } => visitor.visit_expression(expr),
+        Expression::SingleOperation {
+            operation, operand, ..
+        } => {
+            visitor.visit_expression(operand);
+            match operation {
+                UnaryOperation::Call { arguments }
+                | UnaryOperation::GenericArguments { arguments } => {
+                    for argument in arguments.values() {
+                        visitor.visit_expression(argument);
+                    }
+                }
+                UnaryOperation::Get { property, .. } => visitor.visit_expression(property),
+                _ => {}
+            }
+        }
+        Expression::Operation { left, right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        Expression::Array { elements } => {
+            for element in elements.values() {
+                visitor.visit_expression(element);
+            }
+        }
+        Expression::StructLiteral { name, fields, .. } => {
+            if let Some(name) = name {
+                visitor.visit_expression(name);
+            }
+            for field in fields.values() {
+                match field {
+                    StructLiteralField::Field(field) => visitor.visit_expression(&field.value),
+                    StructLiteralField::Shorthand(_) => {}
+                    StructLiteralField::Spread { base, .. } => visitor.visit_expression(base),
+                }
+            }
+        }
+        Expression::Declaration {
+            value_type,
+            initializer,
+            ..
+        } => {
+            if let Some(value_type) = value_type {
+                visitor.visit_expression(&value_type.value_type);
+            }
+            if let Some(initializer) = initializer {
+                visitor.visit_expression(&initializer.value);
+            }
+        }
+        Expression::Closure { closure, .. } => walk_closure(visitor, closure),
+        Expression::Block { environment, block } => {
+            if let Some(environment) = environment {
+                visitor.visit_expression(environment);
+            }
+            walk_block(visitor, block);
+        }
+        Expression::Unsafe { block, .. } | Expression::Const { block, .. } => {
+            walk_block(visitor, block)
+        }
+        Expression::Match {
+            scrutinee, arms, ..
+        } => {
+            visitor.visit_expression(scrutinee);
+            for arm in arms.values() {
+                if let Some(guard) = &arm.value.guard {
+                    visitor.visit_expression(&guard.condition);
+                }
+                visitor.visit_expression(&arm.value.body);
+            }
+        }
+        Expression::If {
+            if_block,
+            else_if_blocks,
+            else_block,
+        } => {
+            let else_ifs = else_if_blocks.iter().map(|else_if| &else_if.value);
+            for if_block in std::iter::once(if_block).chain(else_ifs) {
+                visitor.visit_expression(&if_block.condition);
+                walk_block(visitor, &if_block.block);
+            }
+            if let Some(else_block) = else_block {
+                walk_block(visitor, &else_block.value);
+            }
+        }
+        Expression::While {
+            condition,
+            body,
+            else_block,
+            ..
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_expression(body);
+            if let Some(else_block) = else_block {
+                visitor.visit_expression(&else_block.value);
+            }
+        }
+        Expression::Loop { body, .. } => visitor.visit_expression(body),
+        Expression::For {
+            iterator,
+            body,
+            else_block,
+            ..
+        } => {
+            visitor.visit_expression(iterator);
+            visitor.visit_expression(body);
+            if let Some(else_block) = else_block {
+                visitor.visit_expression(&else_block.value);
+            }
+        }
+    }
+}
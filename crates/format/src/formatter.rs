@@ -0,0 +1,352 @@
+use fst::{List, SpaceElement, Spaced};
+
+use crate::{FormatConfig, FormatNode, TrailingCommas};
+
+/// How a comma separated list is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// On a single line if it fits within the line width, one item per line otherwise
+    Fit,
+    /// One item per line
+    Vertical,
+}
+
+/// Collects formatted source code
+///
+/// Whitespace from the FST is never copied, only the comments in it are kept.
+/// All spacing is decided by the formatter.
+pub struct Formatter<'a> {
+    config: &'a FormatConfig,
+    buf: String,
+    indent: usize,
+    // set while trying to write a list on a single line, nested lists are then written on a single line too
+    flat: bool,
+}
+
+/// Whether two characters would be lexed as a single token or identifier when written next to each other
+fn needs_separation(previous: char, next: char) -> bool {
+    let word = |c: char| c.is_alphanumeric() || c == '_';
+    (word(previous) && word(next))
+        || matches!(
+            (previous, next),
+            ('&', '&')
+                | ('*', '*')
+                | ('|', '|')
+                | ('|', '>')
+                | ('?', '?')
+                | ('.', '.')
+                | ('-', '%')
+                | ('+', '%')
+                | ('*', '%')
+                | ('-', '>')
+                | ('=', '=')
+                | ('!', '=')
+                | ('<', '=')
+                | ('>', '=')
+                | ('/', '/')
+                | ('/', '*')
+        )
+}
+
+fn comments(space: &[SpaceElement]) -> impl Iterator<Item = (&str, bool)> {
+    space.iter().filter_map(|element| match element {
+        SpaceElement::LineComment(text) => Some((text.as_str(), true)),
+        SpaceElement::BlockComment(text) => Some((text.as_str(), false)),
+        SpaceElement::Space(_) => None,
+    })
+}
+
+pub fn has_comments(space: &[SpaceElement]) -> bool {
+    comments(space).next().is_some()
+}
+
+fn has_line_comment(space: &[SpaceElement]) -> bool {
+    comments(space).any(|(_, line)| line)
+}
+
+impl<'a> Formatter<'a> {
+    pub fn new(config: &'a FormatConfig) -> Self {
+        Formatter {
+            config,
+            buf: String::new(),
+            indent: 0,
+            flat: false,
+        }
+    }
+
+    pub fn finish(mut self) -> String {
+        self.buf.truncate(self.buf.trim_end().len());
+        if !self.buf.is_empty() {
+            self.buf.push('\n');
+        }
+        self.buf
+    }
+
+    /// Writes a token, separated by a space from the previous one if they would merge otherwise
+    pub fn token(&mut self, text: &str) {
+        if let (Some(previous), Some(next)) = (self.buf.chars().last(), text.chars().next()) {
+            if needs_separation(previous, next) {
+                self.buf.push(' ');
+            }
+        }
+        self.buf.push_str(text);
+    }
+
+    /// Writes a single space unless the line is empty or already ends with a space
+    pub fn space(&mut self) {
+        if !self.buf.is_empty() && !self.buf.ends_with([' ', '\n']) {
+            self.buf.push(' ');
+        }
+    }
+
+    /// Starts a new line at the current indentation
+    pub fn newline(&mut self) {
+        self.buf.truncate(self.buf.trim_end_matches(' ').len());
+        if !self.buf.is_empty() {
+            self.buf.push('\n');
+        }
+        for _ in 0..self.indent * self.config.indent_width {
+            self.buf.push(' ');
+        }
+    }
+
+    fn blank_line(&mut self) {
+        self.newline();
+        self.newline();
+    }
+
+    /// Writes the indented lines of a block, the lists in it are laid out on their own
+    pub fn indented(&mut self, inner: impl FnOnce(&mut Self)) {
+        let flat = std::mem::replace(&mut self.flat, false);
+        self.indent += 1;
+        inner(self);
+        self.indent -= 1;
+        self.flat = flat;
+    }
+
+    fn at_line_start(&self) -> bool {
+        let line = self.buf.trim_end_matches(' ');
+        line.is_empty() || line.ends_with('\n')
+    }
+
+    fn column(&self) -> usize {
+        let line_start = self.buf.rfind('\n').map_or(0, |index| index + 1);
+        self.buf[line_start..].chars().count()
+    }
+
+    fn comment(&mut self, text: &str, line: bool) {
+        self.buf.push_str(text);
+        if line {
+            self.newline();
+        }
+    }
+
+    /// Space that is written as a single space, `a = b`
+    pub fn spaced(&mut self, space: &[SpaceElement]) {
+        self.space();
+        for (text, line) in comments(space) {
+            self.comment(text, line);
+            if !line {
+                self.buf.push(' ');
+            }
+        }
+    }
+
+    /// Space around a binary operator, written as a single space, `a + b`.
+    ///
+    /// Comments on their own line stay on their own line and the lines after a line break are
+    /// continuation lines, indented once more than the expression.
+    pub fn operator_spaced(&mut self, space: &[SpaceElement]) {
+        let mut newlines = 0;
+        self.indent += 1;
+        for element in space {
+            match element {
+                SpaceElement::Space(text) => newlines += text.matches('\n').count(),
+                SpaceElement::LineComment(text) | SpaceElement::BlockComment(text) => {
+                    if newlines > 0 && !self.at_line_start() {
+                        self.newline();
+                    } else {
+                        self.space();
+                    }
+                    self.buf.push_str(text);
+                    if matches!(element, SpaceElement::LineComment(_)) {
+                        self.newline();
+                    }
+                    newlines = 0;
+                }
+            }
+        }
+        self.indent -= 1;
+        self.space();
+    }
+
+    /// Space that is dropped and is followed by a token, `(a`
+    pub fn glued_before(&mut self, space: &[SpaceElement]) {
+        for (text, line) in comments(space) {
+            self.comment(text, line);
+            if !line {
+                self.buf.push(' ');
+            }
+        }
+    }
+
+    /// Space that is dropped and follows a token, `a)`
+    pub fn glued_after(&mut self, space: &[SpaceElement]) {
+        for (text, line) in comments(space) {
+            self.space();
+            self.comment(text, line);
+        }
+    }
+
+    /// Space between two lines, such as the space between statements.
+    ///
+    /// Comments on their own line stay on their own line, comments directly following
+    /// a token stay behind it and a single blank line is kept where the source had one.
+    /// The next line is started unless `at_end`, then the caller closes the enclosing delimiter.
+    pub fn vertical(&mut self, space: &[SpaceElement], at_start: bool, at_end: bool) {
+        let mut newlines = 0;
+        let mut blank_allowed = !at_start;
+        for element in space {
+            match element {
+                SpaceElement::Space(text) => newlines += text.matches('\n').count(),
+                SpaceElement::LineComment(text) | SpaceElement::BlockComment(text) => {
+                    if newlines == 0 && !self.at_line_start() {
+                        self.space();
+                    } else if newlines >= 2 && blank_allowed {
+                        self.blank_line();
+                    } else {
+                        self.newline();
+                    }
+                    self.buf.push_str(text);
+                    blank_allowed = true;
+                    newlines = 0;
+                }
+            }
+        }
+        if !at_end {
+            if newlines >= 2 && blank_allowed {
+                self.blank_line();
+            } else {
+                self.newline();
+            }
+        }
+    }
+
+    /// Writes a comma separated list surrounded by its delimiters.
+    ///
+    /// Padded lists have a space inside of their delimiters when written on a single line, `{ a, b }`.
+    pub fn list<T: FormatNode>(
+        &mut self,
+        open: &str,
+        list: &List<T>,
+        close: &str,
+        padded: bool,
+        layout: Layout,
+    ) {
+        let slots = || {
+            list.items
+                .iter()
+                .flat_map(|item| [&item.pre_space, &item.post_space])
+                .chain([&list.end_space])
+        };
+        if list.items.is_empty() && !has_comments(&list.end_space) {
+            self.token(open);
+            self.token(close);
+            return;
+        }
+        if layout == Layout::Fit
+            && !list.items.is_empty()
+            && !slots().any(|space| has_line_comment(space))
+        {
+            if self.flat {
+                self.flat_list(open, list, close, padded);
+                return;
+            }
+            let start = self.buf.len();
+            let column = self.column();
+            self.flat = true;
+            let single_line = self.flat_list(open, list, close, padded);
+            self.flat = false;
+            if single_line && self.fits(start, column) {
+                return;
+            }
+            self.buf.truncate(start);
+        }
+        self.indented(|f| f.vertical_list(open, list));
+        self.newline();
+        self.token(close);
+    }
+
+    /// Writes the list on a single line, returns false if an item other than the last spans multiple lines.
+    ///
+    /// Only blocks can make an item span multiple lines, a call may end with a closure that has a block body.
+    fn flat_list<T: FormatNode>(
+        &mut self,
+        open: &str,
+        list: &List<T>,
+        close: &str,
+        padded: bool,
+    ) -> bool {
+        let mut single_line = true;
+        self.token(open);
+        if padded {
+            self.buf.push(' ');
+        }
+        for (i, item) in list.items.iter().enumerate() {
+            if i > 0 {
+                self.token(",");
+                self.buf.push(' ');
+            }
+            let start = self.buf.len();
+            self.spaced_item(item);
+            if i + 1 < list.items.len() && self.buf[start..].contains('\n') {
+                single_line = false;
+            }
+        }
+        if self.config.trailing_commas == TrailingCommas::Always {
+            self.token(",");
+        }
+        self.glued_after(&list.end_space);
+        if padded {
+            self.buf.push(' ');
+        }
+        self.token(close);
+        single_line
+    }
+
+    fn vertical_list<T: FormatNode>(&mut self, open: &str, list: &List<T>) {
+        let trailing_comma = self.config.trailing_commas != TrailingCommas::Never;
+        self.token(open);
+        // the space after an item and the space before the next one are joined,
+        // the comma is always written directly after the item
+        let mut space = Vec::new();
+        for (i, item) in list.items.iter().enumerate() {
+            space.extend(item.pre_space.iter().cloned());
+            self.vertical(&space, i == 0, false);
+            item.value.format_into(self);
+            if i + 1 < list.items.len() || trailing_comma {
+                self.token(",");
+            }
+            space = item.post_space.clone();
+        }
+        space.extend(list.end_space.iter().cloned());
+        self.vertical(&space, list.items.is_empty(), true);
+    }
+
+    pub fn spaced_item<T: FormatNode>(&mut self, item: &Spaced<T>) {
+        self.glued_before(&item.pre_space);
+        item.value.format_into(self);
+        self.glued_after(&item.post_space);
+    }
+
+    /// Whether everything written since `start`, which began at `column`, fits within the line width
+    fn fits(&self, start: usize, column: usize) -> bool {
+        self.buf[start..]
+            .split('\n')
+            .enumerate()
+            .all(|(i, line)| {
+                let offset = if i == 0 { column } else { 0 };
+                offset + line.chars().count() <= self.config.max_width
+            })
+    }
+}
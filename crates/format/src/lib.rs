@@ -0,0 +1,295 @@
+mod formatter;
+mod nodes;
+
+use fst::File;
+use parser::simple_parse;
+
+pub use formatter::Formatter;
+
+/// When lists are written with a comma after their last item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailingCommas {
+    Always,
+    Never,
+    /// Only when the list is written with one item per line
+    Vertical,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatConfig {
+    /// The number of spaces per indentation level
+    pub indent_width: usize,
+    /// Lists that would make a line longer than this are split into one item per line
+    pub max_width: usize,
+    pub trailing_commas: TrailingCommas,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig {
+            indent_width: 4,
+            max_width: 100,
+            trailing_commas: TrailingCommas::Vertical,
+        }
+    }
+}
+
+/// Formats FST nodes into canonical source code.
+///
+/// Unlike printing, formatting ignores the whitespace stored in the FST and only keeps comments.
+pub trait FormatNode {
+    fn format_into(&self, f: &mut Formatter);
+}
+
+pub fn format_file(file: &File, config: &FormatConfig) -> String {
+    let mut formatter = Formatter::new(config);
+    file.format_into(&mut formatter);
+    formatter.finish()
+}
+
+/// Formats source code with the default configuration, fails if the code does not parse
+pub fn format_str(code: &str) -> Result<String, String> {
+    format_str_with_config(code, &FormatConfig::default())
+}
+
+pub fn format_str_with_config(code: &str, config: &FormatConfig) -> Result<String, String> {
+    let file = simple_parse(code)?;
+    Ok(format_file(&file, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_formats(code: &str, expected: &str) {
+        let formatted = format_str(code).unwrap_or_else(|error| panic!("{}", error));
+        assert_eq!(formatted, expected);
+        assert_eq!(format_str(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_operator_spacing() {
+        assert_formats("let a=b+c*  d;", "let a = b + c * d;\n");
+        assert_formats("a=b||c&&!d;", "a = b || c && !d;\n");
+        assert_formats("a  ..b; -  c; &mut  d; e ?; f( g )[ 0 ];", "a .. b;\n-c;\n&mut d;\ne?;\nf(g)[0];\n");
+    }
+
+    #[test]
+    fn test_attributes() {
+        assert_formats(
+            "#[ static,cte ] #[requires_env (Console)]\n\nfn f(){}",
+            "#[static, cte]\n#[requires_env(Console)]\nfn f() {}\n",
+        );
+        assert_formats(
+            "#[a]pub  fn f(){} pub let a=1; pub struct A{pub  a:Int}",
+            "#[a]\npub fn f() {}\npub let a = 1;\npub struct A {\n    pub a: Int,\n}\n",
+        );
+    }
+
+    #[test]
+    fn test_generics() {
+        assert_formats(
+            "fn f <T:Clone,U >(a:T)->Option< U >{a}",
+            "fn f<T: Clone, U>(a: T) -> Option<U> {\n    a\n}\n",
+        );
+        assert_formats("impl< T >A{} let a:Map<K,V> =b<c;", "impl<T> A {}\nlet a: Map<K, V> = b < c;\n");
+        assert_formats(
+            "impl<T>Iterator<T>  for  List< T >{}",
+            "impl<T> Iterator<T> for List<T> {}\n",
+        );
+    }
+
+    #[test]
+    fn test_unsafe() {
+        assert_formats(
+            "pub  unsafe  trait X64{} unsafe fn f(){unsafe{g()}}",
+            "pub unsafe trait X64 {}\nunsafe fn f() {\n    unsafe {\n        g()\n    }\n}\n",
+        );
+    }
+
+    #[test]
+    fn test_pipe_closures() {
+        assert_formats(
+            "let f=| a ,b:Int|a+b;docs.map( |doc|doc.name );let g=||->Int{1}",
+            "let f = |a, b: Int| a + b;\ndocs.map(|doc| doc.name);\nlet g = || -> Int {\n    1\n}\n",
+        );
+    }
+
+    #[test]
+    fn test_struct_literals() {
+        assert_formats(
+            "let p=Point{x:1,y,..origin};let u=Unit{}",
+            "let p = Point { x: 1, y, ..origin };\nlet u = Unit {}\n",
+        );
+    }
+
+    #[test]
+    fn test_const() {
+        assert_formats(
+            "const  {assert(true)};let a=const{1}",
+            "const {\n    assert(true)\n};\nlet a = const {\n    1\n}\n",
+        );
+    }
+
+    #[test]
+    fn test_imports() {
+        assert_formats(
+            "import ./*  as docs using  doc;import std . lang.rust.{rust_std,rs};import ../../a;",
+            "import ./* as docs using doc;\nimport std.lang.rust.{ rust_std, rs };\nimport ../../a;\n",
+        );
+    }
+
+    #[test]
+    fn test_type_aliases() {
+        assert_formats(
+            "pub  type Pair< T > =Tuple<T,T> ;type A=B|C;",
+            "pub type Pair<T> = Tuple<T, T>;\ntype A = B | C;\n",
+        );
+    }
+
+    #[test]
+    fn test_match() {
+        assert_formats(
+            "let a=match b{Some( c )if c>0=>c,Color . Red|_=>0}",
+            "let a = match b {\n    Some(c) if c > 0 => c,\n    Color.Red | _ => 0,\n}\n",
+        );
+        assert_formats(
+            "match s {Shape.Circle{radius}=>radius,1 .. 10=>{a}}",
+            "match s {\n    Shape.Circle { radius } => radius,\n    1..10 => {\n        a\n    },\n}\n",
+        );
+    }
+
+    #[test]
+    fn test_receivers() {
+        assert_formats(
+            "trait A{fn b( & mut  self ,c:Int);fn d(self);}",
+            "trait A {\n    fn b(&mut self, c: Int);\n    fn d(self);\n}\n",
+        );
+        assert_formats("impl A{fn b(&self,){}}", "impl A {\n    fn b(&self) {}\n}\n");
+    }
+
+    #[test]
+    fn test_interpolated_strings() {
+        assert_formats(r#"a("x{ b+c   }y{d}\{");"#, "a(\"x{ b + c }y{d}\\{\");\n");
+    }
+
+    #[test]
+    fn test_indentation() {
+        assert_formats(
+            "fn f(a,b){if a{b}else{let c=1;c}}",
+            "fn f(a, b) {\n    if a {\n        b\n    } else {\n        let c = 1;\n        c\n    }\n}\n",
+        );
+        assert_formats("struct A{a:Int,b:Int}", "struct A {\n    a: Int,\n    b: Int,\n}\n");
+        assert_formats("trait T{fn f();a:Int}", "trait T {\n    fn f();\n    a: Int\n}\n");
+        assert_formats("while a do b; for {a} in b {c;}", "while a do b;\nfor { a } in b {\n    c;\n}\n");
+    }
+
+    #[test]
+    fn test_line_breaking() {
+        let config = FormatConfig {
+            max_width: 20,
+            ..FormatConfig::default()
+        };
+        let format = |code| format_str_with_config(code, &config).unwrap();
+        assert_eq!(format("f(a, b);"), "f(a, b);\n");
+        assert_eq!(
+            format("f(alpha, beta, gamma);"),
+            "f(\n    alpha,\n    beta,\n    gamma,\n);\n"
+        );
+        assert_eq!(
+            format("[[alpha, beta], [gamma, delta]];"),
+            "[\n    [alpha, beta],\n    [gamma, delta],\n];\n"
+        );
+        // a multi line last argument stays on the line of the call
+        assert_eq!(
+            format("f(a, () -> { b; });"),
+            "f(a, () -> {\n    b;\n});\n"
+        );
+    }
+
+    #[test]
+    fn test_trailing_commas() {
+        let format = |code, trailing_commas, max_width| {
+            let config = FormatConfig {
+                trailing_commas,
+                max_width,
+                ..FormatConfig::default()
+            };
+            format_str_with_config(code, &config).unwrap()
+        };
+        assert_eq!(format("[a, b,];", TrailingCommas::Vertical, 100), "[a, b];\n");
+        assert_eq!(format("[a, b];", TrailingCommas::Always, 100), "[a, b,];\n");
+        assert_eq!(
+            format("[alpha, beta];", TrailingCommas::Never, 10),
+            "[\n    alpha,\n    beta\n];\n"
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        assert_formats(
+            "// header\n\n\n\nlet a = 1; // trailing\n// own line\nlet b = /* inline */ 2;\n",
+            "// header\n\nlet a = 1; // trailing\n// own line\nlet b = /* inline */ 2;\n",
+        );
+        assert_formats(
+            "fn f() { // start\n    a; /* end */ }",
+            "fn f() { // start\n    a; /* end */\n}\n",
+        );
+        assert_formats(
+            "f(a, // first\n b);",
+            "f(\n    a, // first\n    b,\n);\n",
+        );
+        assert_formats("struct A {\n    a: Int // no comma\n}", "struct A {\n    a: Int, // no comma\n}\n");
+        // comments around an operator keep their line, the operator is on a continuation line
+        assert_formats(
+            "let a = b\n// comment\n+ c;",
+            "let a = b\n    // comment\n    + c;\n",
+        );
+        assert_formats(
+            "fn f() {\n    a // trailing\n    + /* inline */ b\n}",
+            "fn f() {\n    a // trailing\n        + /* inline */ b\n}\n",
+        );
+        assert_formats(
+            "let a = b +\n    // comment\n    c;",
+            "let a = b +\n    // comment\n    c;\n",
+        );
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        assert!(format_str("let a = ;").is_err());
+    }
+
+    #[test]
+    fn test_repository_files_are_idempotent() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let mut paths = Vec::new();
+        for directory in ["example_files", "std", "docs"] {
+            collect_quip_files(&root.join(directory), &mut paths);
+        }
+        let mut formatted_files = 0;
+        for path in paths {
+            let code = std::fs::read_to_string(&path).unwrap();
+            // files with syntax errors are rejected by the formatter
+            let Ok(formatted) = format_str(&code) else {
+                continue;
+            };
+            let reformatted = format_str(&formatted)
+                .unwrap_or_else(|error| panic!("{}\n{}", path.display(), error));
+            assert_eq!(reformatted, formatted, "{}", path.display());
+            formatted_files += 1;
+        }
+        assert!(formatted_files > 0);
+    }
+
+    fn collect_quip_files(directory: &std::path::Path, paths: &mut Vec<std::path::PathBuf>) {
+        for entry in std::fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                collect_quip_files(&path, paths);
+            } else if path.extension().is_some_and(|extension| extension == "qp") {
+                paths.push(path);
+            }
+        }
+    }
+}
@@ -0,0 +1,1172 @@
+use fst::*;
+
+use crate::{
+    formatter::{has_comments, Formatter, Layout},
+    FormatNode,
+};
+
+impl<T: FormatNode> FormatNode for Located<T> {
+    fn format_into(&self, f: &mut Formatter) {
+        self.value.format_into(f);
+    }
+}
+
+impl<T: FormatNode> FormatNode for Box<T> {
+    fn format_into(&self, f: &mut Formatter) {
+        self.as_ref().format_into(f);
+    }
+}
+
+impl FormatNode for String {
+    fn format_into(&self, f: &mut Formatter) {
+        f.token(self);
+    }
+}
+
+impl FormatNode for File {
+    fn format_into(&self, f: &mut Formatter) {
+        f.vertical(&self.pre_space, true, self.statements.is_empty());
+        format_statements(&self.statements, f);
+    }
+}
+
+fn format_statements(statements: &[(Located<Statement>, Whitespace0)], f: &mut Formatter) {
+    for (i, (statement, space)) in statements.iter().enumerate() {
+        statement.format_into(f);
+        f.vertical(space, false, i + 1 == statements.len());
+    }
+}
+
+impl FormatNode for Block {
+    fn format_into(&self, f: &mut Formatter) {
+        f.token("{");
+        if self.statements.is_empty() && !has_comments(&self.pre_space) {
+            f.token("}");
+            return;
+        }
+        f.indented(|f| {
+            f.vertical(&self.pre_space, true, self.statements.is_empty());
+            format_statements(&self.statements, f);
+        });
+        f.newline();
+        f.token("}");
+    }
+}
+
+/// Each attribute list is written on its own line in front of the item
+fn format_attributes(attributes: &[AttributeList], f: &mut Formatter) {
+    for list in attributes {
+        f.list("#[", &list.attributes, "]", false, Layout::Fit);
+        f.vertical(&list.post_space, true, false);
+    }
+}
+
+impl FormatNode for Attribute {
+    fn format_into(&self, f: &mut Formatter) {
+        f.token(&self.name);
+        if let Some((pre_arguments, arguments)) = &self.arguments {
+            f.glued_after(pre_arguments);
+            f.list("(", arguments, ")", false, Layout::Fit);
+        }
+    }
+}
+
+fn format_generics(generics: &Option<GenericParameters>, f: &mut Formatter) {
+    if let Some(generics) = generics {
+        f.glued_after(&generics.pre_space);
+        f.list("<", &generics.params, ">", false, Layout::Fit);
+    }
+}
+
+impl FormatNode for GenericParameter {
+    fn format_into(&self, f: &mut Formatter) {
+        f.token(&self.name);
+        if let Some(bound) = &self.bound {
+            bound.format_into(f);
+        }
+    }
+}
+
+fn format_unsafety(unsafety: &Option<Whitespace1>, f: &mut Formatter) {
+    if let Some(space) = unsafety {
+        f.token("unsafe");
+        f.spaced(space);
+    }
+}
+
+fn format_visibility(visibility: &Option<Whitespace1>, f: &mut Formatter) {
+    if let Some(space) = visibility {
+        f.token("pub");
+        f.spaced(space);
+    }
+}
+
+impl FormatNode for StructField {
+    fn format_into(&self, f: &mut Formatter) {
+        format_visibility(&self.visibility, f);
+        self.field.format_into(f);
+    }
+}
+
+fn format_semi(semi: &Option<Whitespace0>, f: &mut Formatter) {
+    if let Some(semi) = semi {
+        f.glued_after(semi);
+        f.token(";");
+    }
+}
+
+impl FormatNode for Statement {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            Statement::Expression {
+                visibility,
+                expr,
+                semi,
+            } => {
+                format_visibility(visibility, f);
+                expr.format_into(f);
+                format_semi(semi, f);
+            }
+            Statement::Return(label_expression) => {
+                f.token("return");
+                label_expression.format_into(f);
+            }
+            Statement::Break(label_expression) => {
+                f.token("break");
+                label_expression.format_into(f);
+            }
+            Statement::Continue(spaced_label) => {
+                f.token("continue");
+                spaced_label.format_into(f);
+            }
+            Statement::Function {
+                attributes,
+                visibility,
+                unsafety,
+                after_fn,
+                name,
+                generics,
+                after_name,
+                closure,
+                semi,
+            } => {
+                format_attributes(attributes, f);
+                format_visibility(visibility, f);
+                format_unsafety(unsafety, f);
+                f.token("fn");
+                f.spaced(after_fn);
+                f.token(name);
+                format_generics(generics, f);
+                f.glued_after(after_name);
+                closure.format_into(f);
+                format_semi(semi, f);
+            }
+            Statement::Struct {
+                attributes,
+                visibility,
+                after_struct,
+                name,
+                generics,
+                after_name,
+                fields,
+                semi,
+            } => {
+                format_attributes(attributes, f);
+                format_visibility(visibility, f);
+                f.token("struct");
+                f.spaced(after_struct);
+                f.token(name);
+                format_generics(generics, f);
+                f.spaced(after_name);
+                f.list("{", fields, "}", true, Layout::Vertical);
+                format_semi(semi, f);
+            }
+            Statement::Enum {
+                attributes,
+                visibility,
+                after_enum,
+                name,
+                generics,
+                after_name,
+                options,
+                semi,
+            } => {
+                format_attributes(attributes, f);
+                format_visibility(visibility, f);
+                f.token("enum");
+                f.spaced(after_enum);
+                f.token(name);
+                format_generics(generics, f);
+                f.spaced(after_name);
+                f.list("{", options, "}", true, Layout::Vertical);
+                format_semi(semi, f);
+            }
+            Statement::TypeAlias {
+                attributes,
+                visibility,
+                after_type,
+                name,
+                generics,
+                pre_equals,
+                post_equals,
+                value_type,
+                semi,
+            } => {
+                format_attributes(attributes, f);
+                format_visibility(visibility, f);
+                f.token("type");
+                f.spaced(after_type);
+                f.token(name);
+                format_generics(generics, f);
+                f.spaced(pre_equals);
+                f.token("=");
+                f.spaced(post_equals);
+                value_type.format_into(f);
+                f.glued_after(semi);
+                f.token(";");
+            }
+            Statement::Trait {
+                attributes,
+                visibility,
+                unsafety,
+                after_trait,
+                name,
+                generics,
+                after_name,
+                pre_space,
+                signatures,
+                semi,
+            } => {
+                format_attributes(attributes, f);
+                format_visibility(visibility, f);
+                format_unsafety(unsafety, f);
+                f.token("trait");
+                f.spaced(after_trait);
+                f.token(name);
+                format_generics(generics, f);
+                f.spaced(after_name);
+                f.token("{");
+                if !signatures.is_empty() || has_comments(pre_space) {
+                    f.indented(|f| {
+                        f.vertical(pre_space, true, signatures.is_empty());
+                        for (i, (signature, signature_semi, space)) in signatures.iter().enumerate()
+                        {
+                            signature.format_into(f);
+                            format_semi(signature_semi, f);
+                            f.vertical(space, false, i + 1 == signatures.len());
+                        }
+                    });
+                    f.newline();
+                }
+                f.token("}");
+                format_semi(semi, f);
+            }
+            Statement::Impl {
+                attributes,
+                generics,
+                after_impl,
+                implemented,
+                target,
+                after_target,
+                statements,
+                semi,
+            } => {
+                format_attributes(attributes, f);
+                f.token("impl");
+                format_generics(generics, f);
+                f.spaced(after_impl);
+                if let Some((implemented, pre_for, post_for)) = implemented {
+                    implemented.format_into(f);
+                    f.spaced(pre_for);
+                    f.token("for");
+                    f.spaced(post_for);
+                }
+                target.format_into(f);
+                f.spaced(after_target);
+                statements.format_into(f);
+                format_semi(semi, f);
+            }
+            Statement::Import {
+                after_import,
+                path,
+                extract,
+                using,
+                semi,
+            } => {
+                f.token("import");
+                f.spaced(after_import);
+                path.format_into(f);
+                if let Some((pre_as, post_as, extract)) = extract {
+                    f.spaced(pre_as);
+                    f.token("as");
+                    f.spaced(post_as);
+                    extract.format_into(f);
+                }
+                if let Some(using) = using {
+                    f.spaced(&using.pre_using);
+                    f.token("using");
+                    f.spaced(&using.post_using);
+                    using.schema.format_into(f);
+                }
+                format_semi(semi, f);
+            }
+            Statement::Module {
+                attributes,
+                visibility,
+                after_mod,
+                name,
+                after_name,
+                statements,
+                semi,
+            } => {
+                format_attributes(attributes, f);
+                format_visibility(visibility, f);
+                f.token("mod");
+                f.spaced(after_mod);
+                f.token(name);
+                f.spaced(after_name);
+                statements.format_into(f);
+                format_semi(semi, f);
+            }
+            Statement::Env {
+                after_use_env,
+                expr,
+                semi,
+            } => {
+                f.token("use_env");
+                f.spaced(after_use_env);
+                expr.format_into(f);
+                f.glued_after(semi);
+                f.token(";");
+            }
+            Statement::Error(text) => f.token(text.trim()),
+        }
+    }
+}
+
+impl FormatNode for ImportPath {
+    fn format_into(&self, f: &mut Formatter) {
+        let prefix = match self.relative {
+            RelativeImport::None => String::new(),
+            RelativeImport::Current => "./".to_string(),
+            RelativeImport::Parent(count) => "../".repeat(count),
+        };
+        // `./*` is written as a single token, a separate `*` is kept apart from the `/` so it
+        // doesn't start a comment
+        if let [Spaced {
+            value: ImportSegment::Glob,
+            post_space,
+            ..
+        }] = self.segments.as_slice()
+        {
+            f.token(&format!("{}*", prefix));
+            f.glued_after(post_space);
+            return;
+        }
+        if !prefix.is_empty() {
+            f.token(&prefix);
+        }
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                f.token(".");
+            }
+            f.spaced_item(segment);
+        }
+    }
+}
+
+impl FormatNode for ImportSegment {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            ImportSegment::Name(name) => f.token(name),
+            ImportSegment::Glob => f.token("*"),
+            ImportSegment::List(names) => f.list("{", names, "}", true, Layout::Fit),
+        }
+    }
+}
+
+impl FormatNode for LabelExpression {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            LabelExpression::WithExpression {
+                pre_space,
+                label,
+                expr,
+                semi_space,
+            } => {
+                f.spaced(pre_space);
+                if let Some((label, space)) = label {
+                    f.token(label);
+                    f.spaced(space);
+                }
+                expr.format_into(f);
+                f.glued_after(semi_space);
+                f.token(";");
+            }
+            LabelExpression::NoExpression(spaced_label) => spaced_label.format_into(f),
+        }
+    }
+}
+
+impl FormatNode for SpacedLabel {
+    fn format_into(&self, f: &mut Formatter) {
+        if let Some((space, label)) = &self.label {
+            f.spaced(space);
+            f.token(label);
+        }
+        format_semi(&self.semi_space, f);
+    }
+}
+
+impl FormatNode for Closure {
+    fn format_into(&self, f: &mut Formatter) {
+        self.closure_signature.format_into(f);
+        self.separator.format_into(f);
+        self.body.format_into(f);
+    }
+}
+
+impl FormatNode for BodySeparator {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            BodySeparator::Space(space) => f.spaced(space),
+            BodySeparator::Arrow(pre_arrow, post_arrow) => {
+                f.spaced(pre_arrow);
+                f.token("->");
+                f.spaced(post_arrow);
+            }
+            BodySeparator::Do(pre_do, post_do) => {
+                f.spaced(pre_do);
+                f.token("do");
+                f.spaced(post_do);
+            }
+        }
+    }
+}
+
+/// The receiver and the parameters of a signature, laid out as items of the same list
+enum SignatureItem<'a> {
+    Receiver(&'a Receiver),
+    Parameter(&'a Parameter),
+}
+
+impl FormatNode for SignatureItem<'_> {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            SignatureItem::Receiver(receiver) => receiver.format_into(f),
+            SignatureItem::Parameter(parameter) => parameter.format_into(f),
+        }
+    }
+}
+
+/// Keeps the space around `item` for a value borrowed from it
+fn spaced<'a, T>(item: &Spaced<T>, value: SignatureItem<'a>) -> Spaced<SignatureItem<'a>> {
+    Spaced {
+        pre_space: item.pre_space.clone(),
+        value,
+        post_space: item.post_space.clone(),
+    }
+}
+
+impl FormatNode for ClosureSignature {
+    fn format_into(&self, f: &mut Formatter) {
+        let receiver = self
+            .receiver
+            .iter()
+            .map(|receiver| spaced(receiver, SignatureItem::Receiver(&receiver.value)));
+        let params = self
+            .params
+            .items
+            .iter()
+            .map(|param| spaced(param, SignatureItem::Parameter(&param.value)));
+        let items = List {
+            items: receiver.chain(params).collect(),
+            trailing_comma: self.params.trailing_comma,
+            end_space: self.params.end_space.clone(),
+        };
+        f.list("(", &items, ")", false, Layout::Fit);
+        if let Some(return_type) = &self.return_type {
+            return_type.format_into(f);
+        }
+    }
+}
+
+impl FormatNode for Receiver {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            Receiver::Value => {}
+            Receiver::Reference(after_ampersand) => {
+                f.token("&");
+                f.glued_before(after_ampersand);
+            }
+            Receiver::MutableReference(after_ampersand, after_mut) => {
+                f.token("&");
+                f.glued_before(after_ampersand);
+                f.token("mut");
+                f.spaced(after_mut);
+            }
+        }
+        f.token("self");
+    }
+}
+
+impl FormatNode for Parameter {
+    fn format_into(&self, f: &mut Formatter) {
+        self.creation.format_into(f);
+        if let Some(value_type) = &self.value_type {
+            value_type.format_into(f);
+        }
+    }
+}
+
+impl FormatNode for TypeAnnotation {
+    fn format_into(&self, f: &mut Formatter) {
+        f.glued_after(&self.pre_colon);
+        f.token(":");
+        f.spaced(&self.post_colon);
+        self.value_type.format_into(f);
+    }
+}
+
+impl FormatNode for ReturnType {
+    fn format_into(&self, f: &mut Formatter) {
+        f.spaced(&self.pre_arrow);
+        f.token("->");
+        f.spaced(&self.post_arrow);
+        self.value_type.format_into(f);
+    }
+}
+
+impl FormatNode for Initializer {
+    fn format_into(&self, f: &mut Formatter) {
+        f.spaced(&self.pre_assignment);
+        f.token("=");
+        f.spaced(&self.post_assignment);
+        self.value.format_into(f);
+    }
+}
+
+impl FormatNode for Field {
+    fn format_into(&self, f: &mut Formatter) {
+        f.token(&self.name);
+        f.glued_after(&self.pre_colon);
+        f.token(":");
+        f.spaced(&self.post_colon);
+        self.value.format_into(f);
+    }
+}
+
+impl<T: FormatNode> FormatNode for Else<T> {
+    fn format_into(&self, f: &mut Formatter) {
+        f.spaced(&self.pre_else);
+        f.token("else");
+        f.spaced(&self.post_else);
+        self.value.format_into(f);
+    }
+}
+
+impl FormatNode for IfBlock {
+    fn format_into(&self, f: &mut Formatter) {
+        f.token("if");
+        f.spaced(&self.after_if);
+        self.condition.format_into(f);
+        f.spaced(&self.before_block);
+        self.block.format_into(f);
+    }
+}
+
+impl FormatNode for EnumOption {
+    fn format_into(&self, f: &mut Formatter) {
+        let (name, value) = self;
+        f.token(name);
+        match value {
+            EnumValue::Tuple { space, values } => {
+                f.glued_after(space);
+                f.list("(", values, ")", false, Layout::Fit);
+            }
+            EnumValue::Struct { space, fields } => {
+                f.spaced(space);
+                f.list("{", fields, "}", true, Layout::Fit);
+            }
+            EnumValue::Unit => {}
+        }
+    }
+}
+
+impl FormatNode for Signature {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            Signature::Function(signature) => signature.format_into(f),
+            Signature::Property(signature) => signature.format_into(f),
+        }
+    }
+}
+
+impl FormatNode for FunctionSignature {
+    fn format_into(&self, f: &mut Formatter) {
+        format_unsafety(&self.unsafety, f);
+        f.token("fn");
+        f.spaced(&self.after_fn);
+        f.token(&self.name);
+        format_generics(&self.generics, f);
+        f.glued_after(&self.after_name);
+        self.closure_signature.format_into(f);
+    }
+}
+
+impl FormatNode for PropertySignature {
+    fn format_into(&self, f: &mut Formatter) {
+        if let Some(space) = &self.mutable {
+            f.token("mut");
+            f.spaced(space);
+        }
+        f.token(&self.name);
+        self.value_type.format_into(f);
+    }
+}
+
+impl FormatNode for VariableCreation {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            VariableCreation::Identifier { name, mutable } => {
+                if let Some(space) = mutable {
+                    f.token("mut");
+                    f.spaced(space);
+                }
+                f.token(name);
+            }
+            VariableCreation::Destructure { destructure } => {
+                f.list("{", destructure, "}", true, Layout::Fit)
+            }
+        }
+    }
+}
+
+impl FormatNode for Expression {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            Expression::Literal { value } => value.format_into(f),
+            Expression::InterpolatedString { quote, parts } => {
+                let quote = quote.to_string();
+                f.token(&quote);
+                for part in parts {
+                    match part {
+                        InterpolatedPart::Text { text, .. } => f.token(text),
+                        InterpolatedPart::Expression(expression) => {
+                            // braces are padded with a space only where the source had one
+                            f.token("{");
+                            if !expression.pre_space.is_empty() {
+                                f.spaced(&expression.pre_space);
+                            }
+                            expression.value.format_into(f);
+                            f.glued_after(&expression.post_space);
+                            if !expression.post_space.is_empty() {
+                                f.space();
+                            }
+                            f.token("}");
+                        }
+                    }
+                }
+                f.token(&quote);
+            }
+            Expression::Variable { identifier } => f.token(identifier),
+            Expression::Parenthesized {
+                pre_space,
+                expr,
+                post_space,
+            } => {
+                f.token("(");
+                f.glued_before(pre_space);
+                expr.format_into(f);
+                f.glued_after(post_space);
+                f.token(")");
+            }
+            Expression::SingleOperation {
+                operation,
+                space,
+                operand,
+            } => {
+                if operation.is_prefix() {
+                    operation.format_into(f);
+                    f.glued_before(space);
+                    operand.format_into(f);
+                } else {
+                    operand.format_into(f);
+                    f.glued_after(space);
+                    operation.format_into(f);
+                }
+            }
+            Expression::Operation {
+                left,
+                left_space,
+                operator,
+                right_space,
+                right,
+            } => {
+                left.format_into(f);
+                f.operator_spaced(left_space);
+                operator.format_into(f);
+                f.operator_spaced(right_space);
+                right.format_into(f);
+            }
+            Expression::Array { elements } => f.list("[", elements, "]", false, Layout::Fit),
+            Expression::StructLiteral {
+                name,
+                pre_brace,
+                fields,
+            } => {
+                if let Some(name) = name {
+                    name.format_into(f);
+                    f.spaced(pre_brace);
+                }
+                f.list("{", fields, "}", true, Layout::Fit);
+            }
+            Expression::Declaration {
+                after_let,
+                creation,
+                value_type,
+                initializer,
+            } => {
+                f.token("let");
+                f.spaced(after_let);
+                creation.format_into(f);
+                if let Some(value_type) = value_type {
+                    value_type.format_into(f);
+                }
+                if let Some(initializer) = initializer {
+                    initializer.format_into(f);
+                }
+            }
+            Expression::Closure { closure, delimiter } => match delimiter {
+                ParameterDelimiter::Parentheses => closure.format_into(f),
+                ParameterDelimiter::Pipes | ParameterDelimiter::None => {
+                    let signature = &closure.closure_signature;
+                    if *delimiter == ParameterDelimiter::Pipes {
+                        if signature.params.items.is_empty()
+                            && !has_comments(&signature.params.end_space)
+                        {
+                            // written as the single `||` token, which is read the same way
+                            f.token("||");
+                        } else {
+                            f.list("|", &signature.params, "|", false, Layout::Fit);
+                        }
+                    } else {
+                        for (i, param) in signature.params.items.iter().enumerate() {
+                            if i > 0 {
+                                f.token(",");
+                                f.space();
+                            }
+                            f.spaced_item(param);
+                        }
+                    }
+                    if let Some(return_type) = &signature.return_type {
+                        return_type.format_into(f);
+                    }
+                    closure.separator.format_into(f);
+                    closure.body.format_into(f);
+                }
+            },
+            Expression::Block { environment, block } => {
+                if let Some(environment) = environment {
+                    environment.format_into(f);
+                    f.token(":");
+                    f.space();
+                }
+                block.format_into(f);
+            }
+            Expression::Unsafe {
+                after_unsafe,
+                block,
+            } => {
+                f.token("unsafe");
+                f.spaced(after_unsafe);
+                block.format_into(f);
+            }
+            // foreign code is never reformatted
+            Expression::Foreign { source } => f.token(source),
+            Expression::Const { after_const, block } => {
+                f.token("const");
+                f.spaced(after_const);
+                block.format_into(f);
+            }
+            Expression::Match {
+                after_match,
+                scrutinee,
+                before_arms,
+                arms,
+            } => {
+                f.token("match");
+                f.spaced(after_match);
+                scrutinee.format_into(f);
+                f.spaced(before_arms);
+                f.list("{", arms, "}", true, Layout::Vertical);
+            }
+            Expression::If {
+                if_block,
+                else_if_blocks,
+                else_block,
+            } => {
+                if_block.format_into(f);
+                for else_if_block in else_if_blocks {
+                    else_if_block.format_into(f);
+                }
+                if let Some(else_block) = else_block {
+                    else_block.format_into(f);
+                }
+            }
+            Expression::While {
+                label,
+                after_while,
+                condition,
+                before_body,
+                do_body,
+                body,
+                else_block,
+            } => {
+                format_label(label, f);
+                f.token("while");
+                f.spaced(after_while);
+                condition.format_into(f);
+                f.spaced(before_body);
+                format_loop_body(body, do_body, f);
+                if let Some(else_block) = else_block {
+                    else_block.format_into(f);
+                }
+            }
+            Expression::Loop {
+                label,
+                after_loop,
+                body,
+            } => {
+                format_label(label, f);
+                f.token("loop");
+                f.spaced(after_loop);
+                body.format_into(f);
+            }
+            Expression::For {
+                label,
+                after_for,
+                destructure,
+                before_in,
+                after_in,
+                iterator,
+                before_body,
+                do_body,
+                body,
+                else_block,
+            } => {
+                format_label(label, f);
+                f.token("for");
+                f.spaced(after_for);
+                f.list("{", destructure, "}", true, Layout::Fit);
+                f.spaced(before_in);
+                f.token("in");
+                f.spaced(after_in);
+                iterator.format_into(f);
+                f.spaced(before_body);
+                format_loop_body(body, do_body, f);
+                if let Some(else_block) = else_block {
+                    else_block.format_into(f);
+                }
+            }
+        }
+    }
+}
+
+fn format_label(label: &Option<(String, Whitespace0)>, f: &mut Formatter) {
+    if let Some((label, space)) = label {
+        f.token(label);
+        f.spaced(space);
+    }
+}
+
+fn format_loop_body(body: &Located<Expression>, do_body: &Option<Whitespace0>, f: &mut Formatter) {
+    if let Some(space) = do_body {
+        f.token("do");
+        f.spaced(space);
+    }
+    body.format_into(f);
+}
+
+impl FormatNode for Literal {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            Literal::Number(number) => f.token(&number.text),
+            Literal::String(string) | Literal::RawString(string) => f.token(&string.text),
+            Literal::Boolean(boolean) => f.token(if *boolean { "true" } else { "false" }),
+        }
+    }
+}
+
+impl FormatNode for UnaryOperation {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            UnaryOperation::Not => f.token("!"),
+            UnaryOperation::ErrorUnwrap => f.token("?"),
+            UnaryOperation::Inline => f.token("!"),
+            UnaryOperation::Spread => f.token("*"),
+            UnaryOperation::Negate => f.token("-"),
+            UnaryOperation::Positive => f.token("+"),
+            UnaryOperation::Reference { mutable } => {
+                f.token("&");
+                if *mutable {
+                    f.token("mut");
+                    f.space();
+                }
+            }
+            UnaryOperation::Dereference => f.token("*"),
+            UnaryOperation::Call { arguments } => f.list("(", arguments, ")", false, Layout::Fit),
+            UnaryOperation::GenericArguments { arguments } => {
+                f.list("<", arguments, ">", false, Layout::Fit)
+            }
+            UnaryOperation::Get {
+                pre_space,
+                property,
+                post_space,
+            } => {
+                f.token("[");
+                f.glued_before(pre_space);
+                property.format_into(f);
+                f.glued_after(post_space);
+                f.token("]");
+            }
+            UnaryOperation::ArrayOf { space } => {
+                f.token("[");
+                f.glued_before(space);
+                f.token("]");
+            }
+            UnaryOperation::Extract { dot_space, extract } => {
+                f.token(".");
+                f.glued_before(dot_space);
+                extract.format_into(f);
+            }
+        }
+    }
+}
+
+impl FormatNode for StructLiteralField {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            StructLiteralField::Field(field) => field.format_into(f),
+            StructLiteralField::Shorthand(name) => f.token(&name.value),
+            StructLiteralField::Spread { after_dots, base } => {
+                f.token("..");
+                f.glued_before(after_dots);
+                base.format_into(f);
+            }
+        }
+    }
+}
+
+impl FormatNode for MatchArm {
+    fn format_into(&self, f: &mut Formatter) {
+        self.pattern.format_into(f);
+        if let Some(guard) = &self.guard {
+            f.spaced(&guard.pre_if);
+            f.token("if");
+            f.spaced(&guard.after_if);
+            guard.condition.format_into(f);
+        }
+        f.spaced(&self.pre_arrow);
+        f.token("=>");
+        f.spaced(&self.post_arrow);
+        self.body.format_into(f);
+    }
+}
+
+impl FormatNode for Pattern {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            Pattern::Wildcard => f.token("_"),
+            Pattern::Binding { mutable, name } => {
+                if let Some(space) = mutable {
+                    f.token("mut");
+                    f.spaced(space);
+                }
+                f.token(name);
+            }
+            Pattern::Literal(literal) => literal.format_into(f),
+            Pattern::Range {
+                start,
+                pre_dots,
+                post_dots,
+                end,
+            } => {
+                start.format_into(f);
+                f.glued_after(pre_dots);
+                f.token("..");
+                f.glued_before(post_dots);
+                end.format_into(f);
+            }
+            Pattern::Variant { path, values } => {
+                for (i, name) in path.iter().enumerate() {
+                    if i > 0 {
+                        f.token(".");
+                    }
+                    f.spaced_item(name);
+                }
+                match values {
+                    VariantPattern::Tuple { space, values } => {
+                        f.glued_after(space);
+                        f.list("(", values, ")", false, Layout::Fit);
+                    }
+                    VariantPattern::Struct { space, fields } => {
+                        f.spaced(space);
+                        f.list("{", fields, "}", true, Layout::Fit);
+                    }
+                    VariantPattern::Unit => {}
+                }
+            }
+            Pattern::Alternatives(alternatives) => {
+                // the bars are written with a space on both sides, `a | b`
+                let mut post_space: &[SpaceElement] = &[];
+                for (i, alternative) in alternatives.iter().enumerate() {
+                    if i > 0 {
+                        f.spaced(post_space);
+                        f.token("|");
+                        f.spaced(&alternative.pre_space);
+                    } else {
+                        f.glued_before(&alternative.pre_space);
+                    }
+                    alternative.value.format_into(f);
+                    post_space = &alternative.post_space;
+                }
+                f.glued_after(post_space);
+            }
+        }
+    }
+}
+
+impl FormatNode for Operator {
+    fn format_into(&self, f: &mut Formatter) {
+        f.token(match self {
+            Operator::Assignment => "=",
+            Operator::Range => "..",
+            Operator::And => "&&",
+            Operator::Or => "||",
+            Operator::Equals => "==",
+            Operator::NotEquals => "!=",
+            Operator::LessThan => "<",
+            Operator::LessThanOrEquals => "<=",
+            Operator::GreaterThan => ">",
+            Operator::GreaterThanOrEquals => ">=",
+            Operator::Add => "+",
+            Operator::Subtract => "-",
+            Operator::Multiply => "*",
+            Operator::WrappingAdd => "+%",
+            Operator::WrappingSubtract => "-%",
+            Operator::WrappingMultiply => "*%",
+            Operator::Divide => "/",
+            Operator::Modulo => "%",
+            Operator::Power => "**",
+            Operator::Pipe => "|>",
+            Operator::Union => "|",
+            Operator::Intersection => "&",
+            Operator::ExclusiveOr => "^",
+        })
+    }
+}
+
+impl<T: FormatNode> FormatNode for Alias<T> {
+    fn format_into(&self, f: &mut Formatter) {
+        f.spaced(&self.pre_as);
+        f.token("as");
+        f.spaced(&self.post_as);
+        self.alias.format_into(f);
+    }
+}
+
+impl<T: FormatNode> FormatNode for SubExtract<T> {
+    fn format_into(&self, f: &mut Formatter) {
+        f.glued_after(&self.pre_dot);
+        f.token(".");
+        f.glued_before(&self.post_dot);
+        self.extract.format_into(f);
+    }
+}
+
+impl FormatNode for MutableDestructureProperty {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            MutableDestructureProperty::AliasedSubProperties {
+                property_name,
+                extract,
+                alias,
+            } => {
+                f.token(property_name);
+                extract.format_into(f);
+                alias.format_into(f);
+            }
+            MutableDestructureProperty::Property {
+                property_name,
+                alias,
+            } => {
+                f.token(property_name);
+                if let Some(alias) = alias {
+                    alias.format_into(f);
+                }
+            }
+            MutableDestructureProperty::UnaliasedSubProperties {
+                property_name,
+                extract,
+            } => {
+                f.token(property_name);
+                extract.format_into(f);
+            }
+            MutableDestructureProperty::MutablePropertyChain { property_chain } => {
+                f.token("mut");
+                f.space();
+                for (i, property) in property_chain.iter().enumerate() {
+                    if i > 0 {
+                        f.token(".");
+                    }
+                    f.spaced_item(property);
+                }
+            }
+        }
+    }
+}
+
+impl FormatNode for MutableAlias {
+    fn format_into(&self, f: &mut Formatter) {
+        if let Some(space) = &self.mutable {
+            f.token("mut");
+            f.spaced(space);
+        }
+        f.token(&self.alias);
+    }
+}
+
+impl FormatNode for MutableExtract {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            MutableExtract::Destructured(destructure) => {
+                f.list("{", destructure, "}", true, Layout::Fit)
+            }
+            MutableExtract::DirectProperty(property) => property.format_into(f),
+        }
+    }
+}
+
+impl FormatNode for ImmutableDestructureProperty {
+    fn format_into(&self, f: &mut Formatter) {
+        f.token(&self.property_name);
+        if let Some(extract) = &self.extract {
+            extract.format_into(f);
+        }
+        if let Some(alias) = &self.alias {
+            alias.format_into(f);
+        }
+    }
+}
+
+impl FormatNode for ImmutableExtract {
+    fn format_into(&self, f: &mut Formatter) {
+        match self {
+            ImmutableExtract::Destructured(destructure) => {
+                f.list("{", destructure, "}", true, Layout::Fit)
+            }
+            ImmutableExtract::DirectProperty(property) => property.format_into(f),
+        }
+    }
+}
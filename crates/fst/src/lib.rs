@@ -4,9 +4,10 @@ use std::{
 };
 
 use num::bigint::BigInt;
+use serde::Serialize;
 use vec1::Vec1;
 
-#[derive(Debug, Clone, PartialEq, Copy, Eq)]
+#[derive(Debug, Clone, PartialEq, Copy, Eq, Hash, Serialize)]
 pub struct Location {
     pub line: usize,
     pub column: usize,
@@ -31,7 +32,7 @@ impl Display for Location {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Copy)]
+#[derive(Debug, Clone, PartialEq, Copy, Eq, Hash, Serialize)]
 /// This represents a span within a source file
 /// The range includes the start, but excludes the end
 pub struct SourceSpan {
@@ -45,30 +46,123 @@ impl Display for SourceSpan {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl SourceSpan {
+    /// Creates a span starting at the start of `self` and ending at the end of `other`
+    pub fn to(&self, other: &SourceSpan) -> SourceSpan {
+        SourceSpan {
+            start: self.start,
+            end: other.end,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+/// A node together with the span of source it was parsed from
+///
+/// Equality ignores the span, two nodes are equal if they have the same structure,
+/// regardless of where in the source they are located.
+pub struct Located<T> {
+    pub source_span: SourceSpan,
+    pub value: T,
+}
+
+impl<T> Located<T> {
+    pub fn new(value: T, source_span: SourceSpan) -> Self {
+        Located { source_span, value }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Located<U> {
+        Located {
+            source_span: self.source_span,
+            value: f(self.value),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for Located<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// A node together with the whitespace directly around it
+pub struct Spaced<T> {
+    pub pre_space: Whitespace0,
+    pub value: T,
+    pub post_space: Whitespace0,
+}
+
+impl<T> Spaced<T> {
+    pub fn new(value: T) -> Self {
+        Spaced {
+            pre_space: Vec::new(),
+            value,
+            post_space: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// A comma separated list such as `[a, b]` or `{ a: 1, b: 2, }`
+///
+/// The space in front of a comma or the closing delimiter is the `post_space` of the item
+/// before it, the space after a trailing comma or inside an empty list is `end_space`.
+pub struct List<T> {
+    pub items: Vec<Spaced<T>>,
+    pub trailing_comma: bool,
+    pub end_space: Whitespace0,
+}
+
+impl<T> List<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        List {
+            items: items.into_iter().map(Spaced::new).collect(),
+            trailing_comma: false,
+            end_space: Vec::new(),
+        }
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.items.iter().map(|item| &item.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// The statements between braces, `{ a; b }`
+pub struct Block {
+    pub pre_space: Whitespace0,
+    // each statement is followed by the space up to the next statement or the closing brace
+    pub statements: Vec<(Located<Statement>, Whitespace0)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum LabelExpression {
     WithExpression {
         pre_space: Whitespace1,
         label: Option<(String, Whitespace1)>,
-        expr: Expression,
+        expr: Box<Located<Expression>>,
         // expressions at the end of a statement must end with a semicolon
         semi_space: Whitespace0,
     },
     NoExpression(SpacedLabel),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct SpacedLabel {
-    label: Option<(Whitespace1, String)>,
-    semi_space: Option<Whitespace0>,
+    pub label: Option<(Whitespace1, String)>,
+    pub semi_space: Option<Whitespace0>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Statement {
     Expression {
         // - {expr}
         // - {expr}{space0};
-        expr: Expression,
+        // - pub{visibility}{expr}, only declarations can be public, `pub let a = 1;`
+        visibility: Option<Whitespace1>,
+        expr: Located<Expression>,
         semi: Option<Whitespace0>,
     },
     // - return{label_expression}
@@ -77,98 +171,377 @@ pub enum Statement {
     Break(LabelExpression),
     // - continue{spaced_label}
     Continue(SpacedLabel),
+    // items may be followed by an optional semicolon, `semi` holds the space in front of it
+    // `visibility` is the space after `pub` for public items, which are exported from their module
+    // `unsafety` is the space after `unsafe` for unsafe functions and traits
+    // - {attributes}{visibility}{unsafety}fn{after_fn}{name}{generics}{after_name}{closure}
     Function {
+        attributes: Vec<AttributeList>,
+        visibility: Option<Whitespace1>,
+        unsafety: Option<Whitespace1>,
+        after_fn: Whitespace1,
         name: String,
-        closure: Closure,
+        generics: Option<GenericParameters>,
+        after_name: Whitespace0,
+        closure: Box<Located<Closure>>,
+        semi: Option<Whitespace0>,
     },
+    // - {attributes}{visibility}struct{after_struct}{name}{generics}{after_name}{fields}
     Struct {
+        attributes: Vec<AttributeList>,
+        visibility: Option<Whitespace1>,
+        after_struct: Whitespace0,
         name: String,
-        fields: Vec<(String, Expression)>,
+        generics: Option<GenericParameters>,
+        after_name: Whitespace0,
+        fields: List<StructField>,
+        semi: Option<Whitespace0>,
     },
+    // - {attributes}{visibility}enum{after_enum}{name}{generics}{after_name}{options}
     Enum {
+        attributes: Vec<AttributeList>,
+        visibility: Option<Whitespace1>,
+        after_enum: Whitespace0,
         name: String,
-        options: Vec<EnumOption>,
+        generics: Option<GenericParameters>,
+        after_name: Whitespace0,
+        options: List<Located<EnumOption>>,
+        semi: Option<Whitespace0>,
     },
+    // - {attributes}{visibility}type{after_type}{name}{generics}{pre_equals}={post_equals}{value_type}{semi};
+    TypeAlias {
+        attributes: Vec<AttributeList>,
+        visibility: Option<Whitespace1>,
+        after_type: Whitespace0,
+        name: String,
+        generics: Option<GenericParameters>,
+        pre_equals: Whitespace0,
+        post_equals: Whitespace0,
+        value_type: Located<Expression>,
+        semi: Whitespace0,
+    },
+    // - {attributes}{visibility}{unsafety}trait{after_trait}{name}{generics}{after_name}{{pre_space}{signatures}}
     Trait {
+        attributes: Vec<AttributeList>,
+        visibility: Option<Whitespace1>,
+        unsafety: Option<Whitespace1>,
+        after_trait: Whitespace0,
         name: String,
-        signatures: Vec<Signature>,
+        generics: Option<GenericParameters>,
+        after_name: Whitespace0,
+        pre_space: Whitespace0,
+        // each signature may end with a semicolon and is followed by the space up to the next one
+        signatures: Vec<(Located<Signature>, Option<Whitespace0>, Whitespace0)>,
+        semi: Option<Whitespace0>,
     },
+    // - {attributes}impl{generics}{after_impl}{target}{after_target}{statements}
+    // - {attributes}impl{generics}{after_impl}{implemented}{space1}for{space1}{target}{after_target}{statements}
     Impl {
-        target: String,
-        implemented: Option<Expression>,
-        statements: Vec<Statement>,
+        attributes: Vec<AttributeList>,
+        generics: Option<GenericParameters>,
+        after_impl: Whitespace0,
+        implemented: Option<(Box<Located<Expression>>, Whitespace1, Whitespace1)>,
+        target: Box<Located<Expression>>,
+        after_target: Whitespace0,
+        statements: Block,
+        semi: Option<Whitespace0>,
     },
+    // - import{after_import}{path}
+    // - import{after_import}{path}{space1}as{space1}{extract}
+    // either may be followed by {using}
     Import {
-        importable: Expression,
-        extract: Option<MutableExtract>,
+        after_import: Whitespace0,
+        path: Located<ImportPath>,
+        extract: Option<(Whitespace1, Whitespace1, MutableExtract)>,
+        using: Option<Using>,
+        // only optional when the import ends with an extract
+        semi: Option<Whitespace0>,
     },
+    // - {attributes}{visibility}mod{after_mod}{name}{after_name}{statements}
     Module {
+        attributes: Vec<AttributeList>,
+        visibility: Option<Whitespace1>,
+        after_mod: Whitespace0,
         name: String,
-        statements: Vec<Statement>,
+        after_name: Whitespace0,
+        statements: Block,
+        semi: Option<Whitespace0>,
     },
-    Env(Expression),
+    // - use_env{after_use_env}{expr}{semi};
+    Env {
+        after_use_env: Whitespace0,
+        expr: Located<Expression>,
+        semi: Whitespace0,
+    },
+    // source that could not be parsed, kept verbatim so that a partially parsed file stays lossless
+    Error(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `#[{attributes}]{post_space}`, attributes are written in front of items
+pub struct AttributeList {
+    pub attributes: List<Located<Attribute>>,
+    pub post_space: Whitespace0,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{name}` or `{name}{pre_arguments}({arguments})`, such as `cte` or `requires_env(Console)`
+pub struct Attribute {
+    pub name: String,
+    pub arguments: Option<(Whitespace0, List<Located<Expression>>)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{pre_space}<{params}>`, the generic parameters of an item such as `struct Pair<A, B: Clone>`
+pub struct GenericParameters {
+    pub pre_space: Whitespace0,
+    pub params: List<GenericParameter>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{name}` or `{name}{bound}`, the bound being written like a type annotation
+pub struct GenericParameter {
+    pub name: String,
+    pub bound: Option<TypeAnnotation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Closure {
     pub closure_signature: ClosureSignature,
-    pub body: Expression,
+    pub separator: BodySeparator,
+    pub body: Located<Expression>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/// What encloses the parameters of a closure
+pub enum ParameterDelimiter {
+    /// `(x, y) -> x + y`
+    Parentheses,
+    /// `|x, y| x + y`, the signature is followed by `BodySeparator::Space`
+    Pipes,
+    /// `x, y -> x + y`, the short form without delimiters
+    None,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// What separates the signature of a closure from its body
+pub enum BodySeparator {
+    /// `fn f(){space}{ ... }`
+    Space(Whitespace0),
+    /// `(a){space}->{space}a`, a closure without a return type
+    Arrow(Whitespace0, Whitespace0),
+    /// `(a) -> A{space1}do{space1}a`
+    Do(Whitespace1, Whitespace1),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `({receiver}{params})`, a comma separates the receiver from the parameters following it
 pub struct ClosureSignature {
-    pub params: Vec<(VariableCreation, Option<Expression>)>,
-    pub return_type: Option<Expression>,
+    /// Makes a function declared in an `impl` block or a trait a method. The parser accepts a
+    /// receiver on any function, also on top level ones such as `fn f(self) {}`.
+    pub receiver: Option<Spaced<Receiver>>,
+    pub params: List<Parameter>,
+    pub return_type: Option<ReturnType>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// The `self` parameter of a method
+pub enum Receiver {
+    /// `self`
+    Value,
+    /// `&{space}self`
+    Reference(Whitespace0),
+    /// `&{after_ampersand}mut{after_mut}self`
+    MutableReference(Whitespace0, Whitespace1),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{creation}` or `{creation}{type_annotation}`
+pub struct Parameter {
+    pub creation: Located<VariableCreation>,
+    pub value_type: Option<TypeAnnotation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{pre_colon}:{post_colon}{value_type}`
+pub struct TypeAnnotation {
+    pub pre_colon: Whitespace0,
+    pub post_colon: Whitespace0,
+    pub value_type: Box<Located<Expression>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{pre_arrow}->{post_arrow}{value_type}`
+pub struct ReturnType {
+    pub pre_arrow: Whitespace0,
+    pub post_arrow: Whitespace0,
+    pub value_type: Located<Expression>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{pre_assignment}={post_assignment}{value}`
+pub struct Initializer {
+    pub pre_assignment: Whitespace0,
+    pub post_assignment: Whitespace0,
+    pub value: Box<Located<Expression>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{name}{pre_colon}:{post_colon}{value}`, a field of a struct declaration or literal
+pub struct Field {
+    pub name: String,
+    pub pre_colon: Whitespace0,
+    pub post_colon: Whitespace0,
+    pub value: Located<Expression>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `pub{visibility}{field}` or `{field}`, a field of a struct declaration
+pub struct StructField {
+    pub visibility: Option<Whitespace1>,
+    pub field: Field,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{pre_else}else{post_else}{value}`
+pub struct Else<T> {
+    pub pre_else: Whitespace0,
+    pub post_else: Whitespace0,
+    pub value: T,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `if{after_if}{condition}{before_block}{block}`
+pub struct IfBlock {
+    pub after_if: Whitespace0,
+    pub condition: Box<Located<Expression>>,
+    pub before_block: Whitespace0,
+    pub block: Block,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{unsafety}fn{after_fn}{name}{generics}{after_name}{closure_signature}`
 pub struct FunctionSignature {
+    pub unsafety: Option<Whitespace1>,
     pub after_fn: Whitespace1,
     pub name: String,
+    pub generics: Option<GenericParameters>,
     pub after_name: Whitespace0,
     pub closure_signature: ClosureSignature,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Signature {
-    Function(FunctionSignature),
+    Function(Box<FunctionSignature>),
     Property(PropertySignature),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct PropertySignature {
-    pub mutable: bool,
+    // the space after `mut` for mutable properties
+    pub mutable: Option<Whitespace0>,
     pub name: String,
-    pub value_type: Expression,
+    pub value_type: TypeAnnotation,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{relative}{segments}`, such as `std.lang.rust.{rust_std, rs}` or `./*`
+pub struct ImportPath {
+    pub relative: RelativeImport,
+    // the segments separated by dots, with the space around each dot
+    // only the last segment can be a glob or a list
+    pub segments: Vec<Spaced<ImportSegment>>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum CallArguments {
-    Named(Vec<(String, Expression)>),
-    Positional(Vec<Expression>),
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum RelativeImport {
+    /// `std.error`, a path from the root of a package
+    None,
+    /// `./`, a path from the directory of the importing file
+    Current,
+    /// `../../`, a path from the given number of directories above the importing file
+    Parent(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum ImportSegment {
+    /// `error`, a module
+    Name(String),
+    /// `*`, every module
+    Glob,
+    /// `{a, b}`, the listed modules
+    List(List<String>),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{pre_using}using{post_using}{schema}`, every imported module must export what the schema
+/// describes
+pub struct Using {
+    pub pre_using: Whitespace1,
+    pub post_using: Whitespace1,
+    pub schema: Located<Expression>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// A field of a struct literal
+pub enum StructLiteralField {
+    /// `{name}{pre_colon}:{post_colon}{value}`
+    Field(Field),
+    /// `x`, short for `x: x`
+    Shorthand(Located<String>),
+    /// `..{after_dots}{base}`, the fields that are not listed are taken from `base`
+    Spread {
+        after_dots: Whitespace0,
+        base: Located<Expression>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Expression {
     Literal {
         value: Literal,
     },
+    /// "sum: { a + b }"
+    InterpolatedString {
+        // `"` or `'`
+        quote: char,
+        parts: Vec<InterpolatedPart>,
+    },
     Variable {
         identifier: String,
     },
+    /// (a + b)
+    Parenthesized {
+        pre_space: Whitespace0,
+        expr: Box<Located<Expression>>,
+        post_space: Whitespace0,
+    },
+    /// -a, a?
     SingleOperation {
         operation: UnaryOperation,
-        operand: Box<Expression>,
+        // the space between the operator and the operand
+        space: Whitespace0,
+        operand: Box<Located<Expression>>,
     },
     /// a + b
     Operation {
-        left: Box<Expression>,
+        left: Box<Located<Expression>>,
+        left_space: Whitespace0,
         operator: Operator,
-        right: Box<Expression>,
+        right_space: Whitespace0,
+        right: Box<Located<Expression>>,
     },
     /// [1, 2, 3]
     Array {
-        elements: Vec<Expression>,
+        elements: List<Located<Expression>>,
+    },
+    /// Point { x: 1, y }, Point { x: 1, ..origin }
+    StructLiteral {
+        // the struct that is created, `Point` or `geometry.Point`, none for an anonymous struct
+        // such as `{ x: 1 }`
+        name: Option<Box<Located<Expression>>>,
+        pre_brace: Whitespace0,
+        fields: List<StructLiteralField>,
     },
     /// let x = 1; let mut x = 1;
     ///
@@ -177,48 +550,160 @@ pub enum Expression {
     ///     do_something();
     /// }
     Declaration {
-        creation: VariableCreation,
-        value_type: Option<Box<Expression>>,
-        initializer: Option<Box<Expression>>,
+        after_let: Whitespace0,
+        creation: Located<VariableCreation>,
+        value_type: Option<TypeAnnotation>,
+        initializer: Option<Initializer>,
     },
     /// let f = x -> x + 1;
     /// let f = (x, y) -> x + y;
     /// let f = (x: U64, y: U64) -> U64 do { x + y };
     /// let f = x -> U64 do x + 1;
-    Closure
+    /// let f = |x, y| x + y;
+    Closure {
+        closure: Box<Closure>,
+        delimiter: ParameterDelimiter,
+    },
     /// { stat1; stat2; stat3 }
     /// scope_expr: { stat1; stat2; stat3 }
     Block {
-        environment: Option<Box<Expression>>,
-        block: Vec<Statement>,
+        environment: Option<Box<Located<Expression>>>,
+        block: Block,
+    },
+    /// unsafe { stat1; stat2 }
+    Unsafe {
+        after_unsafe: Whitespace0,
+        block: Block,
+    },
+    /// const { stat1; stat2 }, evaluated at compile time
+    Const {
+        after_const: Whitespace0,
+        block: Block,
+    },
+    /// rs { println!("{}", a) }, code of another language kept as written
+    Foreign {
+        source: String,
+    },
+    /// match a { Some(b) if b > 0 => b, _ => 0 }
+    Match {
+        after_match: Whitespace0,
+        scrutinee: Box<Located<Expression>>,
+        before_arms: Whitespace0,
+        arms: List<Located<MatchArm>>,
     },
+    /// if a { b } else if c { d } else { e }
     If {
-        blocks: Vec<(Expression, Vec<Statement>)>, // multiple blocks occur when using the `else if` syntax
-        else_block: Option<Vec<Statement>>,
+        if_block: IfBlock,
+        else_if_blocks: Vec<Else<IfBlock>>,
+        else_block: Option<Else<Block>>,
     },
+    /// while x < 5 { x = x + 1 }
+    /// while x < 5 do x = x + 1
     While {
-        label: Option<String>,
-        condition: Box<Expression>,
-        body: Box<Expression>,
-        else_block: Option<Box<Expression>>,
+        // the label and the space after it
+        label: Option<(String, Whitespace0)>,
+        after_while: Whitespace0,
+        condition: Box<Located<Expression>>,
+        before_body: Whitespace0,
+        // the space after `do` when the body is not a block
+        do_body: Option<Whitespace0>,
+        body: Box<Located<Expression>>,
+        else_block: Option<Else<Box<Located<Expression>>>>,
     },
     Loop {
-        label: Option<String>,
-        body: Box<Expression>,
+        label: Option<(String, Whitespace0)>,
+        after_loop: Whitespace0,
+        body: Box<Located<Expression>>,
     },
     For {
-        label: Option<String>,
+        label: Option<(String, Whitespace0)>,
+        after_for: Whitespace0,
         destructure: MutableDestructure,
-        iterator: Box<Expression>,
-        body: Box<Expression>,
-        else_block: Option<Box<Expression>>,
+        before_in: Whitespace0,
+        after_in: Whitespace0,
+        iterator: Box<Located<Expression>>,
+        before_body: Whitespace0,
+        do_body: Option<Whitespace0>,
+        body: Box<Located<Expression>>,
+        else_block: Option<Else<Box<Located<Expression>>>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{pattern}{guard}{pre_arrow}=>{post_arrow}{body}`
+pub struct MatchArm {
+    pub pattern: Located<Pattern>,
+    pub guard: Option<Guard>,
+    pub pre_arrow: Whitespace0,
+    pub post_arrow: Whitespace0,
+    pub body: Located<Expression>,
+    // an arm whose body ends with a block can be followed by the next arm without a comma
+    pub omits_comma: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{pre_if}if{after_if}{condition}`, an arm only matches when its guard holds
+pub struct Guard {
+    pub pre_if: Whitespace0,
+    pub after_if: Whitespace0,
+    pub condition: Located<Expression>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum Pattern {
+    /// `_`
+    Wildcard,
+    /// `a` or `mut a`, binds the matched value to a name
+    Binding {
+        // the space after `mut`
+        mutable: Option<Whitespace1>,
+        name: String,
+    },
+    /// `1`, `"a"` or `true`
+    Literal(Literal),
+    /// `{start}{pre_dots}..{post_dots}{end}`, the end is excluded as in `for` loops
+    Range {
+        start: Literal,
+        pre_dots: Whitespace0,
+        post_dots: Whitespace0,
+        end: Literal,
+    },
+    /// `Color.Red`, `Some(a)` or `Shape.Circle { radius }`
+    ///
+    /// A single name without values is a binding, so unit variants are written with their enum.
+    Variant {
+        // the names separated by dots, with the space around each dot
+        path: Vec<Spaced<String>>,
+        values: VariantPattern,
     },
+    /// `a | b`, each alternative keeps the space up to the bars around it
+    Alternatives(Vec<Spaced<Located<Pattern>>>),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// The values of an enum variant in a pattern, `space` is the space in front of the delimiter
+pub enum VariantPattern {
+    Tuple {
+        space: Whitespace0,
+        values: List<Located<Pattern>>,
+    },
+    Struct {
+        space: Whitespace0,
+        fields: MutableDestructure,
+    },
+    Unit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum VariableCreation {
-    Identifier { name: String, mutable: bool },
-    Destructure { destructure: MutableDestructure },
+    // `mutable` holds the space after `mut`
+    Identifier {
+        name: String,
+        mutable: Option<Whitespace1>,
+    },
+    Destructure {
+        destructure: MutableDestructure,
+    },
 }
 
 /// This is a destructure
@@ -230,55 +715,73 @@ pub enum VariableCreation {
 /// { a.{b, mut c} }
 /// { a.{b.f as e, c} as mut d }
 ///
-pub type MutableDestructure = Vec<MutableDestructureProperty>;
-pub type ImmutableDestructure = Vec<ImmutableDestructureProperty>;
+pub type MutableDestructure = List<Located<MutableDestructureProperty>>;
+pub type ImmutableDestructure = List<Located<ImmutableDestructureProperty>>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{pre_as}as{post_as}{alias}`
+pub struct Alias<T> {
+    pub pre_as: Whitespace0,
+    pub post_as: Whitespace0,
+    pub alias: T,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// `{pre_dot}.{post_dot}{extract}`
+pub struct SubExtract<T> {
+    pub pre_dot: Whitespace0,
+    pub post_dot: Whitespace0,
+    pub extract: T,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum MutableDestructureProperty {
     AliasedSubProperties {
         property_name: String,
-        extract: ImmutableExtract,
-        alias: MutableAlias,
+        extract: SubExtract<ImmutableExtract>,
+        alias: Alias<MutableAlias>,
     },
     Property {
         property_name: String,
-        alias: Option<MutableAlias>,
+        alias: Option<Alias<MutableAlias>>,
     },
     UnaliasedSubProperties {
         property_name: String,
-        extract: MutableExtract,
+        extract: SubExtract<MutableExtract>,
     },
+    /// mut{space}a.b.c
     MutablePropertyChain {
-        property_chain: Vec<String>,
+        property_chain: Vec<Spaced<String>>,
     },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MutableAlias {
-    pub mutable: bool,
+    // the space after `mut`
+    pub mutable: Option<Whitespace0>,
     pub alias: String,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ImmutableDestructureProperty {
     pub property_name: String,
-    pub extract: Option<ImmutableExtract>,
-    pub alias: Option<String>,
+    pub extract: Option<SubExtract<ImmutableExtract>>,
+    pub alias: Option<Alias<String>>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum MutableExtract {
     Destructured(MutableDestructure),
-    DirectProperty(Box<MutableDestructureProperty>),
+    DirectProperty(Box<Located<MutableDestructureProperty>>),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum ImmutableExtract {
     Destructured(ImmutableDestructure),
-    DirectProperty(Box<ImmutableDestructureProperty>),
+    DirectProperty(Box<Located<ImmutableDestructureProperty>>),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum UnaryOperation {
     Not,
     ErrorUnwrap,
@@ -291,31 +794,198 @@ pub enum UnaryOperation {
     },
     Dereference,
     /// f(a, b)
-    /// f { a, b }
     Call {
-        arguments: CallArguments,
+        arguments: List<Located<Expression>>,
+    },
+    /// Option<T>, only in type positions where `<` is not a comparison
+    GenericArguments {
+        arguments: List<Located<Expression>>,
     },
     /// a[b]
     Get {
-        property: Box<Expression>,
+        pre_space: Whitespace0,
+        property: Box<Located<Expression>>,
+        post_space: Whitespace0,
+    },
+    /// T[], the type of an array of T
+    ArrayOf {
+        // the space between the brackets
+        space: Whitespace0,
     },
     /// a.{b, c}
     /// a.{b as c, d as e}
     /// a.{b}
     /// a.b
     Extract {
+        // the space after the dot
+        dot_space: Whitespace0,
         extract: ImmutableExtract,
     },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl UnaryOperation {
+    /// Whether the operator is written in front of its operand
+    pub fn is_prefix(&self) -> bool {
+        match self {
+            UnaryOperation::Not
+            | UnaryOperation::Spread
+            | UnaryOperation::Negate
+            | UnaryOperation::Positive
+            | UnaryOperation::Reference { .. }
+            | UnaryOperation::Dereference => true,
+            UnaryOperation::ErrorUnwrap
+            | UnaryOperation::Inline
+            | UnaryOperation::Call { .. }
+            | UnaryOperation::GenericArguments { .. }
+            | UnaryOperation::Get { .. }
+            | UnaryOperation::ArrayOf { .. }
+            | UnaryOperation::Extract { .. } => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Literal {
-    Number(String),
-    String(String),
+    Number(NumberLiteral),
+    String(StringLiteral),
+    // raw strings have no escapes, their value is the text between the quotes
+    RawString(StringLiteral),
     Boolean(bool),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// A string literal such as `"tab\tseparated"`
+pub struct StringLiteral {
+    // the literal as written in the source, including the quotes and escapes
+    pub text: String,
+    // the contents with all escapes decoded
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum InterpolatedPart {
+    Text {
+        // the text as written in the source, including escapes
+        text: String,
+        value: String,
+    },
+    /// { expression }, the braces can be escaped as `\{` and `\}` in the text
+    Expression(Box<Spaced<Located<Expression>>>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+/// A number literal such as `0xFF`, `1_000u32` or `2.5e-3`
+pub struct NumberLiteral {
+    // the literal as written in the source
+    pub text: String,
+    pub value: Number,
+    pub suffix: Option<NumberSuffix>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+/// The type suffix of a number literal, `10u8`
+pub enum NumberSuffix {
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+    F32,
+    F64,
+    /// `20px`, a length in pixels for the ui, an integer like `isize`
+    Px,
+}
+
+impl NumberSuffix {
+    pub const ALL: [NumberSuffix; 15] = [
+        NumberSuffix::U8,
+        NumberSuffix::U16,
+        NumberSuffix::U32,
+        NumberSuffix::U64,
+        NumberSuffix::U128,
+        NumberSuffix::Usize,
+        NumberSuffix::I8,
+        NumberSuffix::I16,
+        NumberSuffix::I32,
+        NumberSuffix::I64,
+        NumberSuffix::I128,
+        NumberSuffix::Isize,
+        NumberSuffix::F32,
+        NumberSuffix::F64,
+        NumberSuffix::Px,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumberSuffix::U8 => "u8",
+            NumberSuffix::U16 => "u16",
+            NumberSuffix::U32 => "u32",
+            NumberSuffix::U64 => "u64",
+            NumberSuffix::U128 => "u128",
+            NumberSuffix::Usize => "usize",
+            NumberSuffix::I8 => "i8",
+            NumberSuffix::I16 => "i16",
+            NumberSuffix::I32 => "i32",
+            NumberSuffix::I64 => "i64",
+            NumberSuffix::I128 => "i128",
+            NumberSuffix::Isize => "isize",
+            NumberSuffix::F32 => "f32",
+            NumberSuffix::F64 => "f64",
+            NumberSuffix::Px => "px",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<NumberSuffix> {
+        NumberSuffix::ALL
+            .into_iter()
+            .find(|suffix| suffix.name() == name)
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, NumberSuffix::F32 | NumberSuffix::F64)
+    }
+
+    /// The number of bits of an integer type, sizes are 64 bits
+    pub fn integer_bits(&self) -> Option<u32> {
+        match self {
+            NumberSuffix::U8 | NumberSuffix::I8 => Some(8),
+            NumberSuffix::U16 | NumberSuffix::I16 => Some(16),
+            NumberSuffix::U32 | NumberSuffix::I32 => Some(32),
+            NumberSuffix::U64 | NumberSuffix::I64 => Some(64),
+            NumberSuffix::Usize | NumberSuffix::Isize | NumberSuffix::Px => Some(64),
+            NumberSuffix::U128 | NumberSuffix::I128 => Some(128),
+            NumberSuffix::F32 | NumberSuffix::F64 => None,
+        }
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            NumberSuffix::I8
+                | NumberSuffix::I16
+                | NumberSuffix::I32
+                | NumberSuffix::I64
+                | NumberSuffix::I128
+                | NumberSuffix::Isize
+                | NumberSuffix::Px
+        )
+    }
+}
+
+impl Display for NumberSuffix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Number {
     Integer(BigInt),
     Float(f64),
@@ -324,8 +994,8 @@ pub enum Number {
 impl Display for Literal {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            Literal::Number(number) => write!(f, "{}", number),
-            Literal::String(string) => write!(f, "{}", string),
+            Literal::Number(number) => write!(f, "{}", number.text),
+            Literal::String(string) | Literal::RawString(string) => write!(f, "{}", string.text),
             Literal::Boolean(boolean) => write!(f, "{}", boolean),
         }
     }
@@ -340,7 +1010,7 @@ impl Display for Number {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum Operator {
     Assignment,          // =
     Range,               // 1..10
@@ -368,16 +1038,24 @@ pub enum Operator {
 }
 
 pub type Mutable = bool;
+
 pub type EnumOption = (String, EnumValue);
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum EnumValue {
-    Tuple(Vec<Expression>),
-    Struct(Vec<(String, Expression)>),
+    // `space` is the space between the option name and the opening delimiter
+    Tuple {
+        space: Whitespace0,
+        values: List<Located<Expression>>,
+    },
+    Struct {
+        space: Whitespace0,
+        fields: List<Field>,
+    },
     Unit,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum TypeLiteral {
     Number(Number),
     String(String),
@@ -385,7 +1063,7 @@ pub enum TypeLiteral {
     None,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum SpaceElement {
     LineComment(String),
     BlockComment(String),
@@ -395,8 +1073,8 @@ pub enum SpaceElement {
 pub type Whitespace0 = Vec<SpaceElement>;
 pub type Whitespace1 = Vec1<SpaceElement>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct File {
     pub pre_space: Whitespace0,
-    pub statements: Vec<(Statement, Whitespace0)>,
+    pub statements: Vec<(Located<Statement>, Whitespace0)>,
 }
@@ -0,0 +1,61 @@
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+/// The files a project is loaded from
+pub trait FileSystem {
+    fn read(&self, path: &Path) -> io::Result<String>;
+
+    fn is_file(&self, path: &Path) -> bool;
+
+    /// The files and directories directly inside of a directory
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+}
+
+/// The file system of the operating system
+pub struct OsFileSystem;
+
+impl FileSystem for OsFileSystem {
+    fn read(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        std::fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect()
+    }
+}
+
+/// Removes the `.` and `..` components of a path without touching the file system, so every
+/// file has a single path no matter how it is imported
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(Path::new("a/./b/../c.qp")), Path::new("a/c.qp"));
+        assert_eq!(normalize(Path::new("/a/b/../../c")), Path::new("/c"));
+        assert_eq!(normalize(Path::new("../a/..")), Path::new(".."));
+    }
+}
@@ -0,0 +1,202 @@
+//! Builds the module graph of a project, starting from an entry file and following its imports.
+//!
+//! Import paths are resolved against the directory of the entry file, or against the `std/`
+//! tree for paths starting with `std`. A module is either a `name.qp` file or a directory with a
+//! `mod.qp` file, and every file is parsed once no matter how often it is imported.
+//!
+//! The exports of `std/prelude.qp` are in scope in every module without an import.
+//!
+//! `import ./* using doc;` checks that every imported module exports the fields of the `export`
+//! struct of `doc`, a `let doc = _ { export: { name: String } };` next to the import.
+
+mod files;
+mod load;
+
+use std::{ops::Index, path::PathBuf};
+
+use fst::{Expression, File, Located, SourceSpan, Statement, VariableCreation};
+use thiserror::Error;
+
+pub use files::{normalize, FileSystem, OsFileSystem};
+pub use load::load_project;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum LoadError {
+    #[error("module `{0}` not found")]
+    ModuleNotFound(String),
+    #[error("`{item}` is not exported by `{module}`")]
+    NotExported { module: String, item: String },
+    #[error("`{module}` does not export `{item}`, which the schema `{schema}` requires")]
+    MissingExport {
+        module: String,
+        item: String,
+        schema: String,
+    },
+    #[error("`using` takes the name of a schema declared next to the import")]
+    InvalidSchema,
+    #[error(
+        "`{0}` is not a schema, expected `let {0} = _ {{ export: {{ ... }} }}` next to the import"
+    )]
+    UnknownSchema(String),
+    #[error("import cycle {}", .0.join(" -> "))]
+    ImportCycle(Vec<String>),
+}
+
+pub type LocatedLoadError = Located<LoadError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModuleId(usize);
+
+/// What a single name of an import refers to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Imported {
+    /// `import std.env.console;` or each module of `import ./*;`
+    Module(ModuleId),
+    /// `import std.env.console.println;`, the first name after the module, which is exported by it
+    Item { module: ModuleId, name: String },
+    /// `import lib.*;`, all exports of a module
+    Exports(ModuleId),
+}
+
+#[derive(Debug, Clone)]
+pub struct Import {
+    /// The location of the import path
+    pub source_span: SourceSpan,
+    pub imported: Vec<Imported>,
+}
+
+#[derive(Debug)]
+pub struct Module {
+    pub path: PathBuf,
+    /// The dotted name of the module relative to the project root, or `std.…` for std modules
+    pub name: String,
+    /// The parsed file, or the error message if it could not be read or parsed
+    pub file: Result<File, String>,
+    /// The resolved imports, in the order they are written, including those in `mod` blocks
+    pub imports: Vec<Import>,
+    pub errors: Vec<LocatedLoadError>,
+}
+
+impl Module {
+    /// The names of the public items declared at the top level of the module
+    pub fn exports(&self) -> Vec<&str> {
+        let Ok(file) = &self.file else {
+            return Vec::new();
+        };
+        file.statements
+            .iter()
+            .filter_map(|(statement, _)| match &statement.value {
+                Statement::Function {
+                    visibility: Some(_),
+                    name,
+                    ..
+                }
+                | Statement::Struct {
+                    visibility: Some(_),
+                    name,
+                    ..
+                }
+                | Statement::Enum {
+                    visibility: Some(_),
+                    name,
+                    ..
+                }
+                | Statement::TypeAlias {
+                    visibility: Some(_),
+                    name,
+                    ..
+                }
+                | Statement::Trait {
+                    visibility: Some(_),
+                    name,
+                    ..
+                }
+                | Statement::Module {
+                    visibility: Some(_),
+                    name,
+                    ..
+                } => Some(name.as_str()),
+                Statement::Expression {
+                    visibility: Some(_),
+                    expr,
+                    ..
+                } => match &expr.value {
+                    Expression::Declaration { creation, .. } => match &creation.value {
+                        VariableCreation::Identifier { name, .. } => Some(name.as_str()),
+                        VariableCreation::Destructure { .. } => None,
+                    },
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The modules this module imports, a module imported more than once is listed once
+    pub fn dependencies(&self) -> Vec<ModuleId> {
+        let mut dependencies = Vec::new();
+        for imported in self.imports.iter().flat_map(|import| &import.imported) {
+            let id = match imported {
+                Imported::Module(id) | Imported::Exports(id) => *id,
+                Imported::Item { module, .. } => *module,
+            };
+            if !dependencies.contains(&id) {
+                dependencies.push(id);
+            }
+        }
+        dependencies
+    }
+}
+
+#[derive(Debug)]
+pub struct ModuleGraph {
+    /// The modules in the order they are first reached, the entry module is always first
+    pub modules: Vec<Module>,
+    pub entry: ModuleId,
+    /// `std.prelude`, `None` if the std tree has no prelude
+    pub prelude: Option<ModuleId>,
+}
+
+impl ModuleGraph {
+    pub fn ids(&self) -> impl Iterator<Item = ModuleId> {
+        (0..self.modules.len()).map(ModuleId)
+    }
+
+    /// The names an import brings into scope, a module by the last part of its name
+    pub fn imported_names(&self, import: &Import) -> Vec<String> {
+        let mut names = Vec::new();
+        for imported in &import.imported {
+            match imported {
+                Imported::Module(id) => {
+                    let name = &self[*id].name;
+                    names.push(name.rsplit('.').next().unwrap_or(name).to_string());
+                }
+                Imported::Item { name, .. } => names.push(name.clone()),
+                Imported::Exports(id) => {
+                    names.extend(self[*id].exports().into_iter().map(str::to_string))
+                }
+            }
+        }
+        names
+    }
+
+    /// The names of the prelude that are in scope in a module, none in the prelude itself
+    pub fn prelude_names(&self, module: ModuleId) -> Vec<String> {
+        match self.prelude {
+            Some(prelude) if prelude != module => self[prelude]
+                .exports()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Index<ModuleId> for ModuleGraph {
+    type Output = Module;
+
+    fn index(&self, id: ModuleId) -> &Module {
+        &self.modules[id.0]
+    }
+}
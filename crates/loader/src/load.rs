@@ -0,0 +1,780 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use fst::{
+    Expression, ImportPath, ImportSegment, Located, RelativeImport, Statement, StructLiteralField,
+    VariableCreation, Whitespace0,
+};
+use parser::simple_parse;
+
+use crate::{
+    files::{normalize, FileSystem},
+    Import, Imported, LoadError, LocatedLoadError, Module, ModuleGraph, ModuleId,
+};
+
+/// Loads the entry file and every module it imports, directly or indirectly.
+///
+/// Errors do not stop the loading, they are stored with the module they occur in: files that
+/// cannot be read or parsed keep their message in [`Module::file`], imports that cannot be
+/// resolved, schemas that are not recognized, imported modules missing an export of their schema
+/// and import cycles end up in [`Module::errors`].
+pub fn load_project(files: &impl FileSystem, entry: &Path, std_root: &Path) -> ModuleGraph {
+    let entry = normalize(entry);
+    let mut loader = Loader {
+        files,
+        project_root: entry.parent().map(Path::to_path_buf).unwrap_or_default(),
+        std_root: normalize(std_root),
+        modules: Vec::new(),
+        ids: HashMap::new(),
+    };
+    let entry = loader.load(entry);
+    let prelude = loader.std_root.join("prelude.qp");
+    let prelude = files.is_file(&prelude).then(|| loader.load(prelude));
+    let mut graph = ModuleGraph {
+        modules: loader.modules,
+        entry,
+        prelude,
+    };
+    report_cycles(&mut graph);
+    graph
+}
+
+struct Loader<'a, F: FileSystem> {
+    files: &'a F,
+    project_root: PathBuf,
+    std_root: PathBuf,
+    modules: Vec<Module>,
+    ids: HashMap<PathBuf, ModuleId>,
+}
+
+/// An import of a file and the schema of its `using` clause
+struct ImportStatement {
+    path: Located<ImportPath>,
+    schema: Option<Result<Schema, LocatedLoadError>>,
+}
+
+/// The names every module imported with a schema has to export
+struct Schema {
+    name: String,
+    exports: Vec<String>,
+}
+
+/// Where an import path starts and the names that follow
+struct Base<'p> {
+    directory: PathBuf,
+    names: Vec<&'p str>,
+    // how many names have to be part of the module path, `std` itself is a module but the
+    // project root is not
+    min_names: usize,
+}
+
+impl<'a, F: FileSystem> Loader<'a, F> {
+    fn load(&mut self, path: PathBuf) -> ModuleId {
+        if let Some(id) = self.ids.get(&path) {
+            return *id;
+        }
+        let file = self
+            .files
+            .read(&path)
+            .map_err(|error| format!("could not read {}: {}", path.display(), error))
+            .and_then(|code| simple_parse(&code));
+        let id = ModuleId(self.modules.len());
+        self.ids.insert(path.clone(), id);
+
+        let mut import_paths = Vec::new();
+        if let Ok(file) = &file {
+            collect_imports(&file.statements, &mut import_paths);
+        }
+        self.modules.push(Module {
+            name: self.module_name(&path),
+            path,
+            file,
+            imports: Vec::new(),
+            errors: Vec::new(),
+        });
+
+        for ImportStatement { path, schema } in import_paths {
+            let imported = self.resolve(id, &path.value);
+            let imported = imported.unwrap_or_else(|error| {
+                self.modules[id.0]
+                    .errors
+                    .push(Located::new(error, path.source_span));
+                Vec::new()
+            });
+            match schema {
+                Some(Ok(schema)) => {
+                    let errors = self.check_schema(&imported, &schema);
+                    self.modules[id.0].errors.extend(
+                        errors
+                            .into_iter()
+                            .map(|error| Located::new(error, path.source_span)),
+                    );
+                }
+                Some(Err(error)) => self.modules[id.0].errors.push(error),
+                None => {}
+            }
+            self.modules[id.0].imports.push(Import {
+                source_span: path.source_span,
+                imported,
+            });
+        }
+        id
+    }
+
+    /// `a/b/c.qp` is `a.b.c` and `a/b/mod.qp` is `a.b`, relative to the project root or to the
+    /// directory containing the std root
+    fn module_name(&self, path: &Path) -> String {
+        let std_parent = self.std_root.parent().unwrap_or(Path::new(""));
+        let relative = path
+            .strip_prefix(&self.std_root)
+            .ok()
+            .and_then(|_| path.strip_prefix(std_parent).ok())
+            .or_else(|| path.strip_prefix(&self.project_root).ok());
+        let Some(relative) = relative else {
+            return path.display().to_string();
+        };
+        let mut names: Vec<String> = relative
+            .with_extension("")
+            .iter()
+            .map(|name| name.to_string_lossy().into_owned())
+            .collect();
+        if names.len() > 1 && names.last().is_some_and(|name| name == "mod") {
+            names.pop();
+        }
+        names.join(".")
+    }
+
+    /// The file of the module at `path`, either `path.qp` or `path/mod.qp`
+    fn module_file(&self, path: &Path) -> Option<PathBuf> {
+        let file = path.with_extension("qp");
+        if path.file_name().is_some() && self.files.is_file(&file) {
+            return Some(file);
+        }
+        let file = path.join("mod.qp");
+        self.files.is_file(&file).then_some(file)
+    }
+
+    fn base<'p>(&self, importer: ModuleId, path: &'p ImportPath) -> Base<'p> {
+        let mut names: Vec<&str> = path
+            .segments
+            .iter()
+            .filter_map(|segment| match &segment.value {
+                ImportSegment::Name(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        let importer_directory = || {
+            let importer = &self.modules[importer.0].path;
+            importer.parent().map(Path::to_path_buf).unwrap_or_default()
+        };
+        let directory = match path.relative {
+            RelativeImport::Current => importer_directory(),
+            RelativeImport::Parent(parents) => {
+                normalize(&importer_directory().join("../".repeat(parents)))
+            }
+            RelativeImport::None if names.first() == Some(&"std") => {
+                names.remove(0);
+                return Base {
+                    directory: self.std_root.clone(),
+                    names,
+                    min_names: 0,
+                };
+            }
+            RelativeImport::None => self.project_root.clone(),
+        };
+        Base {
+            directory,
+            names,
+            min_names: 1,
+        }
+    }
+
+    fn resolve(
+        &mut self,
+        importer: ModuleId,
+        path: &ImportPath,
+    ) -> Result<Vec<Imported>, LoadError> {
+        let base = self.base(importer, path);
+        let directory = base
+            .names
+            .iter()
+            .fold(base.directory.clone(), |directory, name| {
+                directory.join(name)
+            });
+        match path.segments.last().map(|segment| &segment.value) {
+            Some(ImportSegment::Glob) => match self.files.read_dir(&directory) {
+                Ok(entries) => Ok(self.directory_modules(importer, entries)),
+                Err(_) => {
+                    let (module, items) = self.find_module(&base, path)?;
+                    match items.first() {
+                        Some(_) => Ok(vec![self.item(module, &items, path)?]),
+                        None => Ok(vec![Imported::Exports(module)]),
+                    }
+                }
+            },
+            // each name of the list is a module in the directory of the path or an item of the
+            // module at the path
+            Some(ImportSegment::List(names)) => {
+                let mut module = None;
+                let mut imported = Vec::new();
+                for name in names.values() {
+                    if let Some(file) = self.module_file(&directory.join(name)) {
+                        imported.push(Imported::Module(self.load(file)));
+                        continue;
+                    }
+                    let (module, items) = match &module {
+                        Some(module) => module,
+                        None => module.insert(self.find_module(&base, path)?),
+                    };
+                    let mut items = items.clone();
+                    items.push(name);
+                    imported.push(self.item(*module, &items, path)?);
+                }
+                Ok(imported)
+            }
+            _ => {
+                let (module, items) = self.find_module(&base, path)?;
+                match items.first() {
+                    Some(_) => Ok(vec![self.item(module, &items, path)?]),
+                    None => Ok(vec![Imported::Module(module)]),
+                }
+            }
+        }
+    }
+
+    /// The longest prefix of the names that is a module, the rest are items of that module
+    fn find_module<'p>(
+        &mut self,
+        base: &Base<'p>,
+        path: &ImportPath,
+    ) -> Result<(ModuleId, Vec<&'p str>), LoadError> {
+        let (file, items) = (base.min_names..=base.names.len())
+            .rev()
+            .find_map(|length| {
+                let path = base.names[..length]
+                    .iter()
+                    .fold(base.directory.clone(), |directory, name| {
+                        directory.join(name)
+                    });
+                let file = self.module_file(&path)?;
+                Some((file, base.names[length..].to_vec()))
+            })
+            .ok_or_else(|| LoadError::ModuleNotFound(self.display_path(path)))?;
+        Ok((self.load(file), items))
+    }
+
+    /// The first of the items, which has to be exported by the module. The items after it are
+    /// not checked.
+    fn item(
+        &self,
+        module: ModuleId,
+        items: &[&str],
+        path: &ImportPath,
+    ) -> Result<Imported, LoadError> {
+        let name = items[0];
+        if let Err(error) = self.check_exported(module, name) {
+            // `a.b.c` with `b` not exported by `a` is most likely a path to a missing module
+            return Err(match items.len() {
+                1 => error,
+                _ => LoadError::ModuleNotFound(self.display_path(path)),
+            });
+        }
+        Ok(Imported::Item {
+            module,
+            name: name.to_string(),
+        })
+    }
+
+    /// The `.qp` files of a directory and its subdirectories with a `mod.qp` file, other than
+    /// the `mod.qp` file of the directory itself and the importing file
+    fn directory_modules(
+        &mut self,
+        importer: ModuleId,
+        mut entries: Vec<PathBuf>,
+    ) -> Vec<Imported> {
+        entries.sort();
+        let importer_path = &self.modules[importer.0].path;
+        let files: Vec<PathBuf> = entries
+            .into_iter()
+            .map(|entry| normalize(&entry))
+            .filter_map(|entry| {
+                if self.files.is_file(&entry) {
+                    let is_module = entry.extension().is_some_and(|extension| extension == "qp")
+                        && entry.file_name().is_some_and(|name| name != "mod.qp");
+                    is_module.then_some(entry)
+                } else {
+                    let file = entry.join("mod.qp");
+                    self.files.is_file(&file).then_some(file)
+                }
+            })
+            .filter(|file| file != importer_path)
+            .collect();
+        files
+            .into_iter()
+            .map(|file| Imported::Module(self.load(file)))
+            .collect()
+    }
+
+    fn check_exported(&self, module: ModuleId, item: &str) -> Result<(), LoadError> {
+        let module = &self.modules[module.0];
+        // a module that could not be parsed has its own error already
+        if module.file.is_err() || module.exports().contains(&item) {
+            return Ok(());
+        }
+        Err(LoadError::NotExported {
+            module: module.name.clone(),
+            item: item.to_string(),
+        })
+    }
+
+    /// The exports of the schema that the imported modules lack, imported items are not modules
+    /// and are not checked
+    fn check_schema(&self, imported: &[Imported], schema: &Schema) -> Vec<LoadError> {
+        let mut errors = Vec::new();
+        for imported in imported {
+            let (Imported::Module(module) | Imported::Exports(module)) = imported else {
+                continue;
+            };
+            for export in &schema.exports {
+                if let Err(LoadError::NotExported { module, item }) =
+                    self.check_exported(*module, export)
+                {
+                    errors.push(LoadError::MissingExport {
+                        module,
+                        item,
+                        schema: schema.name.clone(),
+                    });
+                }
+            }
+        }
+        errors
+    }
+
+    fn display_path(&self, path: &ImportPath) -> String {
+        let prefix = match path.relative {
+            RelativeImport::None => String::new(),
+            RelativeImport::Current => "./".to_string(),
+            RelativeImport::Parent(parents) => "../".repeat(parents),
+        };
+        let names: Vec<String> = path
+            .segments
+            .iter()
+            .map(|segment| match &segment.value {
+                ImportSegment::Name(name) => name.clone(),
+                ImportSegment::Glob => "*".to_string(),
+                ImportSegment::List(names) => {
+                    format!(
+                        "{{{}}}",
+                        names.values().cloned().collect::<Vec<_>>().join(", ")
+                    )
+                }
+            })
+            .collect();
+        format!("{}{}", prefix, names.join("."))
+    }
+}
+
+fn collect_imports(
+    statements: &[(Located<Statement>, Whitespace0)],
+    imports: &mut Vec<ImportStatement>,
+) {
+    for (statement, _) in statements {
+        match &statement.value {
+            Statement::Import { path, using, .. } => imports.push(ImportStatement {
+                path: path.clone(),
+                schema: using.as_ref().map(|using| {
+                    schema(statements, &using.schema.value)
+                        .map_err(|error| Located::new(error, using.schema.source_span))
+                }),
+            }),
+            Statement::Module { statements, .. } => {
+                collect_imports(&statements.statements, imports)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The schema named by a `using` clause, declared among the statements of the import as
+/// `let doc = _ { export: { name: String, ui: Ui } };`. The exports of a schema that uses
+/// anything else, such as a spread, can not be known before the types are checked.
+fn schema(
+    statements: &[(Located<Statement>, Whitespace0)],
+    schema: &Expression,
+) -> Result<Schema, LoadError> {
+    let Expression::Variable { identifier } = schema else {
+        return Err(LoadError::InvalidSchema);
+    };
+    let unknown = || LoadError::UnknownSchema(identifier.clone());
+    let value = statements.iter().find_map(|(statement, _)| {
+        let Statement::Expression { expr, .. } = &statement.value else {
+            return None;
+        };
+        let Expression::Declaration {
+            creation,
+            initializer: Some(initializer),
+            ..
+        } = &expr.value
+        else {
+            return None;
+        };
+        match &creation.value {
+            VariableCreation::Identifier { name, .. } if name == identifier => {
+                Some(&initializer.value.value)
+            }
+            _ => None,
+        }
+    });
+    let export = struct_fields(value.ok_or_else(unknown)?)
+        .ok_or_else(unknown)?
+        .find_map(|field| match field {
+            StructLiteralField::Field(field) if field.name == "export" => Some(&field.value.value),
+            _ => None,
+        });
+    let exports = struct_fields(export.ok_or_else(unknown)?)
+        .ok_or_else(unknown)?
+        .map(|field| match field {
+            StructLiteralField::Field(field) => Some(field.name.clone()),
+            StructLiteralField::Shorthand(name) => Some(name.value.clone()),
+            StructLiteralField::Spread { .. } => None,
+        })
+        .collect::<Option<_>>()
+        .ok_or_else(unknown)?;
+    Ok(Schema {
+        name: identifier.clone(),
+        exports,
+    })
+}
+
+fn struct_fields(expression: &Expression) -> Option<impl Iterator<Item = &StructLiteralField>> {
+    match expression {
+        Expression::StructLiteral { fields, .. } => Some(fields.values()),
+        _ => None,
+    }
+}
+
+/// Reports each cycle once, at the import that leads back to the first module of the cycle
+fn report_cycles(graph: &mut ModuleGraph) {
+    let mut visited = HashSet::new();
+    let mut stack = Vec::new();
+    let mut cycles = Vec::new();
+    for id in graph.ids() {
+        find_cycles(graph, id, &mut stack, &mut visited, &mut cycles);
+    }
+    for cycle in cycles {
+        let &[.., last, first] = &cycle[..] else {
+            continue;
+        };
+        let names = cycle.iter().map(|id| graph[*id].name.clone()).collect();
+        let module = &mut graph.modules[last.0];
+        let import = module.imports.iter().find(|import| {
+            import.imported.iter().any(|imported| match imported {
+                Imported::Module(id) | Imported::Exports(id) => *id == first,
+                Imported::Item { module, .. } => *module == first,
+            })
+        });
+        if let Some(import) = import {
+            let error = Located::new(LoadError::ImportCycle(names), import.source_span);
+            module.errors.push(error);
+        }
+    }
+}
+
+fn find_cycles(
+    graph: &ModuleGraph,
+    id: ModuleId,
+    stack: &mut Vec<ModuleId>,
+    visited: &mut HashSet<ModuleId>,
+    cycles: &mut Vec<Vec<ModuleId>>,
+) {
+    if let Some(start) = stack.iter().position(|on_stack| *on_stack == id) {
+        let mut cycle = stack[start..].to_vec();
+        cycle.push(id);
+        cycles.push(cycle);
+        return;
+    }
+    if !visited.insert(id) {
+        return;
+    }
+    stack.push(id);
+    for dependency in graph[id].dependencies() {
+        find_cycles(graph, dependency, stack, visited, cycles);
+    }
+    stack.pop();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, io};
+
+    use super::*;
+
+    /// A file system in memory, directories are the parents of the files
+    struct MemoryFileSystem(HashMap<PathBuf, String>);
+
+    impl MemoryFileSystem {
+        fn new(files: &[(&str, &str)]) -> Self {
+            MemoryFileSystem(
+                files
+                    .iter()
+                    .map(|(path, code)| (PathBuf::from(path), code.to_string()))
+                    .collect(),
+            )
+        }
+    }
+
+    impl FileSystem for MemoryFileSystem {
+        fn read(&self, path: &Path) -> io::Result<String> {
+            self.0
+                .get(path)
+                .cloned()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        }
+
+        fn is_file(&self, path: &Path) -> bool {
+            self.0.contains_key(path)
+        }
+
+        fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+            let mut entries: Vec<PathBuf> = self
+                .0
+                .keys()
+                .filter_map(|file| {
+                    let name = file.strip_prefix(path).ok()?.iter().next()?;
+                    Some(path.join(name))
+                })
+                .collect();
+            entries.sort();
+            entries.dedup();
+            if entries.is_empty() {
+                return Err(io::Error::from(io::ErrorKind::NotFound));
+            }
+            Ok(entries)
+        }
+    }
+
+    const STD: &[(&str, &str)] = &[
+        ("std/mod.qp", ""),
+        ("std/env/console/mod.qp", "pub fn println(value: String) {}"),
+        ("std/env/fs/mod.qp", "pub fn read() {}"),
+        ("std/iterator.qp", "pub trait Iterator {}"),
+    ];
+
+    fn load(files: &[(&str, &str)]) -> ModuleGraph {
+        let mut all = STD.to_vec();
+        all.extend_from_slice(files);
+        load_project(
+            &MemoryFileSystem::new(&all),
+            Path::new("project/main.qp"),
+            Path::new("std"),
+        )
+    }
+
+    fn names(graph: &ModuleGraph) -> Vec<&str> {
+        graph
+            .modules
+            .iter()
+            .map(|module| module.name.as_str())
+            .collect()
+    }
+
+    fn errors(graph: &ModuleGraph) -> Vec<String> {
+        graph
+            .modules
+            .iter()
+            .flat_map(|module| &module.errors)
+            .map(|error| error.value.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_std_imports() {
+        let graph = load(&[(
+            "project/main.qp",
+            "import std.env.console;\nimport std.env.console.println;\nimport std.iterator.*;\nimport std.env.{fs};\nimport std;",
+        )]);
+        assert_eq!(
+            names(&graph),
+            vec![
+                "main",
+                "std.env.console",
+                "std.iterator",
+                "std.env.fs",
+                "std"
+            ]
+        );
+        assert!(errors(&graph).is_empty());
+        let main = &graph[graph.entry];
+        let imported: Vec<_> = main
+            .imports
+            .iter()
+            .map(|import| &import.imported[0])
+            .collect();
+        let console = ModuleId(1);
+        assert_eq!(imported[0], &Imported::Module(console));
+        assert_eq!(
+            imported[1],
+            &Imported::Item {
+                module: console,
+                name: "println".to_string()
+            }
+        );
+        assert_eq!(imported[2], &Imported::Exports(ModuleId(2)));
+        assert_eq!(imported[3], &Imported::Module(ModuleId(3)));
+        assert_eq!(graph.imported_names(&main.imports[0]), vec!["console"]);
+        assert_eq!(graph.imported_names(&main.imports[2]), vec!["Iterator"]);
+    }
+
+    #[test]
+    fn test_prelude() {
+        let graph = load(&[
+            ("project/main.qp", "let a = Some(1);"),
+            (
+                "std/prelude.qp",
+                "pub enum Option<T> { Some(T), None }\npub let Some = Option.Some;\nlet hidden = 1;",
+            ),
+        ]);
+        assert_eq!(names(&graph), vec!["main", "std.prelude"]);
+        let prelude = graph.prelude.unwrap();
+        assert_eq!(graph.prelude_names(graph.entry), vec!["Option", "Some"]);
+        assert!(graph.prelude_names(prelude).is_empty());
+        // a std tree without a prelude leaves every module without one
+        assert!(load(&[("project/main.qp", "")]).prelude.is_none());
+    }
+
+    #[test]
+    fn test_project_imports() {
+        let graph = load(&[
+            ("project/main.qp", "import ./util;\nimport shapes;"),
+            ("project/util.qp", "import ./shapes.circle.area;"),
+            ("project/shapes/mod.qp", "import ./*;"),
+            ("project/shapes/circle.qp", "pub fn area() {}"),
+            ("project/shapes/square.qp", "mod inner { import ../util; }"),
+        ]);
+        assert_eq!(
+            names(&graph),
+            vec!["main", "util", "shapes.circle", "shapes", "shapes.square"]
+        );
+        // every file is parsed once, even when it is imported from several places
+        assert_eq!(
+            graph[ModuleId(3)].dependencies(),
+            vec![ModuleId(2), ModuleId(4)]
+        );
+        assert_eq!(graph[ModuleId(4)].dependencies(), vec![ModuleId(1)]);
+        assert!(errors(&graph).is_empty());
+    }
+
+    #[test]
+    fn test_import_cycles() {
+        let graph = load(&[
+            ("project/main.qp", "import a;"),
+            ("project/a.qp", "import b;"),
+            ("project/b.qp", "let x = 1;\nimport a;"),
+        ]);
+        assert_eq!(errors(&graph), vec!["import cycle a -> b -> a"]);
+        let error = &graph[ModuleId(2)].errors[0];
+        assert_eq!(error.source_span.start.line, 1);
+    }
+
+    #[test]
+    fn test_unresolved_imports() {
+        let graph = load(&[
+            ("project/main.qp", "import std.lang.rust.{rs};\nimport lib.hidden;\nimport lib.{shown, other};\nimport main2;"),
+            ("project/lib.qp", "fn hidden() {}\npub let shown = 1;"),
+        ]);
+        assert_eq!(
+            errors(&graph),
+            vec![
+                "module `std.lang.rust.{rs}` not found",
+                "`hidden` is not exported by `lib`",
+                "`other` is not exported by `lib`",
+                "module `main2` not found",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schemas() {
+        let graph = load(&[
+            (
+                "project/main.qp",
+                "let doc = _ { export: { name: String, ui } };\nimport ./docs.* using doc;\nimport ./docs.intro.name using doc;",
+            ),
+            ("project/docs/intro.qp", "pub let name = 1;\npub let ui = 2;"),
+            ("project/docs/outro.qp", "pub let name = 1;\nlet ui = 2;"),
+            ("project/docs/empty.qp", ""),
+        ]);
+        assert_eq!(
+            errors(&graph),
+            vec![
+                "`docs.empty` does not export `name`, which the schema `doc` requires",
+                "`docs.empty` does not export `ui`, which the schema `doc` requires",
+                "`docs.outro` does not export `ui`, which the schema `doc` requires",
+            ]
+        );
+        let error = &graph[graph.entry].errors[0];
+        assert_eq!(error.source_span.start.line, 1);
+        assert_eq!(error.source_span.start.column, 7);
+        let graph = load(&[
+            (
+                "project/main.qp",
+                "import ./docs.* using std.doc;\nimport ./docs.* using missing;\nlet spread = _ { export: { name: String, ..base } };\nimport ./docs.* using spread;",
+            ),
+            ("project/docs/empty.qp", ""),
+        ]);
+        assert_eq!(
+            errors(&graph),
+            vec![
+                "`using` takes the name of a schema declared next to the import",
+                "`missing` is not a schema, expected `let missing = _ { export: { ... } }` next to the import",
+                "`spread` is not a schema, expected `let spread = _ { export: { ... } }` next to the import",
+            ]
+        );
+        let error = &graph[graph.entry].errors[0];
+        assert_eq!(error.source_span.start.column, 22);
+    }
+
+    #[test]
+    fn test_docs() {
+        let mut files = STD.to_vec();
+        files.extend_from_slice(&[
+            ("std/env/ui/mod.qp", "pub struct Ui {}"),
+            ("docs/lib.qp", include_str!("../../../docs/lib.qp")),
+            (
+                "docs/hello_world.qp",
+                include_str!("../../../docs/hello_world.qp"),
+            ),
+        ]);
+        let graph = load_project(
+            &MemoryFileSystem::new(&files),
+            Path::new("docs/lib.qp"),
+            Path::new("std"),
+        );
+        assert_eq!(names(&graph), vec!["lib", "std.env.ui", "hello_world"]);
+        assert!(graph.modules.iter().all(|module| module.file.is_ok()));
+        // hello_world exports everything the schema `doc` requires, and refers back to lib
+        // through `docref`
+        assert_eq!(
+            errors(&graph),
+            vec!["import cycle lib -> hello_world -> lib"]
+        );
+    }
+
+    #[test]
+    fn test_file_errors() {
+        let graph = load(&[
+            ("project/main.qp", "import broken;"),
+            ("project/broken.qp", "fn ("),
+        ]);
+        assert!(graph[ModuleId(1)].file.is_err());
+        assert!(errors(&graph).is_empty());
+        let missing = load(&[]);
+        assert!(missing[missing.entry]
+            .file
+            .as_ref()
+            .unwrap_err()
+            .contains("could not read"));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::*;
 use enum_kinds::EnumKind;
 use enumset::EnumSetType;
-use logos::{internal::LexerInternal, Lexer, Logos};
+use logos::{Lexer, Logos};
 use proc_macros::TokenParser;
 
 
@@ -13,20 +13,24 @@ pub enum Token<'a> {
     Ident(&'a str),
 
     // literals
-    #[regex(r"0[xX][0-9a-fA-F]+|0[bB][01]+|0[oO][0-7]+|(\d+\.?\d*|\.\d+)([eE][+-]?\d+)?")]
+    #[regex(r"[0-9]|\.[0-9]", number)]
     Number(&'a str),
 
-    // strings can use ' or "
-    #[regex(r#""([^"\\]|\\.)*"|'([^'\\]|\\.)*'"#)]
+    // strings can use ' or ", the expressions in `{ }` may contain further strings
+    #[regex(r#""([^"\\{]|\\.)*["{]|'([^'\\{]|\\.)*['{]"#, string)]
     String(&'a str),
-    #[regex("r#", raw_string_start)]
+    // raw strings such as r#"a "quoted" word"#, holding only their contents
+    #[regex("r#*\"", raw_string)]
     RawString(&'a str),
 
+    // `rs { ... }`, a block of Rust code that is not lexed as quip, see `foreign_block`
+    ForeignBlock(&'a str),
+
     // labels
     #[regex("'[a-zA-Z_][a-zA-Z0-9_]*")]
     Label(&'a str),
 
-    #[token("true|false", |lex| {
+    #[regex("true|false", |lex| {
         if lex.slice() == "true" {
             true
         } else {
@@ -88,6 +92,11 @@ pub enum Token<'a> {
     // other tokens
     #[token(".")]
     Dot,
+    // the start of a relative import, lexed as one token so `./*` doesn't start a comment
+    #[token("./")]
+    CurrentDirectory,
+    #[token("../")]
+    ParentDirectory,
     #[token(",")]
     Comma,
     #[token(":")]
@@ -106,10 +115,14 @@ pub enum Token<'a> {
     LeftBrace,
     #[token("}")]
     RightBrace,
+    #[token("#")]
+    Hash,
     #[token("=")]
     Assignment,
     #[token("->")]
     Arrow,
+    #[token("=>")]
+    FatArrow,
     #[token("|")]
     VerticalBar,
     #[token("&")]
@@ -150,12 +163,22 @@ pub enum Token<'a> {
     Type,
     #[token("fn")]
     Fn,
+    #[token("pub")]
+    Pub,
+    #[token("unsafe")]
+    Unsafe,
+    #[token("const")]
+    Const,
+    #[token("match")]
+    Match,
     #[token("mut")]
     Mut,
     #[token("import")]
     Import,
     #[token("as")]
     As,
+    #[token("using")]
+    Using,
     #[token("do")]
     Do,
     #[token("use_env")]
@@ -199,6 +222,8 @@ impl TokenKind {
             TokenKind::Dot => Some(1),
             TokenKind::Comma => Some(1),
             TokenKind::Colon => Some(1),
+            TokenKind::CurrentDirectory => Some(2),
+            TokenKind::ParentDirectory => Some(3),
             TokenKind::Semicolon => Some(1),
             TokenKind::LeftParen => Some(1),
             TokenKind::RightParen => Some(1),
@@ -206,8 +231,10 @@ impl TokenKind {
             TokenKind::RightBracket => Some(1),
             TokenKind::LeftBrace => Some(1),
             TokenKind::RightBrace => Some(1),
+            TokenKind::Hash => Some(1),
             TokenKind::Assignment => Some(1),
             TokenKind::Arrow => Some(2),
+            TokenKind::FatArrow => Some(2),
             TokenKind::Let => Some(3),
             TokenKind::If => Some(2),
             TokenKind::Else => Some(4),
@@ -227,6 +254,7 @@ impl TokenKind {
             TokenKind::Number => "Number",
             TokenKind::String => "String",
             TokenKind::RawString => "RawString",
+            TokenKind::ForeignBlock => "ForeignBlock",
             TokenKind::Label => "Label",
             TokenKind::Boolean => "Boolean",
             TokenKind::Range => "Range",
@@ -262,8 +290,12 @@ impl TokenKind {
             TokenKind::RightBracket => "RightBracket",
             TokenKind::LeftBrace => "LeftBrace",
             TokenKind::RightBrace => "RightBrace",
+            TokenKind::Hash => "Hash",
+            TokenKind::CurrentDirectory => "CurrentDirectory",
+            TokenKind::ParentDirectory => "ParentDirectory",
             TokenKind::Assignment => "Assignment",
             TokenKind::Arrow => "Arrow",
+            TokenKind::FatArrow => "FatArrow",
             TokenKind::VerticalBar => "VerticalBar",
             TokenKind::Ampersand => "Ampersand",
             TokenKind::Let => "Let",
@@ -283,9 +315,14 @@ impl TokenKind {
             TokenKind::Mod => "Mod",
             TokenKind::Type => "Type",
             TokenKind::Fn => "Function",
+            TokenKind::Pub => "Pub",
+            TokenKind::Unsafe => "Unsafe",
+            TokenKind::Const => "Const",
+            TokenKind::Match => "Match",
             TokenKind::Mut => "Mutable",
             TokenKind::Import => "Import",
             TokenKind::As => "As",
+            TokenKind::Using => "Using",
             TokenKind::Do => "Do",
             TokenKind::UseEnv => "UseEnv",
             TokenKind::LineComment => "LineComment",
@@ -296,75 +333,36 @@ impl TokenKind {
     }
 }
 
-// fn number<'a>(lex: &mut Lexer<'a, Token<'a>>) -> Number {
-//     let text = lex.slice();
-//     let mut is_float = false;
-
-//     // Check for prefixed numbers: hex, octal, or binary
-//     if text.starts_with("0") && text.len() > 1 {
-//         match text.chars().nth(1).unwrap() {
-//             'x' | 'X' => {
-//                 return Number::Integer(
-//                     BigInt::from_str_radix(&text[2..], 16).expect("Invalid hexadecimal number"),
-//                 )
-//             }
-//             'o' | 'O' => {
-//                 return Number::Integer(
-//                     BigInt::from_str_radix(&text[2..], 8).expect("Invalid octal number"),
-//                 )
-//             }
-//             'b' | 'B' => {
-//                 return Number::Integer(
-//                     BigInt::from_str_radix(&text[2..], 2).expect("Invalid binary number"),
-//                 )
-//             }
-//             _ => {}
-//         }
-//     }
-
-//     // Manually parse the number to determine if it's an integer or float
-//     for (i, c) in text.chars().enumerate() {
-//         match c {
-//             '0'..='9' => continue, // Digits are fine
-//             '.' => {
-//                 // Only one decimal point allowed
-//                 if is_float {
-//                     panic!("Invalid float number: multiple decimal points");
-//                 }
-//                 is_float = true;
-//             }
-//             'e' | 'E' => {
-//                 // Handle scientific notation; if encountered, the rest must be float
-//                 is_float = true;
-//                 // Check if the exponent is properly formatted
-//                 if i + 1 < text.len() {
-//                     match text.chars().nth(i + 1).unwrap() {
-//                         '+' | '-' => {
-//                             // Skip sign, must be followed by digits
-//                             if i + 2 >= text.len() || !text.chars().nth(i + 2).unwrap().is_digit(10)
-//                             {
-//                                 panic!("Invalid float number: malformed exponent");
-//                             }
-//                         }
-//                         d if d.is_digit(10) => continue,
-//                         _ => panic!("Invalid float number: malformed exponent"),
-//                     }
-//                 } else {
-//                     panic!("Invalid float number: missing exponent digits");
-//                 }
-//                 break; // Rest must be digits
-//             }
-//             _ => panic!("Invalid character in number: '{}'", c), // Non-numeric characters
-//         }
-//     }
-
-//     // Return the parsed number
-//     if is_float {
-//         Number::Float(f64::from_str(text).expect("Invalid float number"))
-//     } else {
-//         Number::Integer(BigInt::from_str(text).expect("Invalid integer number"))
-//     }
-// }
+/// Consumes the rest of a number literal, its value is parsed by `literal::parse_number`.
+///
+/// The token is kept as long as possible, so that malformed literals such as `0b12`
+/// or `1e` are reported as a whole instead of being split into several tokens.
+/// A `.` is only part of a decimal literal if a digit follows it, so `1..2` is a range.
+fn number<'a>(lex: &mut Lexer<'a, Token<'a>>) -> &'a str {
+    let radix = lex.slice() == "0" && lex.remainder().starts_with(['x', 'X', 'o', 'O', 'b', 'B']);
+    let mut fraction = lex.slice().starts_with('.');
+    let mut letters = false;
+    loop {
+        let mut chars = lex.remainder().chars();
+        let (Some(c), next) = (chars.next(), chars.next()) else {
+            break;
+        };
+        let next_is_digit = next.is_some_and(|next| next.is_ascii_digit());
+        let accept = match c {
+            c if c.is_ascii_alphanumeric() || c == '_' => true,
+            '.' => !radix && !fraction && !letters && next_is_digit,
+            '+' | '-' => !radix && lex.slice().ends_with(['e', 'E']) && next_is_digit,
+            _ => false,
+        };
+        if !accept {
+            break;
+        }
+        fraction |= c == '.';
+        letters |= c.is_ascii_alphabetic();
+        lex.bump(c.len_utf8());
+    }
+    lex.slice()
+}
 
 impl<'a> Token<'a> {
     #[inline]
@@ -373,51 +371,287 @@ impl<'a> Token<'a> {
     }
 }
 
-fn block_comment<'a>(lex: &mut Lexer<'a, Token<'a>>) -> &'a str {
+/// Consumes a block comment, which may be nested, returning its full text.
+/// Unterminated comments are lexed as an error.
+fn block_comment<'a>(lex: &mut Lexer<'a, Token<'a>>) -> Option<&'a str> {
     let mut depth = 1;
-    let start = lex.span().start;
 
     while depth > 0 {
-        match lex.slice().chars().next() {
-            Some('/') if lex.slice().starts_with("/*") => {
-                lex.bump(2);
-                depth += 1;
-            }
-            Some('*') if lex.slice().starts_with("*/") => {
-                lex.bump(2);
-                depth -= 1;
-            }
-            Some(_) => {
-                lex.bump(1);
-            }
-            None => {
-                lex.error();
-                break;
-            }
+        let remainder = lex.remainder();
+        if remainder.starts_with("/*") {
+            lex.bump(2);
+            depth += 1;
+        } else if remainder.starts_with("*/") {
+            lex.bump(2);
+            depth -= 1;
+        } else {
+            let c = remainder.chars().next()?;
+            lex.bump(c.len_utf8());
         }
     }
 
-    &lex.source()[start..lex.span().start]
+    Some(lex.slice())
 }
 
-fn raw_string_start<'a>(lex: &mut Lexer<'a, Token<'a>>) -> &'a str {
-    let start_hashes = lex.slice().chars().take_while(|&c| c == '#').count();
-    let mut end = start_hashes;
-    lex.bump(start_hashes);
-    while end > 0 {
-        match lex.slice().chars().next() {
-            Some('#') if lex.slice().ends_with(&"#".repeat(end)) => {
-                lex.bump(end);
-                end -= 1;
-            }
-            Some(_) => {
-                lex.bump(1);
+/// Consumes the rest of a string if the regex stopped at the start of an interpolation.
+/// Unterminated strings are lexed as an error.
+fn string<'a>(lex: &mut Lexer<'a, Token<'a>>) -> Option<&'a str> {
+    if lex.slice().ends_with('{') {
+        let quote = lex.slice().chars().next()?;
+        let interpolation = interpolation_end(lex.remainder())?;
+        let rest = string_end(&lex.remainder()[interpolation..], quote)?;
+        lex.bump(interpolation + rest);
+    }
+    Some(lex.slice())
+}
+
+/// The length of the rest of a string up to and including its closing `quote`
+fn string_end(text: &str, quote: char) -> Option<usize> {
+    let mut index = 0;
+    while let Some(c) = text[index..].chars().next() {
+        index += c.len_utf8();
+        match c {
+            '\\' => index += text[index..].chars().next()?.len_utf8(),
+            '{' => index += interpolation_end(&text[index..])?,
+            c if c == quote => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The length of an interpolated expression up to and including the `}` closing it
+pub(crate) fn interpolation_end(text: &str) -> Option<usize> {
+    let mut index = 0;
+    let mut depth = 0;
+    while let Some(c) = text[index..].chars().next() {
+        index += c.len_utf8();
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(index),
+            '}' => depth -= 1,
+            '"' | '\'' => index += string_end(&text[index..], c)?,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Consumes a raw string, which ends at the first `"` followed by as many `#` as it started with.
+/// Returns the contents between the quotes, unterminated raw strings are lexed as an error.
+fn raw_string<'a>(lex: &mut Lexer<'a, Token<'a>>) -> Option<&'a str> {
+    let hashes = lex.slice().len() - 2;
+    let terminator = format!("\"{}", "#".repeat(hashes));
+    let Some(length) = lex.remainder().find(&terminator) else {
+        lex.bump(lex.remainder().len());
+        return None;
+    };
+    let contents = &lex.remainder()[..length];
+    lex.bump(length + terminator.len());
+    Some(contents)
+}
+
+/// Lexes the `rs { ... }` block following an `rs` identifier up to its closing brace, or `None`
+/// when no brace follows. Braces in the strings, characters and comments of the code are not
+/// counted, unterminated blocks are lexed as an error. This is not a logos pattern as logos
+/// cannot fall back to an identifier after matching `rs` and the whitespace behind it.
+pub(crate) fn foreign_block<'a>(lex: &mut Lexer<'a, Token<'a>>) -> Option<Token<'a>> {
+    let text = lex.remainder();
+    let mut index = text.find(|c: char| !c.is_ascii_whitespace())?;
+    if !text[index..].starts_with('{') {
+        return None;
+    }
+    index += 1;
+    let mut depth = 0;
+    while let Some(c) = text[index..].chars().next() {
+        index += c.len_utf8();
+        let rest = &text[index..];
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => {
+                lex.bump(index);
+                return Some(Token::ForeignBlock(lex.slice()));
             }
-            None => {
-                lex.error();
-                break;
+            '}' => depth -= 1,
+            '"' => match rust_string_end(rest) {
+                Some(length) => index += length,
+                None => break,
+            },
+            // a lifetime such as `'a` has no closing quote
+            '\'' => index += rust_char_end(rest).unwrap_or(0),
+            '/' if rest.starts_with('/') => index += rest.find('\n').unwrap_or(rest.len()),
+            '/' if rest.starts_with('*') => match rest.find("*/") {
+                Some(length) => index += length + 2,
+                None => break,
+            },
+            _ => {}
+        }
+    }
+    lex.bump(text.len());
+    Some(Token::Error)
+}
+
+/// The length of the rest of a Rust string up to and including its closing `"`
+fn rust_string_end(text: &str) -> Option<usize> {
+    let mut chars = text.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
             }
+            '"' => return Some(index + 1),
+            _ => {}
         }
     }
-    &lex.source()[lex.span().start..lex.span().start + end]
+    None
+}
+
+/// The length of the rest of a Rust character up to and including its closing `'`
+fn rust_char_end(text: &str) -> Option<usize> {
+    let first = text.chars().next()?;
+    let end = match first {
+        '\\' => text[1..].find('\'')? + 1,
+        _ => first.len_utf8(),
+    };
+    text[end..].starts_with('\'').then_some(end + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tokenize;
+
+    use super::*;
+
+    fn tokens(code: &str) -> Vec<(Token<'_>, &str)> {
+        tokenize(code)
+            .into_iter()
+            .map(|token| (token.token, token.text))
+            .collect()
+    }
+
+    #[test]
+    fn test_foreign_blocks() {
+        let code = "rs {\n    print!(\"{}\", a);\n    if b { '}' } else { '\\'' } // }\n}!";
+        let block = &code[..code.len() - 1];
+        assert_eq!(
+            tokens(code),
+            vec![
+                (Token::ForeignBlock(block), block),
+                (Token::Exclamation, "!")
+            ]
+        );
+        assert_eq!(
+            tokens("rs { fn f<'a>(a: &'a str) {} }")[0].0,
+            Token::ForeignBlock("rs { fn f<'a>(a: &'a str) {} }")
+        );
+        assert_eq!(tokens("rs { \"}")[0].0, Token::Error);
+        // `rs` is only special in front of a brace
+        assert_eq!(tokens("rs")[0].0, Token::Ident("rs"));
+        assert_eq!(
+            tokens("{ a, rs }"),
+            vec![
+                (Token::LeftBrace, "{"),
+                (Token::Space(" "), " "),
+                (Token::Ident("a"), "a"),
+                (Token::Comma, ","),
+                (Token::Space(" "), " "),
+                (Token::Ident("rs"), "rs"),
+                (Token::Space(" "), " "),
+                (Token::RightBrace, "}")
+            ]
+        );
+        assert_eq!(tokens("rust {}")[0].0, Token::Ident("rust"));
+    }
+
+    #[test]
+    fn test_raw_strings() {
+        assert_eq!(tokens(r#"r"a\nb""#), vec![(Token::RawString(r"a\nb"), r#"r"a\nb""#)]);
+        assert_eq!(tokens(r##"r#""#"##), vec![(Token::RawString(""), r##"r#""#"##)]);
+        assert_eq!(
+            tokens(r###"r#"say "hi""#"###),
+            vec![(Token::RawString(r#"say "hi""#), r###"r#"say "hi""#"###)]
+        );
+        assert_eq!(
+            tokens(r####"r##"a "# b"##"####),
+            vec![(Token::RawString(r##"a "# b"##), r####"r##"a "# b"##"####)]
+        );
+        assert_eq!(
+            tokens("r\"multi\nline\""),
+            vec![(Token::RawString("multi\nline"), "r\"multi\nline\"")]
+        );
+    }
+
+    #[test]
+    fn test_raw_string_ends_at_first_terminator() {
+        assert_eq!(
+            tokens(r###"r#"a"# + r#"b"#"###),
+            vec![
+                (Token::RawString("a"), r##"r#"a"#"##),
+                (Token::Space(" "), " "),
+                (Token::Plus, "+"),
+                (Token::Space(" "), " "),
+                (Token::RawString("b"), r##"r#"b"#"##),
+            ]
+        );
+        // extra hashes after the terminator are not part of the string
+        assert_eq!(
+            tokens(r###"r#"a"##"###),
+            vec![(Token::RawString("a"), r##"r#"a"#"##), (Token::Hash, "#")]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_raw_strings() {
+        assert_eq!(tokens(r#"r"abc"#), vec![(Token::Error, r#"r"abc"#)]);
+        // fewer closing hashes than opening ones do not terminate the string
+        assert_eq!(
+            tokens(r###"r##"abc"# x"###),
+            vec![(Token::Error, r###"r##"abc"# x"###)]
+        );
+    }
+
+    #[test]
+    fn test_r_is_still_an_identifier() {
+        assert_eq!(
+            tokens("r + r2"),
+            vec![
+                (Token::Ident("r"), "r"),
+                (Token::Space(" "), " "),
+                (Token::Plus, "+"),
+                (Token::Space(" "), " "),
+                (Token::Ident("r2"), "r2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_booleans() {
+        assert_eq!(
+            tokens("true false trueish"),
+            vec![
+                (Token::Boolean(true), "true"),
+                (Token::Space(" "), " "),
+                (Token::Boolean(false), "false"),
+                (Token::Space(" "), " "),
+                (Token::Ident("trueish"), "trueish"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_relative_paths() {
+        assert_eq!(
+            tokens("./* ../../a"),
+            vec![
+                (Token::CurrentDirectory, "./"),
+                (Token::Star, "*"),
+                (Token::Space(" "), " "),
+                (Token::ParentDirectory, "../"),
+                (Token::ParentDirectory, "../"),
+                (Token::Ident("a"), "a"),
+            ]
+        );
+        assert_eq!(tokens("a/* b */")[1], (Token::BlockComment("/* b */"), "/* b */"));
+        assert_eq!(tokens("a../b")[1], (Token::ParentDirectory, "../"));
+    }
 }
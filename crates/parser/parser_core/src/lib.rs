@@ -1,14 +1,16 @@
 #![feature(closure_lifetime_binder)]
 pub mod lexer;
+pub mod literal;
 
 #[macro_use]
 mod logs;
 
 use enumset::EnumSet;
-use fst::{Location, SourceSpan};
+use fst::{Located, Location, SourceSpan};
 pub use lexer::*;
 use logos::Logos;
 use proc_macros::{generate_all_alt_impls, generate_all_tuple_impls};
+use literal::LiteralError;
 use thiserror::Error;
 
 #[derive(Debug, Clone, Copy)]
@@ -69,15 +71,25 @@ impl<'a> AsTokenError<'a> for Option<LocatedToken<'a>> {
 
 #[inline]
 pub fn tokenize<'a>(source: &'a str) -> Vec<LocatedToken<'a>> {
+    tokenize_at(
+        source,
+        Location {
+            column: 0,
+            line: 0,
+            index: 0,
+        },
+    )
+}
+
+/// Tokenizes a part of a file, such as an interpolated expression, which starts at `location`
+pub fn tokenize_at<'a>(source: &'a str, mut location: Location) -> Vec<LocatedToken<'a>> {
     let mut iter = Token::lexer(source);
     let mut tokens = Vec::new();
-    let mut location = Location {
-        column: 0,
-        line: 0,
-        index: 0,
-    };
     while let Some(token) = iter.next() {
-        let token = token.unwrap_or(Token::Error);
+        let token = match token {
+            Ok(Token::Ident("rs")) => lexer::foreign_block(&mut iter).unwrap_or(Token::Ident("rs")),
+            token => token.unwrap_or(Token::Error),
+        };
         let range = iter.span();
         let text = &source[range];
         let new_lines = text.chars().filter(|c| *c == '\n').count();
@@ -169,6 +181,9 @@ pub enum ParserError {
     #[error("Unexpected token {0:?}, expected one of {}", format_enum_set(&.1))]
     /// Got, Expected
     UnexpectedToken(Option<TokenKind>, EnumSet<TokenKind>), // Got None is EndOfInput
+    /// A literal token that is malformed, such as `0b12`
+    #[error("{0}")]
+    InvalidLiteral(LiteralError),
 }
 
 fn format_enum_set(set: &EnumSet<TokenKind>) -> String {
@@ -215,9 +230,11 @@ impl LocatedParserError {
         use ParserError::*;
 
         match (&self.error, &other.error) {
-            (UnexpectedToken(_, _), UnexpectedToken(_, _)) => {
-                self.source_span.start > other.source_span.start
+            // a malformed literal is the cause of any other error at its location
+            (InvalidLiteral(_), UnexpectedToken(_, _)) => {
+                self.source_span.start >= other.source_span.start
             }
+            _ => self.source_span.start > other.source_span.start,
         }
     }
 
@@ -245,7 +262,8 @@ impl LocatedParserError {
                     }
                     ParserError::UnexpectedToken(got1, expected1 | expected2)
                         .locate(other.source_span)
-                } //_ => self.clone(),
+                }
+                _ => *self,
             }
         } else {
             self.clone()
@@ -341,6 +359,15 @@ impl<'a> Span<'a> {
 
         (rest, (Some(token), token.source_span))
     }
+    /// The span of source between the start of `self` and the start of `rest`,
+    /// where `rest` is what remains of `self` after a parser consumed some of it.
+    #[inline]
+    pub fn source_span_until(&self, rest: &Span<'a>) -> SourceSpan {
+        SourceSpan {
+            start: self.start,
+            end: rest.start,
+        }
+    }
     #[inline]
     pub fn end(&self) -> Location {
         match self.tokens.last() {
@@ -534,6 +561,7 @@ pub trait MapParser<'a, O> {
         self,
         wrapper: F,
     ) -> impl Fn(Span<'a>) -> ParserResult<'a, O>;
+    fn located(self) -> impl Fn(Span<'a>) -> ParserResult<'a, Located<O>>;
 }
 
 impl<'a, O, P: Fn(Span<'a>) -> ParserResult<'a, O>> MapParser<'a, O> for P {
@@ -557,6 +585,13 @@ impl<'a, O, P: Fn(Span<'a>) -> ParserResult<'a, O>> MapParser<'a, O> for P {
             }
         }
     }
+    #[inline]
+    fn located(self) -> impl Fn(Span<'a>) -> ParserResult<'a, Located<O>> {
+        move |span: Span<'a>| {
+            let (input, output) = self(span)?;
+            Ok((input, Located::new(output, span.source_span_until(&input))))
+        }
+    }
 }
 
 pub trait SafeMapParser<'a, O> {
@@ -0,0 +1,590 @@
+use std::ops::Range;
+
+use fst::{Literal, Location, Number, NumberLiteral, NumberSuffix, SourceSpan, StringLiteral};
+use num::{bigint::BigInt, Num, One};
+use thiserror::Error;
+
+use crate::{lexer::interpolation_end, LocatedParserError, LocatedToken, ParserError, Token};
+
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum LiteralError {
+    #[error("Number literal has no digits")]
+    MissingDigits,
+    #[error("Invalid digit {0:?} in a base {1} number literal")]
+    InvalidDigit(char, u32),
+    #[error("Exponent of a number literal has no digits")]
+    MissingExponentDigits,
+    #[error("Invalid number suffix, expected one of u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, px")]
+    InvalidSuffix,
+    #[error("Float literals can not have the integer suffix {0}")]
+    FloatWithIntegerSuffix(NumberSuffix),
+    #[error("Number literal does not fit in {0}")]
+    OutOfRange(NumberSuffix),
+    #[error(r#"Invalid escape sequence, expected one of \n, \r, \t, \\, \", \', \{{, \}}, \0, \x or \u"#)]
+    InvalidEscape,
+    #[error(r"\x escapes need two hexadecimal digits and can be at most \x7F")]
+    InvalidHexEscape,
+    #[error(r"\u escapes need one to six hexadecimal digits in braces, such as \u{{1F600}}")]
+    InvalidUnicodeEscape,
+    #[error("{0:X} is not a valid unicode codepoint")]
+    InvalidCodepoint(u32),
+    #[error(r"Unmatched }} in a string, use \}} for a literal brace")]
+    UnmatchedBrace,
+    #[error(r"Unclosed {{ in a string, use \{{ for a literal brace")]
+    UnclosedInterpolation,
+    #[error(r"Empty interpolation in a string, put an expression between the braces or use \{{ for a literal brace")]
+    EmptyInterpolation,
+}
+
+/// Converts a literal token into its value, `None` if the token is not a literal.
+/// Strings with interpolations are expressions rather than literals, they are `None` as well.
+///
+/// Errors in string escapes are located at the escape instead of the whole token.
+pub fn parse_literal(token: &LocatedToken) -> Option<Result<Literal, LocatedParserError>> {
+    let literal = match token.token {
+        Token::Number(number) => parse_number(number)
+            .map(Literal::Number)
+            .map_err(|error| ParserError::InvalidLiteral(error).locate(token.source_span)),
+        Token::String(string) => match string_parts(string) {
+            Ok(parts) => match parts.as_slice() {
+                [StringPart::Text { value, .. }] => Ok(Literal::String(StringLiteral {
+                    text: string.to_string(),
+                    value: value.clone(),
+                })),
+                _ => return None,
+            },
+            Err((error, range)) => Err(ParserError::InvalidLiteral(error).locate(SourceSpan {
+                start: location_within(token, range.start),
+                end: location_within(token, range.end),
+            })),
+        },
+        Token::RawString(contents) => Ok(Literal::RawString(StringLiteral {
+            text: token.text.to_string(),
+            value: contents.to_string(),
+        })),
+        Token::Boolean(boolean) => Ok(Literal::Boolean(boolean)),
+        _ => return None,
+    };
+    Some(literal)
+}
+
+/// The location of the byte at `offset` within the text of `token`
+pub fn location_within(token: &LocatedToken, offset: usize) -> Location {
+    let start = token.source_span.start;
+    let before = &token.text[..offset];
+    match before.rfind('\n') {
+        Some(newline) => Location {
+            line: start.line + before.matches('\n').count(),
+            column: offset - newline - 1,
+            index: start.index + offset,
+        },
+        None => Location {
+            line: start.line,
+            column: start.column + offset,
+            index: start.index + offset,
+        },
+    }
+}
+
+/// A part of a string token, strings with interpolations consist of several parts
+#[derive(Debug, Clone, PartialEq)]
+pub enum StringPart<'a> {
+    /// Text as written in the source with its decoded value
+    Text { text: &'a str, value: String },
+    /// The source of an interpolated expression without its braces and its offset in the token
+    Interpolation { source: &'a str, offset: usize },
+}
+
+/// Splits the text of a string token, which includes its quotes, into text and interpolations.
+///
+/// Strings without interpolations consist of a single text part.
+/// On error the byte range of the invalid escape or brace within `text` is returned as well.
+pub fn string_parts(text: &str) -> Result<Vec<StringPart<'_>>, (LiteralError, Range<usize>)> {
+    let end = text.len() - 1;
+    let mut parts = Vec::new();
+    let mut value = String::new();
+    let mut part_start = 1;
+    let mut index = 1;
+    while let Some(c) = text[index..end].chars().next() {
+        match c {
+            '\\' => {
+                let (decoded, length) = decode_escape(&text[index + 1..end])
+                    .map_err(|(error, length)| (error, index..index + 1 + length))?;
+                value.push(decoded);
+                index += 1 + length;
+            }
+            '{' => {
+                let length = interpolation_end(&text[index + 1..end])
+                    .ok_or((LiteralError::UnclosedInterpolation, index..index + 1))?;
+                let source = &text[index + 1..index + length];
+                if source.trim().is_empty() {
+                    return Err((LiteralError::EmptyInterpolation, index..index + 1 + length));
+                }
+                if part_start < index {
+                    parts.push(StringPart::Text {
+                        text: &text[part_start..index],
+                        value: std::mem::take(&mut value),
+                    });
+                }
+                parts.push(StringPart::Interpolation {
+                    source,
+                    offset: index + 1,
+                });
+                index += 1 + length;
+                part_start = index;
+            }
+            '}' => return Err((LiteralError::UnmatchedBrace, index..index + 1)),
+            _ => {
+                value.push(c);
+                index += c.len_utf8();
+            }
+        }
+    }
+    if part_start < end || parts.is_empty() {
+        parts.push(StringPart::Text {
+            text: &text[part_start..end],
+            value,
+        });
+    }
+    Ok(parts)
+}
+
+/// Decodes the escape at the start of `escape`, which follows a `\`.
+/// Returns the decoded character or the error, with the length of the escape in bytes.
+fn decode_escape(escape: &str) -> Result<(char, usize), (LiteralError, usize)> {
+    let Some(c) = escape.chars().next() else {
+        return Err((LiteralError::InvalidEscape, 0));
+    };
+    match c {
+        'n' => Ok(('\n', 1)),
+        'r' => Ok(('\r', 1)),
+        't' => Ok(('\t', 1)),
+        '0' => Ok(('\0', 1)),
+        '\\' | '"' | '\'' | '{' | '}' => Ok((c, 1)),
+        'x' => {
+            let digits = hex_digits(&escape[1..], 2);
+            if digits < 2 {
+                return Err((LiteralError::InvalidHexEscape, 1 + digits));
+            }
+            let code = u8::from_str_radix(&escape[1..3], 16).expect("digits were validated");
+            if code > 0x7F {
+                return Err((LiteralError::InvalidHexEscape, 3));
+            }
+            Ok((code as char, 3))
+        }
+        'u' => {
+            let Some(braced) = escape[1..].strip_prefix('{') else {
+                return Err((LiteralError::InvalidUnicodeEscape, 1));
+            };
+            let digits = hex_digits(braced, usize::MAX);
+            if !braced[digits..].starts_with('}') {
+                return Err((LiteralError::InvalidUnicodeEscape, 2 + digits));
+            }
+            let length = 3 + digits;
+            if digits == 0 || digits > 6 {
+                return Err((LiteralError::InvalidUnicodeEscape, length));
+            }
+            let code = u32::from_str_radix(&braced[..digits], 16).expect("digits were validated");
+            match char::from_u32(code) {
+                Some(c) => Ok((c, length)),
+                None => Err((LiteralError::InvalidCodepoint(code), length)),
+            }
+        }
+        _ => Err((LiteralError::InvalidEscape, c.len_utf8())),
+    }
+}
+
+/// The number of hexadecimal digits at the start of `text`, counting at most `max`
+fn hex_digits(text: &str, max: usize) -> usize {
+    text.chars().take(max).take_while(|c| c.is_ascii_hexdigit()).count()
+}
+
+/// Converts the text of a number token into its value.
+///
+/// Supports `0x`, `0o` and `0b` prefixes, decimals with a fraction or an exponent,
+/// `_` separators between digits and a type suffix such as `u8` or `f32`.
+pub fn parse_number(text: &str) -> Result<NumberLiteral, LiteralError> {
+    let radix = match text.get(..2) {
+        Some("0x" | "0X") => 16,
+        Some("0o" | "0O") => 8,
+        Some("0b" | "0B") => 2,
+        _ => 10,
+    };
+    let (value, suffix) = if radix == 10 {
+        parse_decimal(text)?
+    } else {
+        parse_radix(&text[2..], radix)?
+    };
+    Ok(NumberLiteral {
+        text: text.to_string(),
+        value,
+        suffix,
+    })
+}
+
+fn parse_suffix(suffix: &str) -> Result<Option<NumberSuffix>, LiteralError> {
+    if suffix.is_empty() {
+        return Ok(None);
+    }
+    NumberSuffix::from_name(suffix)
+        .map(Some)
+        .ok_or(LiteralError::InvalidSuffix)
+}
+
+/// Integer suffixes start with `u` or `i`, which are never hexadecimal digits
+fn parse_radix(text: &str, radix: u32) -> Result<(Number, Option<NumberSuffix>), LiteralError> {
+    let suffix_start = text.find(['u', 'i']).unwrap_or(text.len());
+    let (digits, suffix) = text.split_at(suffix_start);
+    let suffix = parse_suffix(suffix)?;
+    let digits = digits.replace('_', "");
+    if digits.is_empty() {
+        return Err(LiteralError::MissingDigits);
+    }
+    if let Some(invalid) = digits.chars().find(|c| !c.is_digit(radix)) {
+        return Err(LiteralError::InvalidDigit(invalid, radix));
+    }
+    let value = BigInt::from_str_radix(&digits, radix).expect("digits were validated");
+    check_range(&value, suffix)?;
+    Ok((Number::Integer(value), suffix))
+}
+
+fn parse_decimal(text: &str) -> Result<(Number, Option<NumberSuffix>), LiteralError> {
+    let is_digit = |c: char| c.is_ascii_digit() || c == '_';
+    let mut end = text.find(|c: char| !is_digit(c) && c != '.').unwrap_or(text.len());
+    let mut is_float = text[..end].contains('.');
+    if text[end..].starts_with(['e', 'E']) {
+        let exponent = text[end + 1..].strip_prefix(['+', '-']).unwrap_or(&text[end + 1..]);
+        let exponent_digits = exponent.find(|c: char| !is_digit(c)).unwrap_or(exponent.len());
+        if !exponent[..exponent_digits].contains(|c: char| c.is_ascii_digit()) {
+            return Err(LiteralError::MissingExponentDigits);
+        }
+        end = text.len() - exponent.len() + exponent_digits;
+        is_float = true;
+    }
+    let (number, suffix) = text.split_at(end);
+    let suffix = parse_suffix(suffix)?;
+    let number = number.replace('_', "");
+    match suffix {
+        Some(suffix) if is_float && !suffix.is_float() => {
+            Err(LiteralError::FloatWithIntegerSuffix(suffix))
+        }
+        Some(suffix) if suffix.is_float() => Ok((parse_float(&number, suffix)?, Some(suffix))),
+        _ if is_float => Ok((parse_float(&number, NumberSuffix::F64)?, suffix)),
+        _ => {
+            let value = BigInt::from_str_radix(&number, 10).expect("digits were validated");
+            check_range(&value, suffix)?;
+            Ok((Number::Integer(value), suffix))
+        }
+    }
+}
+
+/// Floats too large for their type are errors rather than infinity, unsuffixed floats are `f64`
+fn parse_float(number: &str, suffix: NumberSuffix) -> Result<Number, LiteralError> {
+    let value: f64 = number.parse().expect("float syntax was validated");
+    let fits = match suffix {
+        NumberSuffix::F32 => (value as f32).is_finite(),
+        _ => value.is_finite(),
+    };
+    if !fits {
+        return Err(LiteralError::OutOfRange(suffix));
+    }
+    Ok(Number::Float(value))
+}
+
+/// Negates a number literal preceded by a `-` where there is no operator to do it, such as in a pattern
+pub fn negate_number(number: NumberLiteral) -> Result<NumberLiteral, LiteralError> {
+    let value = match number.value {
+        Number::Integer(value) => Number::Integer(-value),
+        Number::Float(value) => Number::Float(-value),
+    };
+    match number.suffix {
+        Some(suffix) if suffix.integer_bits().is_some() && !suffix.is_signed() => {
+            Err(LiteralError::OutOfRange(suffix))
+        }
+        suffix => Ok(NumberLiteral {
+            text: format!("-{}", number.text),
+            value,
+            suffix,
+        }),
+    }
+}
+
+/// Literals are never negative, `-128i8` is a negation, so signed types allow one more than their maximum
+fn check_range(value: &BigInt, suffix: Option<NumberSuffix>) -> Result<(), LiteralError> {
+    let Some(suffix) = suffix else {
+        return Ok(());
+    };
+    let Some(bits) = suffix.integer_bits() else {
+        return Ok(());
+    };
+    let limit = if suffix.is_signed() {
+        BigInt::one() << (bits - 1)
+    } else {
+        (BigInt::one() << bits) - 1
+    };
+    if *value > limit {
+        return Err(LiteralError::OutOfRange(suffix));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tokenize, TokenKind};
+
+    use super::*;
+
+    fn integer(text: &str) -> (BigInt, Option<NumberSuffix>) {
+        match parse_number(text) {
+            Ok(NumberLiteral {
+                value: Number::Integer(value),
+                suffix,
+                ..
+            }) => (value, suffix),
+            other => panic!("{} is not an integer: {:?}", text, other),
+        }
+    }
+
+    fn float(text: &str) -> (f64, Option<NumberSuffix>) {
+        match parse_number(text) {
+            Ok(NumberLiteral {
+                value: Number::Float(value),
+                suffix,
+                ..
+            }) => (value, suffix),
+            other => panic!("{} is not a float: {:?}", text, other),
+        }
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(integer("42"), (42.into(), None));
+        assert_eq!(integer("1_000_000"), (1_000_000.into(), None));
+        assert_eq!(integer("0xFF"), (255.into(), None));
+        assert_eq!(integer("0o17"), (15.into(), None));
+        assert_eq!(integer("0b1010_1010"), (170.into(), None));
+        assert_eq!(
+            integer("123456789012345678901234567890"),
+            ("123456789012345678901234567890".parse().unwrap(), None)
+        );
+    }
+
+    #[test]
+    fn test_floats() {
+        assert_eq!(float("1.5"), (1.5, None));
+        assert_eq!(float(".5"), (0.5, None));
+        assert_eq!(float("1e3"), (1000.0, None));
+        assert_eq!(float("2.5E-2"), (0.025, None));
+        assert_eq!(float("1_0.2_5e+1"), (102.5, None));
+    }
+
+    #[test]
+    fn test_suffixes() {
+        assert_eq!(integer("10u8"), (10.into(), Some(NumberSuffix::U8)));
+        assert_eq!(integer("10_i64"), (10.into(), Some(NumberSuffix::I64)));
+        assert_eq!(integer("0xffu8"), (255.into(), Some(NumberSuffix::U8)));
+        // `f` is a hexadecimal digit, not the start of a suffix
+        assert_eq!(integer("0x1f32"), (0x1f32.into(), None));
+        assert_eq!(float("2.0f32"), (2.0, Some(NumberSuffix::F32)));
+        assert_eq!(float("2f64"), (2.0, Some(NumberSuffix::F64)));
+        assert_eq!(integer("128i8"), (128.into(), Some(NumberSuffix::I8)));
+        assert_eq!(integer("20px"), (20.into(), Some(NumberSuffix::Px)));
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert_eq!(parse_number("0x"), Err(LiteralError::MissingDigits));
+        assert_eq!(parse_number("0b_u8"), Err(LiteralError::MissingDigits));
+        assert_eq!(parse_number("0b102"), Err(LiteralError::InvalidDigit('2', 2)));
+        assert_eq!(parse_number("0o8"), Err(LiteralError::InvalidDigit('8', 8)));
+        assert_eq!(parse_number("0xfg"), Err(LiteralError::InvalidDigit('g', 16)));
+        assert_eq!(parse_number("1e"), Err(LiteralError::MissingExponentDigits));
+        assert_eq!(parse_number("1e+_"), Err(LiteralError::MissingExponentDigits));
+        assert_eq!(parse_number("12abc"), Err(LiteralError::InvalidSuffix));
+        assert_eq!(parse_number("1u7"), Err(LiteralError::InvalidSuffix));
+        assert_eq!(
+            parse_number("1.5u8"),
+            Err(LiteralError::FloatWithIntegerSuffix(NumberSuffix::U8))
+        );
+        assert_eq!(
+            parse_number("256u8"),
+            Err(LiteralError::OutOfRange(NumberSuffix::U8))
+        );
+        assert_eq!(
+            parse_number("129i8"),
+            Err(LiteralError::OutOfRange(NumberSuffix::I8))
+        );
+        assert_eq!(
+            parse_number("1e400"),
+            Err(LiteralError::OutOfRange(NumberSuffix::F64))
+        );
+        assert_eq!(
+            parse_number("1e39f32"),
+            Err(LiteralError::OutOfRange(NumberSuffix::F32))
+        );
+    }
+
+    #[test]
+    fn test_negation() {
+        let negated = negate_number(parse_number("128i8").unwrap()).unwrap();
+        assert_eq!(negated.text, "-128i8");
+        assert_eq!(negated.value, Number::Integer(BigInt::from(-128)));
+        let negated = negate_number(parse_number("1.5").unwrap()).unwrap();
+        assert_eq!(negated.value, Number::Float(-1.5));
+        assert_eq!(
+            negate_number(parse_number("1u8").unwrap()),
+            Err(LiteralError::OutOfRange(NumberSuffix::U8))
+        );
+    }
+
+    fn string(text: &str) -> String {
+        match string_parts(text).as_deref() {
+            Ok([StringPart::Text { text: contents, value }]) => {
+                assert_eq!(*contents, &text[1..text.len() - 1]);
+                value.clone()
+            }
+            other => panic!("{} is not a plain string: {:?}", text, other),
+        }
+    }
+
+    #[test]
+    fn test_escapes() {
+        assert_eq!(string(r#""plain""#), "plain");
+        assert_eq!(string(r#""a\nb\tc\r""#), "a\nb\tc\r");
+        assert_eq!(string(r#""\\ \" \' \0""#), "\\ \" ' \0");
+        assert_eq!(string(r"'\'\x41\x7f'"), "'A\x7f");
+        assert_eq!(string(r#""\u{1F600} \u{e9}""#), "\u{1F600} \u{e9}");
+        assert_eq!(string("\"multi\nline\""), "multi\nline");
+        assert_eq!(string(r#""\{ braces \}""#), "{ braces }");
+        assert_eq!(string(r#""""#), "");
+    }
+
+    #[test]
+    fn test_interpolation_parts() {
+        let text = r#""a\t{ b }{c("}")} d""#;
+        assert_eq!(
+            string_parts(text),
+            Ok(vec![
+                StringPart::Text {
+                    text: r"a\t",
+                    value: "a\t".to_string()
+                },
+                StringPart::Interpolation {
+                    source: " b ",
+                    offset: 5
+                },
+                StringPart::Interpolation {
+                    source: r#"c("}")"#,
+                    offset: 10
+                },
+                StringPart::Text {
+                    text: " d",
+                    value: " d".to_string()
+                },
+            ])
+        );
+        assert_eq!(
+            string_parts("'{x}'"),
+            Ok(vec![StringPart::Interpolation {
+                source: "x",
+                offset: 2
+            }])
+        );
+        assert_eq!(
+            string_parts(r#""a } b""#),
+            Err((LiteralError::UnmatchedBrace, 3..4))
+        );
+        assert_eq!(
+            string_parts(r#""a { b""#),
+            Err((LiteralError::UnclosedInterpolation, 3..4))
+        );
+        assert_eq!(
+            string_parts(r#""a { } b""#),
+            Err((LiteralError::EmptyInterpolation, 3..6))
+        );
+    }
+
+    #[test]
+    fn test_invalid_escapes() {
+        let error = |text: &str| string_parts(text).unwrap_err();
+        assert_eq!(error(r#""a\qb""#), (LiteralError::InvalidEscape, 2..4));
+        assert_eq!(error(r#""\x4""#), (LiteralError::InvalidHexEscape, 1..4));
+        assert_eq!(error(r#""\x80""#), (LiteralError::InvalidHexEscape, 1..5));
+        assert_eq!(error(r#""\u41""#), (LiteralError::InvalidUnicodeEscape, 1..3));
+        assert_eq!(error(r#""\u{41""#), (LiteralError::InvalidUnicodeEscape, 1..6));
+        assert_eq!(error(r#""\u{}""#), (LiteralError::InvalidUnicodeEscape, 1..5));
+        assert_eq!(
+            error(r#""\u{1234567}""#),
+            (LiteralError::InvalidUnicodeEscape, 1..12)
+        );
+        assert_eq!(
+            error(r#""\u{D800}""#),
+            (LiteralError::InvalidCodepoint(0xD800), 1..9)
+        );
+    }
+
+    #[test]
+    fn test_escape_errors_are_located() {
+        let tokens = tokenize("x = \"line\none \\q\"");
+        let string = tokens.iter().find(|token| token.kind() == TokenKind::String).unwrap();
+        let error = parse_literal(string).unwrap().unwrap_err();
+        assert!(matches!(
+            error.error,
+            ParserError::InvalidLiteral(LiteralError::InvalidEscape)
+        ));
+        assert_eq!((error.source_span.start.line, error.source_span.start.column), (1, 4));
+        assert_eq!((error.source_span.end.line, error.source_span.end.column), (1, 6));
+        assert_eq!(error.source_span.start.index, 14);
+    }
+
+    fn token_kinds(code: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(code)
+            .into_iter()
+            .map(|token| (token.kind(), token.text))
+            .collect()
+    }
+
+    #[test]
+    fn test_lexing() {
+        use TokenKind::*;
+        assert_eq!(
+            token_kinds("1..2"),
+            vec![(Number, "1"), (Range, ".."), (Number, "2")]
+        );
+        assert_eq!(
+            token_kinds("1.5e-3-x"),
+            vec![(Number, "1.5e-3"), (Minus, "-"), (Ident, "x")]
+        );
+        assert_eq!(
+            token_kinds("0x1e+5"),
+            vec![(Number, "0x1e"), (Plus, "+"), (Number, "5")]
+        );
+        assert_eq!(
+            token_kinds("1.max"),
+            vec![(Number, "1"), (Dot, "."), (Ident, "max")]
+        );
+        assert_eq!(token_kinds("0b12_u8"), vec![(Number, "0b12_u8")]);
+        assert_eq!(token_kinds(".5"), vec![(Number, ".5")]);
+    }
+
+    #[test]
+    fn test_lexing_interpolated_strings() {
+        use TokenKind::*;
+        let code = r#""see { docref("Install") }." + 'it\'s {a['}']}'"#;
+        assert_eq!(
+            token_kinds(code),
+            vec![
+                (String, r#""see { docref("Install") }.""#),
+                (Space, " "),
+                (Plus, "+"),
+                (Space, " "),
+                (String, r"'it\'s {a['}']}'"),
+            ]
+        );
+        assert_eq!(
+            token_kinds(r#""\{ not interpolated" x"#),
+            vec![(String, r#""\{ not interpolated""#), (Space, " "), (Ident, "x")]
+        );
+        assert_eq!(token_kinds(r#""a { b"#)[0], (Error, r#""a {"#));
+    }
+}
@@ -0,0 +1,137 @@
+use fst::{Attribute, AttributeList};
+use parser_core::*;
+
+use crate::{
+    expression::parse_expression,
+    separated_list::parser::comma_list,
+    utils::{opt, ws0},
+};
+
+/// Parses the attribute lists in front of an item, such as `#[static, cte]`
+pub fn parse_attributes<'a>(input: Span<'a>) -> ParserResult<'a, Vec<AttributeList>> {
+    let mut input = input;
+    let mut attributes = Vec::new();
+    // once a `#` is found the attribute list has to be valid, so that its error is not discarded
+    while input.tokens.first().is_some_and(|token| token.kind() == TokenKind::Hash) {
+        let (rest, list) = parse_attribute_list(input)?;
+        attributes.push(list);
+        input = rest;
+    }
+    Ok((input, attributes))
+}
+
+fn parse_attribute_list<'a>(input: Span<'a>) -> ParserResult<'a, AttributeList> {
+    let (input, _) = parse_hash(input)?;
+    let (input, _) = parse_left_bracket(input)?;
+    let (input, (attributes, _)) =
+        comma_list(parse_attribute.located(), parse_right_bracket, true)(input)?;
+    let (input, post_space) = ws0(input);
+    Ok((
+        input,
+        AttributeList {
+            attributes,
+            post_space,
+        },
+    ))
+}
+
+fn parse_attribute<'a>(input: Span<'a>) -> ParserResult<'a, Attribute> {
+    let (input, name) = parse_ident(input)?;
+    let (input, arguments) = opt((ws0, parse_left_paren).tuple())(input);
+    let (input, arguments) = match arguments {
+        Some((pre_arguments, _)) => {
+            let (input, (arguments, _)) =
+                comma_list(parse_expression, parse_right_paren, false)(input)?;
+            (input, Some((pre_arguments, arguments)))
+        }
+        None => (input, None),
+    };
+    Ok((
+        input,
+        Attribute {
+            name: name.to_string(),
+            arguments,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::{List, Located, Statement};
+
+    use crate::{
+        statement::parse_statement,
+        utils::{located, space, ParseString},
+    };
+
+    use super::*;
+
+    fn attribute(name: &str, arguments: Option<&str>) -> Located<Attribute> {
+        located(Attribute {
+            name: name.to_string(),
+            arguments: arguments.map(|argument| {
+                (
+                    vec![],
+                    List::new(vec![parse_expression.parse_string(argument).unwrap()]),
+                )
+            }),
+        })
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let attributes = parse_attributes
+            .parse_string("#[static, cte]\n#[requires_env(Console)] ")
+            .unwrap();
+        let mut first = List::new(vec![attribute("static", None), attribute("cte", None)]);
+        first.items[1].pre_space = space(" ");
+        assert_eq!(
+            attributes,
+            vec![
+                AttributeList {
+                    attributes: first,
+                    post_space: space("\n"),
+                },
+                AttributeList {
+                    attributes: List::new(vec![attribute("requires_env", Some("Console"))]),
+                    post_space: space(" "),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_attributes_on_items() {
+        for code in [
+            "#[cte] fn f() {}",
+            "#[a] struct A {}",
+            "#[a] enum A {}",
+            "#[a] type A = B;",
+            "#[a] trait A {}",
+            "#[a] impl A {}",
+            "#[a] mod a {}",
+        ] {
+            let statement = parse_statement.parse_string(code).unwrap();
+            let attributes = match statement.value {
+                Statement::Function { attributes, .. }
+                | Statement::Struct { attributes, .. }
+                | Statement::Enum { attributes, .. }
+                | Statement::TypeAlias { attributes, .. }
+                | Statement::Trait { attributes, .. }
+                | Statement::Impl { attributes, .. }
+                | Statement::Module { attributes, .. } => attributes,
+                other => panic!("Expected an item, got {:?}", other),
+            };
+            assert_eq!(attributes.len(), 1, "{}", code);
+            assert_eq!(statement.source_span.start.index, 0, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_invalid_attributes() {
+        assert!(parse_statement.parse_string("#[] fn f() {}").is_err());
+        assert!(parse_statement.parse_string("#[a] let b = 1;").is_err());
+        let error = parse_statement.parse_string("#[a(] fn f() {}").unwrap_err();
+        assert_eq!(error.source_span.start.index, 4);
+    }
+}
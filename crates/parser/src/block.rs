@@ -1,24 +1,49 @@
-use fst::Statement;
+use fst::{Block, Whitespace0};
 use parser_core::*;
 
-use crate::{separated_list::{self, parser::trailing_separated_list}, statement::parse_statement};
+use crate::statement::parse_statement;
 
 use super::utils::ws0;
 
-pub fn parse_block<'a>(input: Span<'a>) -> ParserResult<'a, Vec<Statement>> {
+pub fn parse_block<'a>(input: Span<'a>) -> ParserResult<'a, Block> {
     let (input, _) = parse_left_brace(input)?;
-    let (input, _) = ws0(input);
-    let (input, statements) = parse_righthand_block(input)?;
-    Ok((input, statements))
+    parse_righthand_block(input)
 }
 
-pub fn parse_righthand_block<'a>(input: Span<'a>) -> ParserResult<'a, Vec<Statement>> {
-    let (input, (statements, _)) = trailing_separated_list(
-        ws0,
-        parse_statement,
-        parse_right_brace,
-        true,
-        false,
-    )(input)?;
-    Ok((input, statements))
+pub fn parse_righthand_block<'a>(input: Span<'a>) -> ParserResult<'a, Block> {
+    let (input, (pre_space, statements)) = parse_braced_items(parse_statement)(input)?;
+    Ok((
+        input,
+        Block {
+            pre_space,
+            statements,
+        },
+    ))
+}
+
+/// The space in front of the first item and every item together with the space following it
+pub type BracedItems<O> = (Whitespace0, Vec<(O, Whitespace0)>);
+
+/// Parses items up to and including a closing brace, the opening brace must already be consumed.
+pub fn parse_braced_items<'a, O>(
+    parser: impl Fn(Span<'a>) -> ParserResult<'a, O>,
+) -> impl Fn(Span<'a>) -> ParserResult<'a, BracedItems<O>> {
+    move |input| {
+        let (mut input, pre_space) = ws0(input);
+        let mut items = Vec::new();
+        loop {
+            match parser(input) {
+                Ok((rest, item)) => {
+                    let (rest, space) = ws0(rest);
+                    items.push((item, space));
+                    input = rest;
+                }
+                Err(item_error) => {
+                    let (input, _) =
+                        parse_right_brace(input).map_err(|e| item_error.accumulate(e))?;
+                    return Ok((input, (pre_space, items)));
+                }
+            }
+        }
+    }
 }
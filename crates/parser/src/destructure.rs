@@ -1,10 +1,14 @@
 use fst::{
-    ImmutableDestructure, ImmutableDestructureProperty, ImmutableExtract, MutableAlias,
-    MutableDestructure, MutableDestructureProperty, MutableExtract,
+    Alias, ImmutableDestructure, ImmutableDestructureProperty, ImmutableExtract, List, Located,
+    MutableAlias, MutableDestructure, MutableDestructureProperty, MutableExtract, Spaced,
+    SubExtract,
 };
 use parser_core::*;
 
-use crate::{separated_list::parser::trailing_separated_list, utils::{opt, opt_bool, ws0}};
+use crate::{
+    separated_list::parser::{comma_list, strict_separated_list},
+    utils::{opt, ws0},
+};
 
 /// { mut a, b }
 /// { a as mut c, b as d }
@@ -13,15 +17,11 @@ use crate::{separated_list::parser::trailing_separated_list, utils::{opt, opt_bo
 /// { a.{b, c} as mut d }
 pub fn parse_mutable_destructure<'a>(input: Span<'a>) -> ParserResult<'a, MutableDestructure> {
     let (input, _) = parse_left_brace(input)?;
-
-    let (input, (properties, _)) = trailing_separated_list(
-        (ws0, parse_comma).tuple(),
-        preceded(ws0, parse_mutable_destructure_property),
-        (ws0, parse_right_brace).tuple(),
-        true,
+    let (input, (properties, _)) = comma_list(
+        parse_mutable_destructure_property.located(),
+        parse_right_brace,
         false,
     )(input)?;
-
     Ok((input, properties))
 }
 
@@ -34,36 +34,48 @@ fn parse_mutable_destructure_property<'a>(
         Some(Token::Mut) => {
             // MutablePropertyChain
 
-            let (input, (property_chain, _)) = separated_list(
-                (ws0, parse_dot).tuple(),
-                preceded(ws0, parse_ident).map(|s| s.to_string()),
+            let (input, (property_chain, _)) = strict_separated_list(
+                (ws0, parse_dot).tuple().map(|(space, _)| space),
+                (ws0, parse_ident.map(|s| s.to_string())).tuple(),
                 (ws0, any_of(TokenKind::Comma | TokenKind::RightBrace)).tuple(),
                 false,
-                false,
                 true,
-            )(input)?;
+            )(start_input)?;
 
             Ok((
                 input,
-                MutableDestructureProperty::MutablePropertyChain { property_chain },
+                MutableDestructureProperty::MutablePropertyChain {
+                    property_chain: property_chain.into_spaced_values(),
+                },
             ))
         }
         Some(Token::Ident(property_name)) => {
-            let (input, _) = ws0(start_input);
+            let (input, pre_space) = ws0(start_input);
             let (input, (token, _)) = input.take_token();
 
             match token.delocate() {
                 Some(Token::Dot) => {
-                    let (input, _) = ws0(input);
+                    let (input, post_dot) = ws0(input);
                     let (input, extract) = parse_mutable_extract(input)?;
+                    let pre_dot = pre_space;
 
                     let (input, alias) = opt(|input| {
-                        let (input, _) = ws0(input);
+                        let (input, pre_as) = ws0(input);
                         let as_token_span = input.first_token_span();
                         let (input, _) = parse_as(input)?;
-                        let (input, _) = ws0(input);
+                        let (input, post_as) = ws0(input);
                         let (input, alias) = parse_mutable_alias(input)?;
-                        Ok((input, (as_token_span, alias)))
+                        Ok((
+                            input,
+                            (
+                                as_token_span,
+                                Alias {
+                                    pre_as,
+                                    post_as,
+                                    alias,
+                                },
+                            ),
+                        ))
                     })(input);
 
                     match alias {
@@ -75,7 +87,11 @@ fn parse_mutable_destructure_property<'a>(
                                     input,
                                     MutableDestructureProperty::AliasedSubProperties {
                                         property_name: property_name.to_string(),
-                                        extract: immutable_extract,
+                                        extract: SubExtract {
+                                            pre_dot,
+                                            post_dot,
+                                            extract: immutable_extract,
+                                        },
                                         alias,
                                     },
                                 )),
@@ -90,19 +106,27 @@ fn parse_mutable_destructure_property<'a>(
                             input,
                             MutableDestructureProperty::UnaliasedSubProperties {
                                 property_name: property_name.to_string(),
-                                extract,
+                                extract: SubExtract {
+                                    pre_dot,
+                                    post_dot,
+                                    extract,
+                                },
                             },
                         )),
                     }
                 }
                 Some(Token::As) => {
-                    let (input, _) = ws0(input);
+                    let (input, post_as) = ws0(input);
                     let (input, alias) = parse_mutable_alias(input)?;
                     Ok((
                         input,
                         MutableDestructureProperty::Property {
                             property_name: property_name.to_string(),
-                            alias: Some(alias),
+                            alias: Some(Alias {
+                                pre_as: pre_space,
+                                post_as,
+                                alias,
+                            }),
                         },
                     ))
                 }
@@ -120,7 +144,7 @@ fn parse_mutable_destructure_property<'a>(
 }
 
 fn parse_mutable_alias<'a>(input: Span<'a>) -> ParserResult<'a, MutableAlias> {
-    let (input, mutable) = opt_bool((parse_mut, ws0).tuple())(input);
+    let (input, mutable) = opt((parse_mut, ws0).tuple().map(|(_, space)| space))(input);
 
     let (input, alias) = parse_ident(input)?;
 
@@ -136,23 +160,20 @@ fn parse_mutable_alias<'a>(input: Span<'a>) -> ParserResult<'a, MutableAlias> {
 pub fn parse_mutable_extract<'a>(input: Span<'a>) -> ParserResult<'a, MutableExtract> {
     (
         parse_mutable_destructure.map(|p| MutableExtract::Destructured(p)),
-        parse_mutable_destructure_property.map(|p| MutableExtract::DirectProperty(Box::new(p))),
+        parse_mutable_destructure_property
+            .located()
+            .map(|p| MutableExtract::DirectProperty(Box::new(p))),
     )
         .alt()(input)
 }
 
 pub fn parse_immutable_destructure<'a>(input: Span<'a>) -> ParserResult<'a, ImmutableDestructure> {
     let (input, _) = parse_left_brace(input)?;
-
-    let (input, (properties, _)) = separated_list(
-        (ws0, parse_comma).tuple(),
-        preceded(ws0, parse_immutable_destructure_property),
-        (ws0, parse_right_brace).tuple(),
-        true,
-        true,
+    let (input, (properties, _)) = comma_list(
+        parse_immutable_destructure_property.located(),
+        parse_right_brace,
         false,
     )(input)?;
-
     Ok((input, properties))
 }
 
@@ -163,11 +184,19 @@ fn parse_immutable_destructure_property<'a>(
 
     let (input, extract) = opt((ws0, parse_dot, ws0, parse_immutable_extract)
         .tuple()
-        .map(|(_, _, _, p)| p))(input);
+        .map(|(pre_dot, _, post_dot, extract)| SubExtract {
+            pre_dot,
+            post_dot,
+            extract,
+        }))(input);
 
     let (input, alias) = opt((ws0, parse_as, ws0, parse_ident)
         .tuple()
-        .map(|(_, _, _, p)| p.to_string()))(input);
+        .map(|(pre_as, _, post_as, alias)| Alias {
+            pre_as,
+            post_as,
+            alias: alias.to_string(),
+        }))(input);
 
     Ok((
         input,
@@ -182,7 +211,9 @@ fn parse_immutable_destructure_property<'a>(
 pub fn parse_immutable_extract<'a>(input: Span<'a>) -> ParserResult<'a, ImmutableExtract> {
     (
         parse_immutable_destructure.map(|p| ImmutableExtract::Destructured(p)),
-        parse_immutable_destructure_property.map(|p| ImmutableExtract::DirectProperty(Box::new(p))),
+        parse_immutable_destructure_property
+            .located()
+            .map(|p| ImmutableExtract::DirectProperty(Box::new(p))),
     )
         .alt()(input)
 }
@@ -190,14 +221,22 @@ pub fn parse_immutable_extract<'a>(input: Span<'a>) -> ParserResult<'a, Immutabl
 fn mutable_extract_to_immutable<'a>(extract: MutableExtract) -> Option<ImmutableExtract> {
     match extract {
         MutableExtract::Destructured(p) => {
-            let mut immutable_properties = Vec::with_capacity(p.len());
-
-            for property in p {
-                let immutable_property = mutable_destructure_property_to_immutable(property)?;
-                immutable_properties.push(immutable_property);
+            let mut immutable_properties = Vec::with_capacity(p.items.len());
+
+            for property in p.items {
+                let immutable_property = mutable_destructure_property_to_immutable(property.value)?;
+                immutable_properties.push(Spaced {
+                    pre_space: property.pre_space,
+                    value: immutable_property,
+                    post_space: property.post_space,
+                });
             }
 
-            Some(ImmutableExtract::Destructured(immutable_properties))
+            Some(ImmutableExtract::Destructured(List {
+                items: immutable_properties,
+                trailing_comma: p.trailing_comma,
+                end_space: p.end_space,
+            }))
         }
         MutableExtract::DirectProperty(p) => {
             let immutable_property = mutable_destructure_property_to_immutable(*p)?;
@@ -209,20 +248,25 @@ fn mutable_extract_to_immutable<'a>(extract: MutableExtract) -> Option<Immutable
 }
 
 fn mutable_destructure_property_to_immutable<'a>(
-    property: MutableDestructureProperty,
-) -> Option<ImmutableDestructureProperty> {
-    match property {
+    property: Located<MutableDestructureProperty>,
+) -> Option<Located<ImmutableDestructureProperty>> {
+    let source_span = property.source_span;
+    let immutable_property = match property.value {
         MutableDestructureProperty::AliasedSubProperties {
             property_name,
             extract,
             alias,
         } => {
-            if alias.mutable {
+            if alias.alias.mutable.is_some() {
                 return None;
             }
 
             Some(ImmutableDestructureProperty {
-                alias: Some(alias.alias),
+                alias: Some(Alias {
+                    pre_as: alias.pre_as,
+                    post_as: alias.post_as,
+                    alias: alias.alias.alias,
+                }),
                 extract: Some(extract),
                 property_name,
             })
@@ -232,12 +276,16 @@ fn mutable_destructure_property_to_immutable<'a>(
             alias,
         } => match alias {
             Some(alias) => {
-                if alias.mutable {
+                if alias.alias.mutable.is_some() {
                     return None;
                 }
 
                 Some(ImmutableDestructureProperty {
-                    alias: Some(alias.alias),
+                    alias: Some(Alias {
+                        pre_as: alias.pre_as,
+                        post_as: alias.post_as,
+                        alias: alias.alias.alias,
+                    }),
                     extract: None,
                     property_name,
                 })
@@ -252,22 +300,82 @@ fn mutable_destructure_property_to_immutable<'a>(
             property_name,
             extract,
         } => {
-            let immutable_extract = mutable_extract_to_immutable(extract)?;
+            let immutable_extract = mutable_extract_to_immutable(extract.extract)?;
 
             Some(ImmutableDestructureProperty {
                 alias: None,
-                extract: Some(immutable_extract),
+                extract: Some(SubExtract {
+                    pre_dot: extract.pre_dot,
+                    post_dot: extract.post_dot,
+                    extract: immutable_extract,
+                }),
                 property_name,
             })
         }
         MutableDestructureProperty::MutablePropertyChain { .. } => None,
-    }
+    };
+    immutable_property.map(|property| Located::new(property, source_span))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::ParseString;
+    use crate::utils::{located, space, ParseString};
+    use fst::SpaceElement;
+
+    /// A list written as `a, b`
+    fn list<T>(items: Vec<T>) -> List<T> {
+        let mut list = List::new(items);
+        for item in list.items.iter_mut().skip(1) {
+            item.pre_space = space(" ");
+        }
+        list
+    }
+
+    /// A list written as `{ a, b }`, with spaces inside the braces
+    fn padded_list<T>(items: Vec<T>) -> List<T> {
+        let mut list = list(items);
+        list.items.first_mut().unwrap().pre_space = space(" ");
+        list.items.last_mut().unwrap().post_space = space(" ");
+        list
+    }
+
+    /// `{space}as{space}{alias}`
+    fn alias<T>(alias: T) -> Alias<T> {
+        Alias {
+            pre_as: space(" "),
+            post_as: space(" "),
+            alias,
+        }
+    }
+
+    /// `.{extract}`
+    fn sub_extract<T>(extract: T) -> SubExtract<T> {
+        SubExtract {
+            pre_dot: vec![],
+            post_dot: vec![],
+            extract,
+        }
+    }
+
+    /// `mut{space}{name}`
+    fn mutable_chain(name: &str) -> MutableDestructureProperty {
+        MutableDestructureProperty::MutablePropertyChain {
+            property_chain: vec![Spaced {
+                pre_space: space(" "),
+                value: name.to_string(),
+                post_space: vec![],
+            }],
+        }
+    }
+
+    fn immutable_property(name: &str) -> ImmutableDestructureProperty {
+        ImmutableDestructureProperty {
+            property_name: name.to_string(),
+            extract: None,
+            alias: None,
+        }
+    }
 
     #[test]
     fn test_parse_mutable_destructure_basic() {
@@ -276,15 +384,13 @@ mod tests {
         let result = parse_mutable_destructure.parse_string(input).unwrap();
         assert_eq!(
             result,
-            vec![
-                MutableDestructureProperty::MutablePropertyChain {
-                    property_chain: vec!["a".to_string()],
-                },
-                MutableDestructureProperty::Property {
+            padded_list(vec![
+                located(mutable_chain("a")),
+                located(MutableDestructureProperty::Property {
                     property_name: "b".to_string(),
                     alias: None,
-                },
-            ]
+                }),
+            ])
         );
     }
 
@@ -295,22 +401,22 @@ mod tests {
         let result = parse_mutable_destructure.parse_string(input).unwrap();
         assert_eq!(
             result,
-            vec![
-                MutableDestructureProperty::Property {
+            padded_list(vec![
+                located(MutableDestructureProperty::Property {
                     property_name: "a".to_string(),
-                    alias: Some(MutableAlias {
-                        mutable: true,
+                    alias: Some(alias(MutableAlias {
+                        mutable: Some(space(" ")),
                         alias: "c".to_string(),
-                    }),
-                },
-                MutableDestructureProperty::Property {
+                    })),
+                }),
+                located(MutableDestructureProperty::Property {
                     property_name: "b".to_string(),
-                    alias: Some(MutableAlias {
-                        mutable: false,
+                    alias: Some(alias(MutableAlias {
+                        mutable: None,
                         alias: "d".to_string(),
-                    }),
-                },
-            ]
+                    })),
+                }),
+            ])
         );
     }
 
@@ -321,18 +427,16 @@ mod tests {
         let result = parse_mutable_destructure.parse_string(input).unwrap();
         assert_eq!(
             result,
-            vec![MutableDestructureProperty::UnaliasedSubProperties {
+            padded_list(vec![located(MutableDestructureProperty::UnaliasedSubProperties {
                 property_name: "a".to_string(),
-                extract: MutableExtract::Destructured(vec![
-                    MutableDestructureProperty::Property {
+                extract: sub_extract(MutableExtract::Destructured(list(vec![
+                    located(MutableDestructureProperty::Property {
                         property_name: "b".to_string(),
                         alias: None,
-                    },
-                    MutableDestructureProperty::MutablePropertyChain {
-                        property_chain: vec!["c".to_string()],
-                    },
-                ]),
-            }]
+                    }),
+                    located(mutable_chain("c")),
+                ]))),
+            })])
         );
     }
 
@@ -343,25 +447,17 @@ mod tests {
         let result = parse_mutable_destructure.parse_string(input).unwrap();
         assert_eq!(
             result,
-            vec![MutableDestructureProperty::AliasedSubProperties {
+            padded_list(vec![located(MutableDestructureProperty::AliasedSubProperties {
                 property_name: "a".to_string(),
-                extract: ImmutableExtract::Destructured(vec![
-                    ImmutableDestructureProperty {
-                        property_name: "b".to_string(),
-                        extract: None,
-                        alias: None,
-                    },
-                    ImmutableDestructureProperty {
-                        property_name: "c".to_string(),
-                        extract: None,
-                        alias: None,
-                    },
-                ]),
-                alias: MutableAlias {
-                    mutable: true,
+                extract: sub_extract(ImmutableExtract::Destructured(list(vec![
+                    located(immutable_property("b")),
+                    located(immutable_property("c")),
+                ]))),
+                alias: alias(MutableAlias {
+                    mutable: Some(space(" ")),
                     alias: "d".to_string(),
-                },
-            }]
+                }),
+            })])
         );
     }
 
@@ -372,18 +468,10 @@ mod tests {
         let result = parse_immutable_destructure.parse_string(input).unwrap();
         assert_eq!(
             result,
-            vec![
-                ImmutableDestructureProperty {
-                    property_name: "a".to_string(),
-                    extract: None,
-                    alias: None,
-                },
-                ImmutableDestructureProperty {
-                    property_name: "b".to_string(),
-                    extract: None,
-                    alias: None,
-                },
-            ]
+            padded_list(vec![
+                located(immutable_property("a")),
+                located(immutable_property("b")),
+            ])
         );
     }
 
@@ -394,18 +482,18 @@ mod tests {
         let result = parse_immutable_destructure.parse_string(input).unwrap();
         assert_eq!(
             result,
-            vec![
-                ImmutableDestructureProperty {
+            padded_list(vec![
+                located(ImmutableDestructureProperty {
                     property_name: "a".to_string(),
                     extract: None,
-                    alias: Some("x".to_string()),
-                },
-                ImmutableDestructureProperty {
+                    alias: Some(alias("x".to_string())),
+                }),
+                located(ImmutableDestructureProperty {
                     property_name: "b".to_string(),
                     extract: None,
-                    alias: Some("y".to_string()),
-                },
-            ]
+                    alias: Some(alias("y".to_string())),
+                }),
+            ])
         );
     }
 
@@ -416,22 +504,40 @@ mod tests {
         let result = parse_immutable_destructure.parse_string(input).unwrap();
         assert_eq!(
             result,
-            vec![ImmutableDestructureProperty {
+            padded_list(vec![located(ImmutableDestructureProperty {
                 property_name: "a".to_string(),
-                extract: Some(ImmutableExtract::Destructured(vec![
-                    ImmutableDestructureProperty {
-                        property_name: "b".to_string(),
-                        extract: None,
-                        alias: None,
-                    },
-                    ImmutableDestructureProperty {
-                        property_name: "c".to_string(),
-                        extract: None,
-                        alias: None,
-                    },
-                ])),
+                extract: Some(sub_extract(ImmutableExtract::Destructured(list(vec![
+                    located(immutable_property("b")),
+                    located(immutable_property("c")),
+                ])))),
                 alias: None,
-            }]
+            })])
         );
     }
+
+    #[test]
+    fn test_parse_destructure_keeps_whitespace() {
+        let input = "{ a /* x */ as\n mut c , }";
+        let result = parse_mutable_destructure.parse_string(input).unwrap();
+        assert!(result.trailing_comma);
+        assert_eq!(result.end_space, space(" "));
+        let item = &result.items[0];
+        assert_eq!(item.post_space, space(" "));
+        match &item.value.value {
+            MutableDestructureProperty::Property {
+                alias: Some(alias), ..
+            } => {
+                assert_eq!(
+                    alias.pre_as,
+                    vec![
+                        SpaceElement::Space(" ".to_string()),
+                        SpaceElement::BlockComment("/* x */".to_string()),
+                        SpaceElement::Space(" ".to_string()),
+                    ]
+                );
+                assert_eq!(alias.post_as, space("\n "));
+            }
+            other => panic!("Expected an aliased property, got {:?}", other),
+        }
+    }
 }
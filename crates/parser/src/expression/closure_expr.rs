@@ -1,12 +1,14 @@
-use fst::{Closure, ClosureSignature, Expression};
+use fst::{
+    BodySeparator, Closure, ClosureSignature, Expression, List, ParameterDelimiter, ReturnType,
+};
 use parser_core::*;
 
 use crate::{
-    function_parameters::parse_function_parameters,
+    function_parameters::{parse_function_parameters, parse_pipe_parameters},
     utils::{opt, ws0, ws1},
 };
 
-use super::parse_expression;
+use super::{parse_expression, parse_type_expression};
 
 // Examples:
 // a -> a + 2 // valid
@@ -15,53 +17,205 @@ use super::parse_expression;
 // (a: Int) -> a + 2 // valid
 // (a: Int, b) -> a + b // valid
 // a -> Int
+// |a| a + 2 // valid
+// |a: Int, b| -> Int { a + b } // valid
+// || 2 // valid
 
 pub fn parse_closure_expr(input: Span) -> ParserResult<Expression> {
-    let (input, params) = match parse_left_paren(input) {
+    (parse_pipe_closure, parse_arrow_closure).alt()(input)
+}
+
+/// `|a, b| a + b`, a `|` can only start a closure here as the union operator and `||` both
+/// follow an operand
+fn parse_pipe_closure(input: Span) -> ParserResult<Expression> {
+    let (input, params) = match parse_or(input) {
+        // `||` is lexed as a single token, the closure has no parameters
+        Ok((input, _)) => (input, List::new(Vec::new())),
+        Err(or_error) => {
+            let (input, _) = parse_vertical_bar(input).map_err(|e| or_error.accumulate(e))?;
+            parse_pipe_parameters(input)?
+        }
+    };
+    let (input, pre_arrow) = ws0(input);
+    let return_type = opt((parse_arrow, ws0, parse_type_expression).tuple())(input);
+    let (input, return_type, separator) = match return_type {
+        (input, Some((_, post_arrow, value_type))) => {
+            let (input, before_body) = ws0(input);
+            let return_type = ReturnType {
+                pre_arrow,
+                post_arrow,
+                value_type,
+            };
+            (input, Some(return_type), before_body)
+        }
+        (_, None) => (input, None, pre_arrow),
+    };
+    let (input, body) = parse_expression(input)?;
+    Ok((
+        input,
+        Expression::Closure {
+            closure: Box::new(Closure {
+                closure_signature: ClosureSignature {
+                    receiver: None,
+                    params,
+                    return_type,
+                },
+                separator: BodySeparator::Space(separator),
+                body,
+            }),
+            delimiter: ParameterDelimiter::Pipes,
+        },
+    ))
+}
+
+fn parse_arrow_closure(input: Span) -> ParserResult<Expression> {
+    let (input, (params, pre_arrow, delimiter)) = match parse_left_paren(input) {
         Ok((input, _)) => {
-            let (input, _) = ws0(input);
-            let (input, params) = parse_function_parameters(
+            let (input, (params, (_, pre_arrow, _))) = parse_function_parameters(
                 false,
-                (ws0, parse_right_paren).tuple(),
+                (parse_right_paren, ws0, parse_arrow).tuple(),
             )(input)?;
-            (input, params)
+            (input, (params, pre_arrow, ParameterDelimiter::Parentheses))
         }
         Err(left_paren_error) => {
-            let (input, params) = parse_function_parameters(true, parse_arrow)(input)
+            // The space in front of the arrow is kept by the parameter list
+            let (input, (params, _)) = parse_function_parameters(true, parse_arrow)(input)
                 .map_err(|e| left_paren_error.accumulate(e))?;
             // All following errors are guaranteed to be better than the left paren error
             // because the left paren error is at offset 0, while all following errors are at offset 1 or more due to require_at_least_one
             // This means we don't need to accumulate anymore
-            (input, params)
+            (input, (params, Vec::new(), ParameterDelimiter::None))
         }
     };
-    let (input, _) = ws0(input);
-    let (input, expr1) = parse_expression(input)?;
-    let (input, expr2) = opt((ws1, parse_do, ws1, parse_expression).tuple().map(|v| v.3))(input);
-    match expr2 {
-        Some(expr2) => Ok((
+
+    let (input, post_arrow) = ws0(input);
+    // Only a return type is followed by `do`, so it is read as a type first and the
+    // expression is read again as the body when there is no `do`
+    let return_type =
+        opt((parse_type_expression, ws1, parse_do, ws1, parse_expression).tuple())(input);
+
+    let (input, closure) = match return_type {
+        (input, Some((value_type, pre_do, _, post_do, body))) => (
             input,
-            Expression::Closure {
-                closure: Box::new(Closure {
-                    body: expr2,
-                    closure_signature: ClosureSignature {
-                        params,
-                        return_type: Some(expr1),
-                    },
-                }),
+            Closure {
+                closure_signature: ClosureSignature {
+                    receiver: None,
+                    params,
+                    return_type: Some(ReturnType {
+                        pre_arrow,
+                        post_arrow,
+                        value_type,
+                    }),
+                },
+                separator: BodySeparator::Do(pre_do, post_do),
+                body,
             },
-        )),
-        None => Ok((
-            input,
-            Expression::Closure {
-                closure: Box::new(Closure {
-                    body: expr1,
+        ),
+        (_, None) => {
+            let (input, body) = parse_expression(input)?;
+            (
+                input,
+                Closure {
                     closure_signature: ClosureSignature {
+                        receiver: None,
                         params,
                         return_type: None,
                     },
-                }),
-            },
-        )),
+                    separator: BodySeparator::Arrow(pre_arrow, post_arrow),
+                    body,
+                },
+            )
+        }
+    };
+    Ok((
+        input,
+        Expression::Closure {
+            closure: Box::new(closure),
+            delimiter,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::{Located, Operator, UnaryOperation};
+
+    use crate::utils::ParseString;
+
+    use super::*;
+
+    fn pipe_closure(code: &str) -> (Vec<String>, Closure) {
+        let result = parse_expression.parse_string(code).unwrap();
+        let Expression::Closure {
+            closure,
+            delimiter: ParameterDelimiter::Pipes,
+        } = result.value
+        else {
+            panic!("Expected a pipe closure, got {:?}", result.value);
+        };
+        let params = closure
+            .closure_signature
+            .params
+            .values()
+            .map(|param| format!("{:?}", param.creation.value))
+            .collect();
+        (params, *closure)
+    }
+
+    fn operator(expression: &Located<Expression>) -> Option<Operator> {
+        match &expression.value {
+            Expression::Operation { operator, .. } => Some(*operator),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_pipe_closures() {
+        assert_eq!(pipe_closure("|doc| doc.name").0.len(), 1);
+        assert_eq!(pipe_closure("| a , b |a + b").0.len(), 2);
+        assert!(pipe_closure("|| 1").0.is_empty());
+        assert!(pipe_closure("| | 1").0.is_empty());
+        let (_, closure) = pipe_closure("|a: Int| -> Int { a }");
+        assert!(closure.closure_signature.return_type.is_some());
+        assert!(matches!(closure.body.value, Expression::Block { .. }));
+    }
+
+    #[test]
+    fn test_pipes_in_closures() {
+        // the parameter type ends at the `|`, while the body may be a union
+        let (_, closure) = pipe_closure("|a: Int| a | b");
+        let value_type = &closure
+            .closure_signature
+            .params
+            .values()
+            .next()
+            .unwrap()
+            .value_type;
+        assert_eq!(operator(&value_type.as_ref().unwrap().value_type), None);
+        assert_eq!(operator(&closure.body), Some(Operator::Union));
+        // a union is allowed in the generic arguments of a parameter type
+        assert_eq!(pipe_closure("|a: List<A | B>, b| a").0.len(), 2);
+    }
+
+    #[test]
+    fn test_pipe_operators() {
+        let union = parse_expression.parse_string("a | b").unwrap();
+        assert_eq!(operator(&union), Some(Operator::Union));
+        let or = parse_expression.parse_string("a || b").unwrap();
+        assert_eq!(operator(&or), Some(Operator::Or));
+        let call = parse_expression
+            .parse_string("docs.find(|doc| doc.name == name)")
+            .unwrap();
+        let Expression::SingleOperation {
+            operation: UnaryOperation::Call { arguments },
+            ..
+        } = call.value
+        else {
+            panic!("Expected a call, got {:?}", call.value);
+        };
+        assert!(matches!(
+            arguments.values().next().unwrap().value,
+            Expression::Closure { .. }
+        ));
     }
 }
@@ -0,0 +1,12 @@
+use fst::Expression;
+use parser_core::*;
+
+use crate::{block::parse_block, utils::ws0};
+
+/// const { a }
+pub fn parse_const_expr(input: Span) -> ParserResult<Expression> {
+    let (input, _) = parse_const(input)?;
+    let (input, after_const) = ws0(input);
+    let (input, block) = parse_block(input)?;
+    Ok((input, Expression::Const { after_const, block }))
+}
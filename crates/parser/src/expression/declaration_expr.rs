@@ -1,50 +1,33 @@
 use crate::{
-    destructure::parse_mutable_destructure,
     expression::parse_expression,
-    utils::{opt, ws0, ws1},
+    function_parameters::parse_type_annotation,
+    utils::{opt, ws0},
+    variable_creation::parse_variable_creation,
 };
-use fst::{VariableCreation, Expression};
+use fst::{Expression, Initializer};
 use parser_core::*;
 
 pub fn parse_declaration_expr<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
     let (input, _) = parse_let(input)?;
-    let (input, _) = ws0(input);
-
-    let (input, declared) = parse_variable_creation(input)?;
-
-    let (input, value_type) =
-        opt((ws0, parse_colon, ws0, parse_expression).tuple()).map(|v| match v {
-            Some((_, _, _, type_)) => Some(type_),
-            None => None,
-        })(input);
-
-    let (input, _) = ws0(input);
-
-    let (input, expression_opt) =
-        opt((parse_assignment, ws0, parse_expression).tuple()).map(|v| match v {
-            Some((_, _, expression)) => Some(expression),
-            None => None,
-        })(input);
-
+    let (input, after_let) = ws0(input);
+    let (input, declared) = parse_variable_creation.located()(input)?;
+    let (input, value_type) = opt(parse_type_annotation)(input);
+    let (input, initializer) = opt((ws0, parse_assignment, ws0, parse_expression)
+        .tuple()
+        .map(
+            |(pre_assignment, _, post_assignment, value)| Initializer {
+                pre_assignment,
+                post_assignment,
+                value: Box::new(value),
+            },
+        ))(input);
     Ok((
         input,
         Expression::Declaration {
+            after_let,
             creation: declared,
-            value_type: value_type.map(|v| Box::new(v)),
-            initializer: expression_opt.map(|v| Box::new(v)),
+            value_type,
+            initializer,
         },
     ))
 }
-
-fn parse_variable_creation<'a>(input: Span<'a>) -> ParserResult<'a, VariableCreation> {
-    (
-        (opt((parse_mut, ws1).tuple()), parse_ident)
-            .tuple()
-            .map(|(mutable, ident)| VariableCreation::Identifier {
-                name: ident.to_string(),
-                mutable: mutable.is_some(),
-            }),
-        parse_mutable_destructure.map(|destructure| VariableCreation::Destructure { destructure }),
-    )
-        .alt()(input)
-}
@@ -0,0 +1,13 @@
+use fst::Expression;
+use parser_core::*;
+
+/// rs { println!("{}", a) }
+pub fn parse_foreign_expr(input: Span) -> ParserResult<Expression> {
+    let (input, source) = parse_foreign_block(input)?;
+    Ok((
+        input,
+        Expression::Foreign {
+            source: source.to_string(),
+        },
+    ))
+}
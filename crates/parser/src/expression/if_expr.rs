@@ -1,28 +1,58 @@
 use crate::{
     block::parse_block,
-    expression::parse_expression,
-    utils::{opt, ws0, ws1},
+    expression::parse_condition,
+    utils::{opt, ws0},
 };
-use fst::{Expression, Statement};
+use fst::{Else, Expression, IfBlock};
 use parser_core::*;
 
-fn parse_if_block<'a>(input: Span<'a>) -> ParserResult<'a, (Expression, Vec<Statement>)> {
+fn parse_if_block<'a>(input: Span<'a>) -> ParserResult<'a, IfBlock> {
     let (input, _) = parse_if(input)?;
-    let (input, _) = ws0(input);
-    let (input, condition) = parse_expression(input)?;
-    let (input, _) = ws0(input);
-    let (input, code) = parse_block(input)?;
-    Ok((input, (condition, code)))
+    let (input, after_if) = ws0(input);
+    let (input, condition) = parse_condition(input)?;
+    let (input, before_block) = ws0(input);
+    let (input, block) = parse_block(input)?;
+    Ok((
+        input,
+        IfBlock {
+            after_if,
+            condition: Box::new(condition),
+            before_block,
+            block,
+        },
+    ))
 }
 
-pub fn parse_if_expr<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
-    let (input, first_block) = parse_if_block(input)?;
-    let (input, else_if_blocks) =
-        many0((ws0, parse_else, ws1, parse_if_block).tuple().map(|e| e.3))(input);
-    let (input, else_block) = opt((ws0, parse_else, ws1, parse_block).tuple().map(|v| v.3))(input);
-
-    let mut blocks = vec![first_block];
-    blocks.extend(else_if_blocks);
+/// Parses `else` followed by `parser`, together with the space on both sides of the keyword
+pub fn parse_else_clause<'a, O>(
+    parser: impl Fn(Span<'a>) -> ParserResult<'a, O>,
+) -> impl Fn(Span<'a>) -> ParserResult<'a, Else<O>> {
+    move |input| {
+        let (input, pre_else) = ws0(input);
+        let (input, _) = parse_else(input)?;
+        let (input, post_else) = ws0(input);
+        let (input, value) = parser(input)?;
+        Ok((
+            input,
+            Else {
+                pre_else,
+                post_else,
+                value,
+            },
+        ))
+    }
+}
 
-    Ok((input, Expression::If { blocks, else_block }))
+pub fn parse_if_expr<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
+    let (input, if_block) = parse_if_block(input)?;
+    let (input, else_if_blocks) = many0(parse_else_clause(parse_if_block))(input);
+    let (input, else_block) = opt(parse_else_clause(parse_block))(input);
+    Ok((
+        input,
+        Expression::If {
+            if_block,
+            else_if_blocks,
+            else_block,
+        },
+    ))
 }
@@ -0,0 +1,162 @@
+use enumset::EnumSet;
+use fst::{Expression, InterpolatedPart, Located, Location, SourceSpan, Spaced};
+use lexer::TokenKind;
+use parser_core::{
+    literal::{location_within, string_parts, StringPart},
+    *,
+};
+
+use crate::utils::ws0;
+
+use super::parse_expression;
+
+/// Parses a string token containing `{ expression }` interpolations.
+///
+/// The expressions are tokenized and parsed separately, their locations are those within the file.
+pub fn parse_interpolated_string(token: &LocatedToken) -> Result<Expression, LocatedParserError> {
+    let parts = string_parts(token.text).map_err(|(error, range)| {
+        ParserError::InvalidLiteral(error).locate(SourceSpan {
+            start: location_within(token, range.start),
+            end: location_within(token, range.end),
+        })
+    })?;
+    let parts = parts
+        .into_iter()
+        .map(|part| match part {
+            StringPart::Text { text, value } => Ok(InterpolatedPart::Text {
+                text: text.to_string(),
+                value,
+            }),
+            StringPart::Interpolation { source, offset } => {
+                parse_interpolation(source, location_within(token, offset))
+                    .map(|expression| InterpolatedPart::Expression(Box::new(expression)))
+            }
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Expression::InterpolatedString {
+        quote: token.text.chars().next().unwrap_or('"'),
+        parts,
+    })
+}
+
+fn parse_interpolation(
+    source: &str,
+    start: Location,
+) -> Result<Spaced<Located<Expression>>, LocatedParserError> {
+    let tokens = tokenize_at(source, start);
+    let input = Span {
+        tokens: &tokens,
+        start,
+    };
+    let (rest, (pre_space, value, post_space)) = (ws0, parse_expression, ws0).tuple()(input)?;
+    if let Some(token) = rest.tokens.first() {
+        let expected: EnumSet<TokenKind> = TokenKind::RightBrace.into();
+        return Err(Some(*token).as_parser_error(expected, token.source_span));
+    }
+    Ok(Spaced {
+        pre_space,
+        value,
+        post_space,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::Literal;
+
+    use crate::{
+        expression::literal_expr::parse_literal_expr,
+        utils::{space, ParseString},
+    };
+
+    use super::*;
+
+    fn text(text: &str, value: &str) -> InterpolatedPart {
+        InterpolatedPart::Text {
+            text: text.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_interpolated_string() {
+        let input = r#""sum:\t{ a + b }, {f("{x}")}""#;
+        let expected = Expression::InterpolatedString {
+            quote: '"',
+            parts: vec![
+                text(r"sum:\t", "sum:\t"),
+                InterpolatedPart::Expression(Box::new(Spaced {
+                    pre_space: space(" "),
+                    value: parse_expression.parse_string("a + b").unwrap(),
+                    post_space: space(" "),
+                })),
+                text(", ", ", "),
+                InterpolatedPart::Expression(Box::new(Spaced::new(
+                    parse_expression.parse_string(r#"f("{x}")"#).unwrap(),
+                ))),
+            ],
+        };
+        assert_eq!(parse_literal_expr.parse_string(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_escaped_braces_are_not_interpolated() {
+        let result = parse_literal_expr.parse_string(r#"'\{a\}'"#).unwrap();
+        match result {
+            Expression::Literal {
+                value: Literal::String(string),
+            } => assert_eq!(string.value, "{a}"),
+            other => panic!("Expected a string literal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_interpolation_locations() {
+        let tokens = tokenize("x(\n  \"a {b + c} d\")");
+        let string = tokens.iter().position(|token| token.kind() == TokenKind::String).unwrap();
+        let input = Span {
+            tokens: &tokens[string..],
+            start: tokens[string].source_span.start,
+        };
+        let (_, expression) = parse_literal_expr(input).unwrap();
+        let Expression::InterpolatedString { parts, .. } = expression else {
+            panic!("Expected an interpolated string, got {:?}", expression);
+        };
+        let InterpolatedPart::Expression(expression) = &parts[1] else {
+            panic!("Expected an interpolation, got {:?}", parts[1]);
+        };
+        let span = expression.value.source_span;
+        assert_eq!((span.start.line, span.start.column, span.start.index), (1, 6, 9));
+        assert_eq!((span.end.line, span.end.column, span.end.index), (1, 11, 14));
+    }
+
+    #[test]
+    fn test_invalid_interpolations() {
+        let error = parse_literal_expr.parse_string(r#""a {b c} d""#).unwrap_err();
+        assert!(matches!(
+            error.error,
+            ParserError::UnexpectedToken(Some(TokenKind::Ident), _)
+        ));
+        assert_eq!(error.source_span.start.index, 6);
+
+        let error = parse_literal_expr.parse_string(r#""a {}""#).unwrap_err();
+        assert!(matches!(
+            error.error,
+            ParserError::InvalidLiteral(literal::LiteralError::EmptyInterpolation)
+        ));
+        assert_eq!(error.source_span.start.index, 3);
+        assert_eq!(error.source_span.end.index, 5);
+
+        let error = parse_literal_expr.parse_string(r#""{  }""#).unwrap_err();
+        assert!(matches!(
+            error.error,
+            ParserError::InvalidLiteral(literal::LiteralError::EmptyInterpolation)
+        ));
+
+        let error = parse_literal_expr.parse_string(r#""a } b""#).unwrap_err();
+        assert!(matches!(
+            error.error,
+            ParserError::InvalidLiteral(literal::LiteralError::UnmatchedBrace)
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use fst::Expression;
+use fst::{Expression, Located, Whitespace0};
 use parser_core::*;
 
 use crate::{
@@ -7,63 +7,66 @@ use crate::{
     utils::{opt, ws0},
 };
 
-use super::parse_expression;
+use super::{if_expr::parse_else_clause, parse_condition, parse_expression};
 
-fn parse_optional_label<'a>(input: Span<'a>) -> SafeParserResult<'a, Option<&'a str>> {
-    opt((parse_label, ws0).tuple().map(|(label, _)| label))(input)
+fn parse_optional_label<'a>(input: Span<'a>) -> SafeParserResult<'a, Option<(String, Whitespace0)>> {
+    opt((parse_label, ws0)
+        .tuple()
+        .map(|(label, space)| (label.to_string(), space)))(input)
 }
 
-pub fn parse_while_expr<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
-    let (input, label) = parse_optional_label(input);
-    let (input, _) = parse_while(input)?;
-    let (input, _) = ws0(input);
-    let (input, condition) = parse_expression(input)?;
-    let (input, _) = ws0(input);
+/// Parses either `do <expression>` or a block `{ ... }`,
+/// returning the body and the space after `do` if it used `do`
+fn parse_loop_body<'a>(
+    input: Span<'a>,
+) -> ParserResult<'a, (Located<Expression>, Option<Whitespace0>)> {
+    let body_start = input;
     let (input, (token, source_span)) = input.take_token();
-    let (input, body) = match token.delocate() {
+    match token.delocate() {
         Some(Token::Do) => {
-            let (input, _) = ws0(input);
+            let (input, space) = ws0(input);
             let (input, body) = parse_expression(input)?;
-            (input, body)
+            Ok((input, (body, Some(space))))
         }
         Some(Token::LeftBrace) => {
-            let (input, _) = ws0(input);
-            let (input, statements) = parse_righthand_block(input)?;
-            (
+            let (input, block) = parse_righthand_block(input)?;
+            Ok((
                 input,
-                Expression::Block {
-                    environment: None,
-                    block: statements,
-                },
-            )
+                (
+                    Located::new(
+                        Expression::Block {
+                            environment: None,
+                            block,
+                        },
+                        body_start.source_span_until(&input),
+                    ),
+                    None,
+                ),
+            ))
         }
-        _ => {
-            return Err(token.as_parser_error(TokenKind::Do | TokenKind::LeftBrace, source_span));
-        }
-    };
-    let (input, _) = ws0(input);
-
-    if let Ok((input, _)) = parse_else(input) {
-        let (input, _) = ws0(input);
-        let (input, else_block) = parse_expression(input)?;
-        return Ok((
-            input,
-            Expression::While {
-                label: label.map(|s| s.to_string()),
-                condition: Box::new(condition),
-                body: Box::new(body),
-                else_block: Some(Box::new(else_block)),
-            },
-        ));
+        _ => Err(token.as_parser_error(TokenKind::Do | TokenKind::LeftBrace, source_span)),
     }
+}
+
+pub fn parse_while_expr<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
+    let (input, label) = parse_optional_label(input);
+    let (input, _) = parse_while(input)?;
+    let (input, after_while) = ws0(input);
+    let (input, condition) = parse_condition(input)?;
+    let (input, before_body) = ws0(input);
+    let (input, (body, do_body)) = parse_loop_body(input)?;
+    let (input, else_block) = opt(parse_else_clause(parse_expression.map(Box::new)))(input);
 
     Ok((
         input,
         Expression::While {
-            label: label.map(|s| s.to_string()),
+            label,
+            after_while,
             condition: Box::new(condition),
+            before_body,
+            do_body,
             body: Box::new(body),
-            else_block: None,
+            else_block,
         },
     ))
 }
@@ -71,13 +74,14 @@ pub fn parse_while_expr<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
 pub fn parse_loop_expr<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
     let (input, label) = parse_optional_label(input);
     let (input, _) = parse_loop(input)?;
-    let (input, _) = ws0(input);
+    let (input, after_loop) = ws0(input);
     let (input, expression) = parse_expression(input)?;
 
     Ok((
         input,
         Expression::Loop {
-            label: label.map(|s| s.to_string()),
+            label,
+            after_loop,
             body: Box::new(expression),
         },
     ))
@@ -86,60 +90,29 @@ pub fn parse_loop_expr<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
 pub fn parse_for_expr<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
     let (input, label) = parse_optional_label(input);
     let (input, _) = parse_for(input)?;
-    let (input, _) = ws0(input);
+    let (input, after_for) = ws0(input);
     let (input, destructure) = parse_mutable_destructure(input)?;
-    let (input, _) = ws0(input);
+    let (input, before_in) = ws0(input);
     let (input, _) = parse_in(input)?;
-    let (input, _) = ws0(input);
-    let (input, iterator) = parse_expression(input)?;
-    let (input, _) = ws0(input);
-    let (input, (token, source_span)) = input.take_token();
-    let (input, body) = match token.delocate() {
-        Some(Token::Do) => {
-            let (input, _) = ws0(input);
-            let (input, body) = parse_expression(input)?;
-            (input, body)
-        }
-        Some(Token::LeftBrace) => {
-            let (input, _) = ws0(input);
-            let (input, statements) = parse_righthand_block(input)?;
-            (
-                input,
-                Expression::Block {
-                    environment: None,
-                    block: statements,
-                },
-            )
-        }
-        _ => {
-            return Err(token.as_parser_error(TokenKind::Do | TokenKind::LeftBrace, source_span));
-        }
-    };
-    let (input, _) = ws0(input);
-
-    if let Ok((input, _)) = parse_else(input) {
-        let (input, _) = ws0(input);
-        let (input, else_block) = parse_expression(input)?;
-        return Ok((
-            input,
-            Expression::For {
-                label: label.map(|s| s.to_string()),
-                destructure,
-                iterator: Box::new(iterator),
-                body: Box::new(body),
-                else_block: Some(Box::new(else_block)),
-            },
-        ));
-    }
+    let (input, after_in) = ws0(input);
+    let (input, iterator) = parse_condition(input)?;
+    let (input, before_body) = ws0(input);
+    let (input, (body, do_body)) = parse_loop_body(input)?;
+    let (input, else_block) = opt(parse_else_clause(parse_expression.map(Box::new)))(input);
 
     Ok((
         input,
         Expression::For {
-            label: label.map(|s| s.to_string()),
+            label,
+            after_for,
             destructure,
+            before_in,
+            after_in,
             iterator: Box::new(iterator),
+            before_body,
+            do_body,
             body: Box::new(body),
-            else_block: None,
+            else_block,
         },
     ))
 }
@@ -0,0 +1,231 @@
+use fst::{Expression, Guard, List, Located, MatchArm, Spaced};
+use parser_core::*;
+
+use crate::{
+    block::parse_block,
+    pattern::parse_pattern,
+    utils::{opt, ws0},
+};
+
+use super::{
+    const_expr::parse_const_expr, if_expr::parse_if_expr, parse_condition, parse_expression,
+    unsafe_expr::parse_unsafe_expr,
+};
+
+/// match a { Some(b) if b > 0 => b, _ => 0 }
+pub fn parse_match_expr(input: Span) -> ParserResult<Expression> {
+    let (input, _) = parse_match(input)?;
+    let (input, after_match) = ws0(input);
+    let (input, scrutinee) = parse_condition(input)?;
+    let (input, before_arms) = ws0(input);
+    let (input, _) = parse_left_brace(input)?;
+    let (input, arms) = parse_arms(input)?;
+    Ok((
+        input,
+        Expression::Match {
+            after_match,
+            scrutinee: Box::new(scrutinee),
+            before_arms,
+            arms,
+        },
+    ))
+}
+
+/// The arms up to and including the closing brace, separated by commas like a `List` except that
+/// the comma after an arm whose body ends with a block can be left out
+fn parse_arms(mut input: Span) -> ParserResult<List<Located<MatchArm>>> {
+    let mut items: Vec<Spaced<Located<MatchArm>>> = Vec::new();
+    loop {
+        let (rest, pre_space) = ws0(input);
+        let (rest, mut arm) = match parse_match_arm.located()(rest) {
+            Ok(result) => result,
+            Err(error) => {
+                let (rest, _) = parse_right_brace(rest).map_err(|e| error.accumulate(e))?;
+                let trailing_comma = !items.is_empty();
+                return Ok((
+                    rest,
+                    List {
+                        items,
+                        trailing_comma,
+                        end_space: pre_space,
+                    },
+                ));
+            }
+        };
+
+        let (after_space, post_space) = ws0(rest);
+        input = match parse_comma(after_space) {
+            Ok((after_comma, _)) => after_comma,
+            Err(error) => match parse_right_brace(after_space) {
+                Ok((after_brace, _)) => {
+                    items.push(Spaced {
+                        pre_space,
+                        value: arm,
+                        post_space,
+                    });
+                    return Ok((
+                        after_brace,
+                        List {
+                            items,
+                            trailing_comma: false,
+                            end_space: Vec::new(),
+                        },
+                    ));
+                }
+                Err(_) if ends_with_block(&arm.value.body.value) => {
+                    // the space up to the next arm is in front of it
+                    arm.value.omits_comma = true;
+                    items.push(Spaced {
+                        pre_space,
+                        value: arm,
+                        post_space: Vec::new(),
+                    });
+                    input = rest;
+                    continue;
+                }
+                Err(brace_error) => return Err(error.accumulate(brace_error)),
+            },
+        };
+        items.push(Spaced {
+            pre_space,
+            value: arm,
+            post_space,
+        });
+    }
+}
+
+/// A body that starts with a block ends at the block when the arm is not followed by a comma or
+/// the closing brace, `1 => { a } -1 => b` is two arms rather than a subtraction
+fn parse_arm_body(input: Span) -> ParserResult<Located<Expression>> {
+    let expression = parse_expression(input);
+    if let Ok((rest, _)) = &expression {
+        let (rest, _) = ws0(*rest);
+        if parse_comma(rest).is_ok() || parse_right_brace(rest).is_ok() {
+            return expression;
+        }
+    }
+    let block = (
+        parse_if_expr,
+        parse_unsafe_expr,
+        parse_const_expr,
+        parse_match_expr,
+        parse_block.map(|block| Expression::Block {
+            environment: None,
+            block,
+        }),
+    )
+        .alt()
+        .located()(input);
+    block.or(expression)
+}
+
+fn ends_with_block(expression: &Expression) -> bool {
+    matches!(
+        expression,
+        Expression::Block { .. }
+            | Expression::Unsafe { .. }
+            | Expression::Const { .. }
+            | Expression::Match { .. }
+            | Expression::If { .. }
+    )
+}
+
+fn parse_match_arm(input: Span) -> ParserResult<MatchArm> {
+    let (input, pattern) = parse_pattern(input)?;
+    let (input, guard) = opt((ws0, parse_if, ws0, parse_expression).tuple().map(
+        |(pre_if, _, after_if, condition)| Guard {
+            pre_if,
+            after_if,
+            condition,
+        },
+    ))(input);
+    let (input, pre_arrow) = ws0(input);
+    let (input, _) = parse_fat_arrow(input)?;
+    let (input, post_arrow) = ws0(input);
+    let (input, body) = parse_arm_body(input)?;
+    Ok((
+        input,
+        MatchArm {
+            pattern,
+            guard,
+            pre_arrow,
+            post_arrow,
+            body,
+            omits_comma: false,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::{List, Located, Pattern};
+
+    use crate::utils::ParseString;
+
+    use super::*;
+
+    fn arms(code: &str) -> List<Located<MatchArm>> {
+        let result = parse_expression.parse_string(code).unwrap();
+        let Expression::Match { arms, .. } = result.value else {
+            panic!("Expected a match, got {:?}", result.value);
+        };
+        arms
+    }
+
+    #[test]
+    fn test_match() {
+        let arms = arms("match a { Some(b) if b > 0 => b, Some(_) => 0, None => -1, }");
+        assert_eq!(arms.values().count(), 3);
+        assert!(arms.trailing_comma);
+        let first = &arms.values().next().unwrap().value;
+        assert!(first.guard.is_some());
+        assert!(matches!(first.body.value, Expression::Variable { .. }));
+        let last = &arms.values().last().unwrap().value;
+        assert!(matches!(last.pattern.value, Pattern::Binding { .. }));
+    }
+
+    #[test]
+    fn test_match_scrutinee() {
+        // the scrutinee ends at the arms like an `if` condition
+        let result = parse_expression
+            .parse_string("match shape.kind {\n    Shape.Circle { radius } => radius\n}")
+            .unwrap();
+        let Expression::Match {
+            scrutinee, arms, ..
+        } = result.value
+        else {
+            panic!("Expected a match, got {:?}", result.value);
+        };
+        assert!(matches!(
+            scrutinee.value,
+            Expression::SingleOperation { .. }
+        ));
+        assert_eq!(arms.values().count(), 1);
+        assert!(matches!(
+            parse_expression.parse_string("match a {}").unwrap().value,
+            Expression::Match { .. }
+        ));
+    }
+
+    #[test]
+    fn test_block_bodies() {
+        let arms = arms("match a { 1 | 2 => { b }, 3..10 => c }");
+        let first = &arms.values().next().unwrap().value;
+        assert!(matches!(first.pattern.value, Pattern::Alternatives(_)));
+        assert!(matches!(first.body.value, Expression::Block { .. }));
+    }
+
+    #[test]
+    fn test_arms_without_comma() {
+        let arms = arms("match a { 1 => { b }\n -1 => if c { d } _ => e }");
+        assert_eq!(arms.values().count(), 3);
+        assert!(!arms.trailing_comma);
+        let omits_comma: Vec<_> = arms.values().map(|arm| arm.value.omits_comma).collect();
+        assert_eq!(omits_comma, vec![true, true, false]);
+        assert!(!arms.items[1].pre_space.is_empty());
+        // only a body ending with a block can leave out the comma
+        assert!(parse_expression
+            .parse_string("match a { 1 => b 2 => c }")
+            .is_err());
+    }
+}
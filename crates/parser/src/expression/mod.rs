@@ -1,12 +1,20 @@
 mod array_expr;
 mod closure_expr;
+mod const_expr;
 mod declaration_expr;
+mod foreign_expr;
 mod identifier_expr;
 mod if_expr;
+mod interpolated_string_expr;
 mod literal_expr;
 mod loops_expr;
+mod match_expr;
 mod call_arguments;
 mod pratt;
+mod struct_literal_expr;
+mod unsafe_expr;
 
-pub use self::pratt::parse_expression;
+pub use self::pratt::{
+    parse_condition, parse_expression, parse_pipe_parameter_type, parse_type_expression,
+};
 
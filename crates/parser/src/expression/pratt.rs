@@ -5,6 +5,7 @@ use lexer::TokenKind;
 use crate::{
     block::parse_block,
     destructure::parse_immutable_extract,
+    separated_list::parser::comma_list,
     utils::{opt, token_branch, ws0},
 };
 use parser_core::*;
@@ -13,13 +14,33 @@ use super::{
     array_expr::parse_array_expr,
     call_arguments::parse_call_arguments,
     closure_expr::parse_closure_expr,
+    const_expr::parse_const_expr,
     declaration_expr::parse_declaration_expr,
+    foreign_expr::parse_foreign_expr,
     identifier_expr::parse_variable_expr,
     if_expr::parse_if_expr,
     literal_expr::parse_literal_expr,
     loops_expr::{parse_for_expr, parse_loop_expr, parse_while_expr},
+    match_expr::parse_match_expr,
+    struct_literal_expr::{parse_anonymous_struct_literal, parse_struct_literal_fields},
+    unsafe_expr::parse_unsafe_expr,
 };
 
+/// Where an expression is written, which decides how `<` and `>` are read
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ExpressionContext {
+    /// `a < b` is a comparison and `A { a }` is a struct literal
+    Value,
+    /// The condition of an `if` or a loop, a value in which `{` after an operand starts the
+    /// body instead of a struct literal, `if a == A { a }`
+    Condition,
+    /// `Option<T>` applies generic arguments, `>` is never an operator so it can close them
+    Type,
+    /// The type of a `|a: T| a` closure parameter, where `|` closes the parameters instead of
+    /// forming a union
+    PipeParameterType,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 struct InfixOperator {
     token: TokenKind,
@@ -287,7 +308,8 @@ fn parse_prefix<'a>(input: Span<'a>) -> ParserResult<'a, PrattUnary> {
     });
     let reference = |input| {
         let (input, _) = parse_ampersand(input)?;
-        let (input, mutable) = opt((parse_mut, ws0).tuple()).map(|v| v.is_some())(input);
+        // the space after `mut` is kept as the space between the operator and its operand
+        let (input, mutable) = opt(parse_mut).map(|v| v.is_some())(input);
         Ok((
             input,
             PrattUnary {
@@ -299,7 +321,7 @@ fn parse_prefix<'a>(input: Span<'a>) -> ParserResult<'a, PrattUnary> {
     (basic_prefix, reference).alt()(input)
 }
 
-fn parse_postfix<'a>(input: Span<'a>) -> ParserResult<'a, PrattUnary> {
+fn parse_postfix<'a>(input: Span<'a>, context: ExpressionContext) -> ParserResult<'a, PrattUnary> {
     let basic_postfix =
         token_branch(&POSTFIX_OPERATORS, |op| op.token).map(|operator| PrattUnary {
             operation: operator.operator.clone(),
@@ -307,13 +329,13 @@ fn parse_postfix<'a>(input: Span<'a>) -> ParserResult<'a, PrattUnary> {
         });
     let extract = |input| {
         let (input, _) = parse_dot(input)?;
-        let (input, _) = ws0(input);
+        let (input, dot_space) = ws0(input);
         let (input, extract) = parse_immutable_extract(input)?;
         Ok((
             input,
             PrattUnary {
-                binding: 0,
-                operation: UnaryOperation::Extract { extract },
+                binding: 28,
+                operation: UnaryOperation::Extract { dot_space, extract },
             },
         ))
     };
@@ -322,43 +344,110 @@ fn parse_postfix<'a>(input: Span<'a>) -> ParserResult<'a, PrattUnary> {
         Ok((
             input,
             PrattUnary {
-                binding: 0,
+                binding: 28,
                 operation: UnaryOperation::Call { arguments },
             },
         ))
     };
     let get_property = |input| {
         let (input, _) = parse_left_bracket(input)?;
-        let (input, _) = ws0(input);
+        let (input, pre_space) = ws0(input);
         let (input, property) = parse_expression(input)?;
-        let (input, _) = ws0(input);
+        let (input, post_space) = ws0(input);
         let (input, _) = parse_right_bracket(input)?;
         Ok((
             input,
             PrattUnary {
-                binding: 0,
+                binding: 28,
                 operation: UnaryOperation::Get {
+                    pre_space,
                     property: Box::new(property),
+                    post_space,
                 },
             },
         ))
     };
-    (basic_postfix, extract, function_call, get_property).alt()(input)
+    let array_of = |input| {
+        let (input, _) = parse_left_bracket(input)?;
+        let (input, space) = ws0(input);
+        let (input, _) = parse_right_bracket(input)?;
+        Ok((
+            input,
+            PrattUnary {
+                binding: 28,
+                operation: UnaryOperation::ArrayOf { space },
+            },
+        ))
+    };
+    let generic_arguments = |input| {
+        let (input, _) = parse_less_than(input)?;
+        let (input, (arguments, _)) =
+            comma_list(parse_type_expression, parse_greater_than, true)(input)?;
+        Ok((
+            input,
+            PrattUnary {
+                binding: 28,
+                operation: UnaryOperation::GenericArguments { arguments },
+            },
+        ))
+    };
+    match context {
+        ExpressionContext::Value | ExpressionContext::Condition => {
+            (basic_postfix, extract, function_call, array_of, get_property).alt()(input)
+        }
+        ExpressionContext::Type | ExpressionContext::PipeParameterType => (
+            basic_postfix,
+            extract,
+            function_call,
+            array_of,
+            get_property,
+            generic_arguments,
+        )
+            .alt()(input),
+    }
 }
 
-fn parse_infix<'a>(input: Span<'a>) -> ParserResult<'a, InfixOperator> {
+fn parse_infix<'a>(input: Span<'a>, context: ExpressionContext) -> ParserResult<'a, InfixOperator> {
     let branch = token_branch(&INFIX_OPERATORS, |op| op.token);
-    branch(input).map(|(input, operator)| (input, operator.clone()))
+    let (rest, operator) = branch(input)?;
+    let rejected = match context {
+        ExpressionContext::Value | ExpressionContext::Condition => false,
+        // `=` ends a type, it starts the value in `let a: int = 1`
+        ExpressionContext::Type => matches!(
+            operator.token,
+            TokenKind::LessThan | TokenKind::GreaterThan | TokenKind::Assignment
+        ),
+        ExpressionContext::PipeParameterType => matches!(
+            operator.token,
+            TokenKind::LessThan
+                | TokenKind::GreaterThan
+                | TokenKind::VerticalBar
+                | TokenKind::Assignment
+        ),
+    };
+    if rejected {
+        let (_, (token, source_span)) = input.take_token();
+        return Err(token.as_parser_error(EnumSet::empty(), source_span));
+    }
+    Ok((rest, *operator))
 }
 
-fn parse_primary<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
+fn parse_primary<'a>(input: Span<'a>) -> ParserResult<'a, Located<Expression>> {
     // None of these will ever start with an expression
     (
-        delimited(
-            (parse_left_paren, ws0).tuple(),
+        (
+            parse_left_paren,
+            ws0,
             parse_expression,
-            (ws0, parse_right_paren).tuple(),
-        ),
+            ws0,
+            parse_right_paren,
+        )
+            .tuple()
+            .map(|(_, pre_space, expr, post_space, _)| Expression::Parenthesized {
+                pre_space,
+                expr: Box::new(expr),
+                post_space,
+            }),
         parse_variable_expr,
         parse_literal_expr,
         parse_array_expr,
@@ -368,17 +457,25 @@ fn parse_primary<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
         parse_while_expr,
         parse_loop_expr,
         parse_for_expr,
+        parse_unsafe_expr,
+        parse_const_expr,
+        parse_foreign_expr,
+        parse_match_expr,
+        parse_anonymous_struct_literal,
         parse_block.map(|code| Expression::Block {
             block: code,
             environment: None,
         }),
     )
-        .alt()(input)
+        .alt()
+        .located()(input)
 }
 
 enum PrattOperator {
     Postfix(PrattUnary),
     Infix(InfixOperator),
+    /// The fields of a struct literal, which follow its name like a postfix operator
+    StructLiteral(List<StructLiteralField>),
 }
 
 impl PrattOperator {
@@ -386,20 +483,72 @@ impl PrattOperator {
         match self {
             PrattOperator::Postfix(op) => op.binding,
             PrattOperator::Infix(op) => op.left_binding,
+            PrattOperator::StructLiteral(_) => 28,
         }
     }
 }
 
-fn parse_pratt_operator<'a>(input: Span<'a>) -> ParserResult<'a, PrattOperator> {
-    (
-        parse_infix.map(|op| PrattOperator::Infix(op)),
-        parse_postfix.map(|op| PrattOperator::Postfix(op)),
-    )
-        .alt()(input)
+/// An operator together with the whitespace in front of it
+struct SpacedPrattOperator {
+    pre_space: Whitespace0,
+    operator: PrattOperator,
+}
+
+fn parse_pratt_operator<'a>(
+    input: Span<'a>,
+    context: ExpressionContext,
+) -> ParserResult<'a, SpacedPrattOperator> {
+    let (input, pre_space) = ws0(input);
+    let infix = (|input| parse_infix(input, context)).map(PrattOperator::Infix);
+    let postfix = (|input| parse_postfix(input, context)).map(PrattOperator::Postfix);
+    // a `{` after a type or a condition starts the body that follows it
+    let (input, operator) = match context {
+        ExpressionContext::Value => (
+            infix,
+            postfix,
+            parse_struct_literal_fields.map(PrattOperator::StructLiteral),
+        )
+            .alt()(input)?,
+        _ => (infix, postfix).alt()(input)?,
+    };
+    Ok((
+        input,
+        SpacedPrattOperator {
+            pre_space,
+            operator,
+        },
+    ))
 }
 
-pub fn parse_expression<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
-    let (input, (expr, pratt_operator)) = parse_expression_pratt(input, 0)?;
+pub fn parse_expression<'a>(input: Span<'a>) -> ParserResult<'a, Located<Expression>> {
+    let (input, (expr, pratt_operator)) =
+        parse_expression_pratt(input, 0, ExpressionContext::Value)?;
+    debug_assert!(pratt_operator.is_none());
+    Ok((input, expr))
+}
+
+/// Parses an expression in a type position, such as a type annotation, a return type or a
+/// generic bound, where `Option<T>` applies generic arguments instead of comparing
+pub fn parse_type_expression<'a>(input: Span<'a>) -> ParserResult<'a, Located<Expression>> {
+    let (input, (expr, pratt_operator)) =
+        parse_expression_pratt(input, 0, ExpressionContext::Type)?;
+    debug_assert!(pratt_operator.is_none());
+    Ok((input, expr))
+}
+
+/// Parses the type annotation of a `|a: T| a` closure parameter, which can't be a union as the
+/// `|` closes the parameters
+pub fn parse_pipe_parameter_type<'a>(input: Span<'a>) -> ParserResult<'a, Located<Expression>> {
+    let (input, (expr, pratt_operator)) =
+        parse_expression_pratt(input, 0, ExpressionContext::PipeParameterType)?;
+    debug_assert!(pratt_operator.is_none());
+    Ok((input, expr))
+}
+
+/// Parses the condition of an `if` or a loop, which ends at the `{` of the body
+pub fn parse_condition<'a>(input: Span<'a>) -> ParserResult<'a, Located<Expression>> {
+    let (input, (expr, pratt_operator)) =
+        parse_expression_pratt(input, 0, ExpressionContext::Condition)?;
     debug_assert!(pratt_operator.is_none());
     Ok((input, expr))
 }
@@ -412,53 +561,57 @@ pub fn parse_expression<'a>(input: Span<'a>) -> ParserResult<'a, Expression> {
 fn parse_expression_pratt<'a>(
     input: Span<'a>,
     min_left_binding: u8,
-) -> ParserResult<'a, (Expression, Option<PrattOperator>)> {
+    context: ExpressionContext,
+) -> ParserResult<'a, (Located<Expression>, Option<SpacedPrattOperator>)> {
     // Begin by attempting to parse a prefix operator (e.g., unary minus, logical NOT).
     // If a prefix operator is found, parse the corresponding right operand recursively
     // with the prefix's binding power as the new minimum binding power.
     let (mut left_input, (mut left, mut pratt_operator)) = {
         match parse_prefix(input) {
             // A prefix operator is found
-            Ok((input, prefix)) => {
-                // Consume any whitespace following the prefix.
-                let (input, _) = ws0(input);
+            Ok((after_prefix, prefix)) => {
+                // Keep any whitespace following the prefix.
+                let (after_prefix, space) = ws0(after_prefix);
 
                 // Recursively parse the right-hand side of the prefix operation.
                 // The prefix operator's binding power dictates the new minimum binding power.
-                let (input, (right, next_pratt_operator)) =
-                    parse_expression_pratt(input, prefix.binding)?;
-
-                // Construct the expression for this prefix operation.
-                let expr = Expression::SingleOperation {
-                    operation: prefix.operation,
-                    operand: Box::new(right),
-                };
+                let (after_operand, (right, next_pratt_operator)) =
+                    parse_expression_pratt(after_prefix, prefix.binding, context)?;
+
+                // Construct the expression for this prefix operation,
+                // spanning from the prefix to the end of the operand.
+                let source_span = input.first_token_span().to(&right.source_span);
+                let expr = Located::new(
+                    Expression::SingleOperation {
+                        operation: prefix.operation,
+                        space,
+                        operand: Box::new(right),
+                    },
+                    source_span,
+                );
 
                 // next_pratt_operator is present IF the called parse_pratt_operator
                 // successfully parsed an operator, but had a lower binding power than
                 // the current operator.
                 match next_pratt_operator {
-                    Some(next_pratt_operator) => (input, (expr, next_pratt_operator)),
+                    Some(next_pratt_operator) => (after_operand, (expr, next_pratt_operator)),
                     // If no more operators are found, return the current expression.
-                    None => return Ok((input, (expr, None))),
+                    None => return Ok((after_operand, (expr, None))),
                 }
             }
             Err(e1) => {
                 // If no prefix is found, parse the primary expression (like literals, variables, etc.).
-                let (pre_ws0_input, expr) = parse_primary(input).map_err(|e2| e1.accumulate(e2))?;
-
-                // Consume any whitespace after the primary expression.
-                let (input, _) = ws0(pre_ws0_input);
+                let (input, expr) = parse_primary(input).map_err(|e2| e1.accumulate(e2))?;
 
-                // Check if there is a Pratt operator (infix or postfix) immediately following.
-                let (input, next_pratt_operator) = match parse_pratt_operator(input) {
-                    Ok((input, operator)) => (input, operator),
+                // Check if there is a Pratt operator (infix or postfix) following, possibly after whitespace.
+                let (after_operator, next_pratt_operator) = match parse_pratt_operator(input, context) {
+                    Ok((after_operator, operator)) => (after_operator, operator),
                     // If no operator is found, return the parsed primary expression.
-                    Err(_) => return Ok((pre_ws0_input, (expr, None))),
+                    Err(_) => return Ok((input, (expr, None))),
                 };
 
                 // Set up the left expression and current operator for further processing in the loop.
-                (input, (expr, next_pratt_operator))
+                (after_operator, (expr, next_pratt_operator))
             }
         }
     };
@@ -467,28 +620,40 @@ fn parse_expression_pratt<'a>(
     loop {
         // If the current operator's binding power is less than the minimum binding power
         // required, the loop breaks, ending the recursive parsing.
-        if pratt_operator.left_binding() < min_left_binding {
+        if pratt_operator.operator.left_binding() < min_left_binding {
             return Ok((left_input, (left, Some(pratt_operator))));
         }
 
-        // Consume any whitespace after the left expression or operator.
-        let (input, _) = ws0(left_input);
+        let SpacedPrattOperator {
+            pre_space,
+            operator,
+        } = pratt_operator;
 
         // Match on the type of operator: infix or postfix.
-        match pratt_operator {
+        match operator {
             // Handle infix operators like +, -, *, etc.
             PrattOperator::Infix(operator) => {
+                // Keep any whitespace after the operator.
+                let (input, right_space) = ws0(left_input);
+
                 // Recursively parse the right-hand side of the infix operation.
                 // The operator's right binding power dictates the new minimum binding power.
                 let (input, (right, next_pratt_operator)) =
-                    parse_expression_pratt(input, operator.right_binding)?;
-
-                // Construct the expression for this infix operation.
-                left = Expression::Operation {
-                    left: Box::new(left),
-                    operator: operator.operator,
-                    right: Box::new(right),
-                };
+                    parse_expression_pratt(input, operator.right_binding, context)?;
+
+                // Construct the expression for this infix operation,
+                // spanning from the start of the left to the end of the right operand.
+                let source_span = left.source_span.to(&right.source_span);
+                left = Located::new(
+                    Expression::Operation {
+                        left: Box::new(left),
+                        left_space: pre_space,
+                        operator: operator.operator,
+                        right_space,
+                        right: Box::new(right),
+                    },
+                    source_span,
+                );
 
                 // Update the current operator or finish if there are no more operators.
                 match next_pratt_operator {
@@ -500,15 +665,31 @@ fn parse_expression_pratt<'a>(
                 }
             }
             // Handle postfix operators like ! (error unwrap), [] (property access), etc.
-            PrattOperator::Postfix(operator) => {
-                // Construct the expression for this postfix operation.
-                left = Expression::SingleOperation {
-                    operation: operator.operation,
-                    operand: Box::new(left),
+            // and the fields of struct literals
+            operator @ (PrattOperator::Postfix(_) | PrattOperator::StructLiteral(_)) => {
+                // Construct the expression for this postfix operation,
+                // spanning from the start of the operand to the end of the postfix.
+                let source_span = SourceSpan {
+                    start: left.source_span.start,
+                    end: left_input.start,
                 };
+                let expression = match operator {
+                    PrattOperator::StructLiteral(fields) => Expression::StructLiteral {
+                        name: Some(Box::new(left)),
+                        pre_brace: pre_space,
+                        fields,
+                    },
+                    PrattOperator::Postfix(operator) => Expression::SingleOperation {
+                        operation: operator.operation,
+                        space: pre_space,
+                        operand: Box::new(left),
+                    },
+                    PrattOperator::Infix(_) => unreachable!("infix operators are handled above"),
+                };
+                left = Located::new(expression, source_span);
 
                 // Check if there's another Pratt operator to process.
-                match parse_pratt_operator(input) {
+                match parse_pratt_operator(left_input, context) {
                     Ok((input, operator)) => {
                         pratt_operator = operator;
                         left_input = input;
@@ -523,16 +704,44 @@ fn parse_expression_pratt<'a>(
 
 #[cfg(test)]
 mod tests {
-    use crate::utils::ParseString;
+    use crate::utils::{located, space, ParseString};
 
     use super::*;
 
+    fn integer(value: i32) -> Literal {
+        Literal::Number(NumberLiteral {
+            text: value.to_string(),
+            value: Number::Integer(value.into()),
+            suffix: None,
+        })
+    }
+
+    /// Renders an expression with every operation wrapped in parentheses, ignoring the
+    /// parentheses from the source, so that differently written groupings can be compared
+    fn grouping(expr: &Located<Expression>) -> String {
+        match &expr.value {
+            Expression::Parenthesized { expr, .. } => grouping(expr),
+            Expression::Operation {
+                left,
+                operator,
+                right,
+                ..
+            } => format!("({} {:?} {})", grouping(left), operator, grouping(right)),
+            Expression::SingleOperation {
+                operation, operand, ..
+            } => {
+                format!("({:?} {})", operation, grouping(operand))
+            }
+            other => format!("{:?}", other),
+        }
+    }
+
     #[test]
     fn test_single_number() {
         let input = "5";
-        let expected = Expression::Literal {
-            value: Literal::Number("5".to_string()),
-        };
+        let expected = located(Expression::Literal {
+            value: integer(5),
+        });
         let result = parse_expression.parse_string(input).unwrap();
         assert_eq!(result, expected);
     }
@@ -540,18 +749,22 @@ mod tests {
     #[test]
     fn test_not_field_access() {
         let input = "test.field";
-        let expected = Expression::SingleOperation {
+        let expected = located(Expression::SingleOperation {
             operation: UnaryOperation::Extract {
-                extract: ImmutableExtract::DirectProperty(Box::new(ImmutableDestructureProperty {
-                    property_name: String::from("field"),
-                    extract: None,
-                    alias: None,
-                })),
+                dot_space: vec![],
+                extract: ImmutableExtract::DirectProperty(Box::new(located(
+                    ImmutableDestructureProperty {
+                        property_name: String::from("field"),
+                        extract: None,
+                        alias: None,
+                    },
+                ))),
             },
-            operand: Box::new(Expression::Variable {
+            space: vec![],
+            operand: Box::new(located(Expression::Variable {
                 identifier: String::from("test"),
-            }),
-        };
+            })),
+        });
         let result = parse_expression.parse_string(input).unwrap();
         assert_eq!(result, expected);
     }
@@ -561,7 +774,7 @@ mod tests {
         let input = "-5 == 2";
         let expected = parse_expression.parse_string("(-5) == (2)").unwrap();
         let result = parse_expression.parse_string(input).unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(grouping(&result), grouping(&expected));
     }
 
     #[test]
@@ -569,7 +782,7 @@ mod tests {
         let input = "-!5 ** 6";
         let expected = parse_expression.parse_string("-((!5) ** 6)").unwrap();
         let result = parse_expression.parse_string(input).unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(grouping(&result), grouping(&expected));
     }
 
     #[test]
@@ -577,7 +790,7 @@ mod tests {
         let input = "5 ** 2 ** 3";
         let expected = parse_expression.parse_string("5 ** (2 ** 3)").unwrap();
         let result = parse_expression.parse_string(input).unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(grouping(&result), grouping(&expected));
     }
 
     #[test]
@@ -585,7 +798,7 @@ mod tests {
         let input = "5 ** 2 * 3";
         let expected = parse_expression.parse_string("(5 ** 2) * 3").unwrap();
         let result = parse_expression.parse_string(input).unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(grouping(&result), grouping(&expected));
     }
 
     #[test]
@@ -593,7 +806,7 @@ mod tests {
         let input = "5 * 2 ** 3";
         let expected = parse_expression.parse_string("5 * (2 ** 3)").unwrap();
         let result = parse_expression.parse_string(input).unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(grouping(&result), grouping(&expected));
     }
 
     #[test]
@@ -601,7 +814,7 @@ mod tests {
         let input = "5 * 2 * 3";
         let expected = parse_expression.parse_string("(5 * 2) * 3").unwrap();
         let result = parse_expression.parse_string(input).unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(grouping(&result), grouping(&expected));
     }
 
     #[test]
@@ -609,7 +822,7 @@ mod tests {
         let input = "5 + 2 * 3";
         let expected = parse_expression.parse_string("5 + (2 * 3)").unwrap();
         let result = parse_expression.parse_string(input).unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(grouping(&result), grouping(&expected));
     }
 
     #[test]
@@ -617,7 +830,7 @@ mod tests {
         let input = "5 * 2 + 3";
         let expected = parse_expression.parse_string("(5 * 2) + 3").unwrap();
         let result = parse_expression.parse_string(input).unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(grouping(&result), grouping(&expected));
     }
 
     #[test]
@@ -625,7 +838,7 @@ mod tests {
         let input = "5 + 2 + 3";
         let expected = parse_expression.parse_string("(5 + 2) + 3").unwrap();
         let result = parse_expression.parse_string(input).unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(grouping(&result), grouping(&expected));
     }
 
     #[test]
@@ -633,22 +846,138 @@ mod tests {
         let input = "5 + 2 - 3";
         let expected = parse_expression.parse_string("(5 + 2) - 3").unwrap();
         let result = parse_expression.parse_string(input).unwrap();
-        assert_eq!(result, expected);
+        assert_eq!(grouping(&result), grouping(&expected));
     }
 
     #[test]
     fn test_simple_addition() {
         let input = "3 + 4";
-        let expected = Expression::Operation {
-            left: Box::new(Expression::Literal {
-                value: Literal::Number("3".to_string()),
-            }),
+        let expected = located(Expression::Operation {
+            left: Box::new(located(Expression::Literal {
+                value: integer(3),
+            })),
+            left_space: space(" "),
             operator: Operator::Add,
-            right: Box::new(Expression::Literal {
-                value: Literal::Number("4".to_string()),
-            }),
-        };
+            right_space: space(" "),
+            right: Box::new(located(Expression::Literal {
+                value: integer(4),
+            })),
+        });
         let result = parse_expression.parse_string(input).unwrap();
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_parenthesized() {
+        let input = "(1 + 2) * 3";
+        let result = parse_expression.parse_string(input).unwrap();
+        match result.value {
+            Expression::Operation { left, .. } => {
+                assert!(matches!(left.value, Expression::Parenthesized { .. }));
+                assert_eq!((left.source_span.start.index, left.source_span.end.index), (0, 7));
+            }
+            other => panic!("Expected an operation, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_source_spans() {
+        let input = "-a + b?";
+        let result = parse_expression.parse_string(input).unwrap();
+        assert_eq!((result.source_span.start.index, result.source_span.end.index), (0, 7));
+        match result.value {
+            Expression::Operation { left, right, .. } => {
+                assert_eq!((left.source_span.start.index, left.source_span.end.index), (0, 2));
+                assert_eq!((right.source_span.start.index, right.source_span.end.index), (5, 7));
+                match right.value {
+                    Expression::SingleOperation { operand, .. } => assert_eq!(
+                        (operand.source_span.start.index, operand.source_span.end.index),
+                        (5, 6)
+                    ),
+                    other => panic!("Expected a postfix operation, got {:?}", other),
+                }
+            }
+            other => panic!("Expected an operation, got {:?}", other),
+        }
+    }
+
+    /// The generic arguments applied to a variable, `None` when the expression is not one
+    fn generic_arguments(expr: &Located<Expression>) -> Option<(String, usize)> {
+        match &expr.value {
+            Expression::SingleOperation {
+                operation: UnaryOperation::GenericArguments { arguments },
+                operand,
+                ..
+            } => match &operand.value {
+                Expression::Variable { identifier } => {
+                    Some((identifier.clone(), arguments.values().count()))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_postfix_binds_tighter_than_infix() {
+        let result = parse_expression.parse_string("1 + f(2) * a.b[0]").unwrap();
+        let Expression::Operation { right, .. } = result.value else {
+            panic!("Expected an operation, got {:?}", result.value);
+        };
+        let Expression::Operation { left, right, .. } = right.value else {
+            panic!("Expected an operation, got {:?}", right.value);
+        };
+        assert!(matches!(
+            left.value,
+            Expression::SingleOperation {
+                operation: UnaryOperation::Call { .. },
+                ..
+            }
+        ));
+        assert!(matches!(
+            right.value,
+            Expression::SingleOperation {
+                operation: UnaryOperation::Get { .. },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_generic_arguments_in_types() {
+        let result = parse_type_expression.parse_string("Future<Option<T>>").unwrap();
+        assert_eq!(generic_arguments(&result), Some(("Future".to_string(), 1)));
+        let result = parse_type_expression.parse_string("Map< K , V >").unwrap();
+        assert_eq!(generic_arguments(&result), Some(("Map".to_string(), 2)));
+        // `>` ends a type instead of comparing
+        let result = parse_type_expression.parse_string("A > b").unwrap();
+        assert_eq!(result.value, Expression::Variable { identifier: "A".to_string() });
+    }
+
+    #[test]
+    fn test_assignment_ends_types() {
+        let result = parse_expression.parse_string("let a: int | string = 5").unwrap();
+        let Expression::Declaration {
+            value_type: Some(value_type),
+            initializer: Some(_),
+            ..
+        } = result.value
+        else {
+            panic!("Expected a declaration with a value, got {:?}", result.value);
+        };
+        assert!(matches!(
+            value_type.value_type.value,
+            Expression::Operation {
+                operator: Operator::Union,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_comparisons_in_values() {
+        let result = parse_expression.parse_string("a < b && c > d").unwrap();
+        let expected = parse_expression.parse_string("(a < b) && (c > d)").unwrap();
+        assert_eq!(grouping(&result), grouping(&expected));
+    }
 }
@@ -0,0 +1,146 @@
+use fst::{Expression, List, StructLiteralField};
+use parser_core::*;
+
+use crate::{field::parse_field, separated_list::parser::comma_list, utils::ws0};
+
+use super::parse_expression;
+
+/// Parses the fields of a struct literal such as `{ x: 1, y, ..origin }`, the name in front of
+/// the braces is parsed as the operand of the literal
+pub fn parse_struct_literal_fields(input: Span) -> ParserResult<List<StructLiteralField>> {
+    let (input, _) = parse_left_brace(input)?;
+    let (input, (fields, _)) =
+        comma_list(parse_struct_literal_field, parse_right_brace, false)(input)?;
+    Ok((input, fields))
+}
+
+/// Parses a struct literal without a name such as `{ x: 1, y }`. Braces only start one when the
+/// first field has a value, as no statement of a block starts with `name:`, `{ x }` is a block.
+pub fn parse_anonymous_struct_literal(input: Span) -> ParserResult<Expression> {
+    let (rest, _) = parse_left_brace(input)?;
+    let (rest, _) = ws0(rest);
+    let (rest, _) = parse_ident(rest)?;
+    let (rest, _) = ws0(rest);
+    parse_colon(rest)?;
+    let (input, fields) = parse_struct_literal_fields(input)?;
+    Ok((
+        input,
+        Expression::StructLiteral {
+            name: None,
+            pre_brace: Vec::new(),
+            fields,
+        },
+    ))
+}
+
+fn parse_struct_literal_field(input: Span) -> ParserResult<StructLiteralField> {
+    let spread = |input| {
+        let (input, _) = parse_range(input)?;
+        let (input, after_dots) = ws0(input);
+        let (input, base) = parse_expression(input)?;
+        Ok((input, StructLiteralField::Spread { after_dots, base }))
+    };
+    (
+        spread,
+        parse_field.map(StructLiteralField::Field),
+        parse_ident
+            .map(|name| name.to_string())
+            .located()
+            .map(StructLiteralField::Shorthand),
+    )
+        .alt()(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::{IfBlock, Located};
+
+    use crate::utils::ParseString;
+
+    use super::*;
+
+    fn struct_literal(code: &str) -> (Located<Expression>, Vec<StructLiteralField>) {
+        let result = parse_expression.parse_string(code).unwrap();
+        let Expression::StructLiteral { name, fields, .. } = result.value else {
+            panic!("Expected a struct literal, got {:?}", result.value);
+        };
+        (*name.unwrap(), fields.values().cloned().collect())
+    }
+
+    fn parse_if_block(code: &str) -> IfBlock {
+        let result = parse_expression.parse_string(code).unwrap();
+        let Expression::If { if_block, .. } = result.value else {
+            panic!("Expected an if, got {:?}", result.value);
+        };
+        if_block
+    }
+
+    #[test]
+    fn test_struct_literals() {
+        let (name, fields) = struct_literal("Point { x: 1, y }");
+        assert!(matches!(name.value, Expression::Variable { .. }));
+        assert!(matches!(&fields[0], StructLiteralField::Field(field) if field.name == "x"));
+        let StructLiteralField::Shorthand(y) = &fields[1] else {
+            panic!("Expected a shorthand field, got {:?}", fields[1]);
+        };
+        assert_eq!(y.value, "y");
+        assert_eq!((y.source_span.start.column, y.source_span.end.column), (14, 15));
+        let (_, fields) = struct_literal("Point{ x: 1, ..origin, }");
+        assert!(matches!(&fields[1], StructLiteralField::Spread { .. }));
+        assert!(struct_literal("Unit {}").1.is_empty());
+        // the name can be any path
+        let (name, _) = struct_literal("geometry.Point { x }");
+        assert!(matches!(name.value, Expression::SingleOperation { .. }));
+    }
+
+    #[test]
+    fn test_anonymous_struct_literals() {
+        let result = parse_expression
+            .parse_string("{ name: String, next: DocName[] }")
+            .unwrap();
+        let Expression::StructLiteral { name, fields, .. } = result.value else {
+            panic!("Expected a struct literal, got {:?}", result.value);
+        };
+        assert!(name.is_none());
+        assert_eq!(fields.values().count(), 2);
+        // without a field value the braces are a block
+        let result = parse_expression.parse_string("{ x }").unwrap();
+        assert!(matches!(result.value, Expression::Block { .. }));
+    }
+
+    #[test]
+    fn test_struct_literal_values() {
+        let result = parse_expression
+            .parse_string("box { subs: [text { text }], size: 1 + 2 }.width")
+            .unwrap();
+        let Expression::SingleOperation { operand, .. } = result.value else {
+            panic!("Expected a property access, got {:?}", result.value);
+        };
+        assert!(matches!(operand.value, Expression::StructLiteral { .. }));
+    }
+
+    #[test]
+    fn test_conditions_end_at_the_block() {
+        let if_block = parse_if_block("if a { b }");
+        assert!(matches!(
+            if_block.condition.value,
+            Expression::Variable { .. }
+        ));
+        assert_eq!(if_block.block.statements.len(), 1);
+        let if_block = parse_if_block("if a == A { b } else { c }");
+        assert!(matches!(
+            if_block.condition.value,
+            Expression::Operation { .. }
+        ));
+        // parentheses allow a struct literal in a condition
+        let if_block = parse_if_block("if (A { a }) == b { c }");
+        assert!(matches!(
+            if_block.condition.value,
+            Expression::Operation { .. }
+        ));
+        let while_expr = parse_expression.parse_string("while a { b }").unwrap();
+        assert!(matches!(while_expr.value, Expression::While { .. }));
+        let for_expr = parse_expression.parse_string("for { a } in b { a }").unwrap();
+        assert!(matches!(for_expr.value, Expression::For { .. }));
+    }
+}
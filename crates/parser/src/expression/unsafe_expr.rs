@@ -0,0 +1,18 @@
+use fst::Expression;
+use parser_core::*;
+
+use crate::{block::parse_block, utils::ws0};
+
+/// unsafe { a }
+pub fn parse_unsafe_expr(input: Span) -> ParserResult<Expression> {
+    let (input, _) = parse_unsafe(input)?;
+    let (input, after_unsafe) = ws0(input);
+    let (input, block) = parse_block(input)?;
+    Ok((
+        input,
+        Expression::Unsafe {
+            after_unsafe,
+            block,
+        },
+    ))
+}
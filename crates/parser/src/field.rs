@@ -0,0 +1,37 @@
+use fst::{Expression, Field, Located};
+use parser_core::*;
+
+use crate::{
+    expression::{parse_expression, parse_type_expression},
+    utils::ws0,
+};
+
+/// Parses `name: value`, used by the fields of struct literals
+pub fn parse_field<'a>(input: Span<'a>) -> ParserResult<'a, Field> {
+    parse_field_with(input, parse_expression)
+}
+
+/// Parses `name: Type`, used by the fields of structs and enum options
+pub fn parse_typed_field<'a>(input: Span<'a>) -> ParserResult<'a, Field> {
+    parse_field_with(input, parse_type_expression)
+}
+
+fn parse_field_with<'a>(
+    input: Span<'a>,
+    parse_value: impl Fn(Span<'a>) -> ParserResult<'a, Located<Expression>>,
+) -> ParserResult<'a, Field> {
+    let (input, name) = parse_ident(input)?;
+    let (input, pre_colon) = ws0(input);
+    let (input, _) = parse_colon(input)?;
+    let (input, post_colon) = ws0(input);
+    let (input, value) = parse_value(input)?;
+    Ok((
+        input,
+        Field {
+            name: name.to_string(),
+            pre_colon,
+            post_colon,
+            value,
+        },
+    ))
+}
@@ -1,35 +1,172 @@
 use crate::{
-    expression::parse_expression,
-    utils::{opt, ws0},
+    expression::{parse_pipe_parameter_type, parse_type_expression},
+    separated_list::parser::comma_list,
+    utils::{opt, ws0, ws1},
     variable_creation::parse_variable_creation,
 };
-use fst::{Expression, VariableCreation};
+use fst::{Expression, List, Located, Parameter, Receiver, Spaced, TypeAnnotation};
 use parser_core::*;
 
-fn parse_function_parameter<'a>(
+fn parse_function_parameter<'a>(input: Span<'a>) -> ParserResult<'a, Parameter> {
+    parse_parameter_with(input, parse_type_expression)
+}
+
+fn parse_parameter_with<'a>(
     input: Span<'a>,
-) -> ParserResult<'a, (VariableCreation, Option<Expression>)> {
-    let (input, creation) = parse_variable_creation(input)?;
-    let (input, value_type) = opt((ws0, parse_colon, ws0, parse_expression)
-        .tuple()
-        .map(|v| v.3))(input);
-    Ok((input, (creation, value_type)))
+    parse_type: impl Fn(Span<'a>) -> ParserResult<'a, Located<Expression>>,
+) -> ParserResult<'a, Parameter> {
+    let (input, creation) = parse_variable_creation.located()(input)?;
+    let (input, value_type) = opt(|input| parse_type_annotation_with(input, &parse_type))(input);
+    Ok((
+        input,
+        Parameter {
+            creation,
+            value_type,
+        },
+    ))
+}
+
+/// Parses `: Type` together with the space around the colon
+pub fn parse_type_annotation<'a>(input: Span<'a>) -> ParserResult<'a, TypeAnnotation> {
+    parse_type_annotation_with(input, parse_type_expression)
+}
+
+fn parse_type_annotation_with<'a>(
+    input: Span<'a>,
+    parse_type: impl Fn(Span<'a>) -> ParserResult<'a, Located<Expression>>,
+) -> ParserResult<'a, TypeAnnotation> {
+    let (input, pre_colon) = ws0(input);
+    let (input, _) = parse_colon(input)?;
+    let (input, post_colon) = ws0(input);
+    let (input, value_type) = parse_type(input)?;
+    Ok((
+        input,
+        TypeAnnotation {
+            pre_colon,
+            post_colon,
+            value_type: Box::new(value_type),
+        },
+    ))
 }
 
 pub fn parse_function_parameters<'a, TO>(
     require_at_least_one: bool,
     termination_parser: impl Fn(Span<'a>) -> ParserResult<'a, TO>,
-) -> impl Fn(Span<'a>) -> ParserResult<'a, Vec<(VariableCreation, Option<Expression>)>> {
-    move |input| {
-        let (input, (params, _)) = separated_list(
-            (ws0, parse_comma).tuple(),
-            preceded(ws0, parse_function_parameter),
-            &termination_parser,
-            true,
-            true,
-            require_at_least_one,
-        )(input)?;
-
-        Ok((input, params))
+) -> impl Fn(Span<'a>) -> ParserResult<'a, (List<Parameter>, TO)> {
+    comma_list(
+        parse_function_parameter,
+        termination_parser,
+        require_at_least_one,
+    )
+}
+
+/// Parses the parameters of a `|a, b: Int|` closure after the opening `|` up to the closing one
+pub fn parse_pipe_parameters<'a>(input: Span<'a>) -> ParserResult<'a, List<Parameter>> {
+    let (input, (params, _)) = comma_list(
+        |input| parse_parameter_with(input, parse_pipe_parameter_type),
+        parse_vertical_bar,
+        false,
+    )(input)?;
+    Ok((input, params))
+}
+
+/// Parses the parameters of a function declaration up to the closing parenthesis,
+/// the first one may be a receiver such as `&mut self`
+pub fn parse_method_parameters<'a>(
+    input: Span<'a>,
+) -> ParserResult<'a, (Option<Spaced<Receiver>>, List<Parameter>)> {
+    let receiver = (ws0, parse_receiver, ws0)
+        .tuple()
+        .map(|(pre_space, value, post_space)| Spaced {
+            pre_space,
+            value,
+            post_space,
+        });
+    // `self` is also a valid parameter name, so it is only a receiver when it is not followed by a type
+    if let Ok((rest, receiver)) = receiver(input) {
+        if let Ok((rest, _)) = parse_right_paren(rest) {
+            return Ok((rest, (Some(receiver), List::new(Vec::new()))));
+        }
+        if let Ok((rest, _)) = parse_comma(rest) {
+            let (rest, (mut params, _)) =
+                parse_function_parameters(false, parse_right_paren)(rest)?;
+            // the comma after the receiver is a trailing comma when no parameters follow
+            params.trailing_comma |= params.items.is_empty();
+            return Ok((rest, (Some(receiver), params)));
+        }
+    }
+    let (input, (params, _)) = parse_function_parameters(false, parse_right_paren)(input)?;
+    Ok((input, (None, params)))
+}
+
+fn parse_receiver<'a>(input: Span<'a>) -> ParserResult<'a, Receiver> {
+    if let Ok((input, _)) = parse_self(input) {
+        return Ok((input, Receiver::Value));
+    }
+    let (input, _) = parse_ampersand(input)?;
+    let (input, after_ampersand) = ws0(input);
+    let (input, after_mut) = opt((parse_mut, ws1).tuple())(input);
+    let (input, _) = parse_self(input)?;
+    let receiver = match after_mut {
+        Some((_, after_mut)) => Receiver::MutableReference(after_ampersand, after_mut),
+        None => Receiver::Reference(after_ampersand),
+    };
+    Ok((input, receiver))
+}
+
+fn parse_self<'a>(input: Span<'a>) -> ParserResult<'a, ()> {
+    let (rest, (token, source_span)) = input.take_token();
+    match token.delocate() {
+        Some(Token::Ident("self")) => Ok((rest, ())),
+        _ => Err(token.as_parser_error(TokenKind::Ident.into(), source_span)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::{Receiver, Statement};
+
+    use crate::{statement::parse_statement, utils::ParseString};
+
+    fn signature(code: &str) -> (Option<Receiver>, Vec<String>) {
+        let statement = parse_statement.parse_string(code).unwrap();
+        let Statement::Function { closure, .. } = statement.value else {
+            panic!("Expected a function, got {:?}", statement.value);
+        };
+        let signature = closure.value.closure_signature;
+        let params = signature
+            .params
+            .values()
+            .map(|param| format!("{:?}", param.creation.value))
+            .collect();
+        (signature.receiver.map(|receiver| receiver.value), params)
+    }
+
+    #[test]
+    fn test_receivers() {
+        assert_eq!(signature("fn f(self) {}").0, Some(Receiver::Value));
+        assert_eq!(
+            signature("fn f(&self, a) {}").0,
+            Some(Receiver::Reference(vec![]))
+        );
+        assert!(matches!(
+            signature("fn f( & mut  self ,) {}").0,
+            Some(Receiver::MutableReference(..))
+        ));
+        assert_eq!(signature("fn f(&mut self, a, b) {}").1.len(), 2);
+    }
+
+    #[test]
+    fn test_self_without_receiver() {
+        // a typed `self` is an ordinary parameter
+        let (receiver, params) = signature("fn f(self: Int, a) {}");
+        assert_eq!(receiver, None);
+        assert_eq!(params.len(), 2);
+        assert_eq!(signature("fn f(a, b) {}").0, None);
+    }
+
+    #[test]
+    fn test_receiver_must_come_first() {
+        assert!(parse_statement.parse_string("fn f(a, &self) {}").is_err());
     }
 }
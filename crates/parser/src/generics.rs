@@ -0,0 +1,114 @@
+use fst::{GenericParameter, GenericParameters};
+use parser_core::*;
+
+use crate::{
+    function_parameters::parse_type_annotation,
+    separated_list::parser::comma_list,
+    utils::{opt, ws0},
+};
+
+/// Parses the generic parameters of an item such as `<T, U: Iterator<T>>`, `None` when there
+/// is no `<`. Once the list is opened its errors are reported instead of being swallowed.
+pub fn parse_generic_parameters<'a>(
+    input: Span<'a>,
+) -> ParserResult<'a, Option<GenericParameters>> {
+    let (after_space, pre_space) = ws0(input);
+    let Ok((rest, _)) = parse_less_than(after_space) else {
+        return Ok((input, None));
+    };
+    let (rest, (params, _)) = comma_list(parse_generic_parameter, parse_greater_than, true)(rest)?;
+    Ok((rest, Some(GenericParameters { pre_space, params })))
+}
+
+fn parse_generic_parameter<'a>(input: Span<'a>) -> ParserResult<'a, GenericParameter> {
+    let (input, name) = parse_ident(input)?;
+    let (input, bound) = opt(parse_type_annotation)(input);
+    Ok((
+        input,
+        GenericParameter {
+            name: name.to_string(),
+            bound,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::{Expression, Located, Statement, UnaryOperation};
+
+    use crate::{statement::parse_statement, utils::ParseString};
+
+    /// Renders a bound such as `Iterator<..>`, leaving out the generic arguments
+    fn bound_name(bound: &Located<Expression>) -> String {
+        match &bound.value {
+            Expression::Variable { identifier } => identifier.clone(),
+            Expression::SingleOperation {
+                operation: UnaryOperation::GenericArguments { .. },
+                operand,
+                ..
+            } => format!("{}<..>", bound_name(operand)),
+            other => panic!("Unexpected bound {:?}", other),
+        }
+    }
+
+    fn generics(code: &str) -> Vec<(String, Option<String>)> {
+        let statement = parse_statement.parse_string(code).unwrap();
+        let generics = match statement.value {
+            Statement::Function { generics, .. }
+            | Statement::Struct { generics, .. }
+            | Statement::Enum { generics, .. }
+            | Statement::TypeAlias { generics, .. }
+            | Statement::Trait { generics, .. }
+            | Statement::Impl { generics, .. } => generics,
+            other => panic!("Expected an item, got {:?}", other),
+        };
+        let Some(generics) = generics else {
+            return Vec::new();
+        };
+        generics
+            .params
+            .values()
+            .map(|param| {
+                let bound = param
+                    .bound
+                    .as_ref()
+                    .map(|bound| bound_name(&bound.value_type));
+                (param.name.clone(), bound)
+            })
+            .collect()
+    }
+
+    fn param(name: &str, bound: Option<&str>) -> (String, Option<String>) {
+        (name.to_string(), bound.map(|bound| bound.to_string()))
+    }
+
+    #[test]
+    fn test_generic_items() {
+        let t = vec![param("T", None)];
+        assert_eq!(generics("fn f<T>(a: T) {}"), t);
+        assert_eq!(generics("struct A<T> { a: T }"), t);
+        assert_eq!(generics("enum A<T> { B(T) }"), t);
+        assert_eq!(generics("type A<T> = B<T>;"), t);
+        assert_eq!(generics("trait Iterator<T> {}"), t);
+        assert_eq!(generics("impl<T> A {}"), t);
+        assert_eq!(generics("struct A { a: Int }"), vec![]);
+    }
+
+    #[test]
+    fn test_generic_bounds() {
+        assert_eq!(
+            generics("fn f<T: Clone, U>() {}"),
+            vec![param("T", Some("Clone")), param("U", None)]
+        );
+        assert_eq!(
+            generics("struct A < T : Iterator<Int> > {}"),
+            vec![param("T", Some("Iterator<..>"))]
+        );
+    }
+
+    #[test]
+    fn test_unclosed_generics() {
+        assert!(parse_statement.parse_string("struct A<T {}").is_err());
+        assert!(parse_statement.parse_string("fn f<>() {}").is_err());
+    }
+}
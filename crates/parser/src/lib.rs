@@ -2,42 +2,57 @@
 #![feature(impl_trait_in_assoc_type)]
 #![feature(const_trait_impl)]
 
+mod attribute;
 mod block;
 mod destructure;
 mod error;
+mod field;
 pub mod expression;
 mod function_parameters;
+mod generics;
+mod pattern;
 mod statement;
+mod unsafety;
 mod utils;
 mod variable_creation;
+mod visibility;
 mod whitespace;
 mod separated_list;
+mod recovery;
 
 use self::{statement::parse_statement, utils::ws0};
-use fst::Statement;
+use fst::File;
 use error::create_fancy_error;
 use parser_core::*;
 
+pub use recovery::parse_file_recovering;
+
 pub mod core {
     pub use parser_core::*;
 }
 
-pub fn parse_file<'a>(input: Span<'a>) -> ParserResult<'a, Vec<Statement>> {
-    let (input, _) = ws0(input);
-    let (input, out) =
-        aggressive_many0((parse_statement, ws0).tuple().map(|(stmt, _)| stmt))(input)?;
-    Ok((input, out))
+pub fn parse_file<'a>(input: Span<'a>) -> ParserResult<'a, File> {
+    let (input, pre_space) = ws0(input);
+    let (input, statements) = aggressive_many0((parse_statement, ws0).tuple())(input)?;
+    Ok((
+        input,
+        File {
+            pre_space,
+            statements,
+        },
+    ))
 }
 
-pub fn simple_parse(code: &str) -> Result<Vec<Statement>, String> {
+pub fn simple_parse(code: &str) -> Result<File, String> {
     let tokens = tokenize(code);
     let input = create_span(&tokens);
-    let result = parse_file(input);
-    match result {
-        Ok((input2, statements)) => match input2.tokens.len() {
-            0 => Ok(statements),
-            _ => unreachable!(),
-        },
-        Err(err) => Err(create_fancy_error(&code, err)),
+    let (file, errors) = parse_file_recovering(input);
+    if errors.is_empty() {
+        return Ok(file);
     }
+    Err(errors
+        .into_iter()
+        .map(|err| create_fancy_error(code, err))
+        .collect::<Vec<_>>()
+        .join("\n\n"))
 }
@@ -0,0 +1,278 @@
+use fst::{Literal, Located, Pattern, Spaced, VariantPattern};
+use parser_core::{
+    literal::{negate_number, parse_literal},
+    *,
+};
+
+use crate::{
+    destructure::parse_mutable_destructure,
+    separated_list::parser::comma_list,
+    utils::{opt, ws0, ws1},
+};
+
+/// a | b, each alternative is one of:
+/// _
+/// mut a
+/// 1, -1 or 1..10
+/// Color.Red, Some(a) or Shape.Circle { radius }
+pub fn parse_pattern(input: Span) -> ParserResult<Located<Pattern>> {
+    parse_alternatives.located()(input)
+}
+
+fn parse_alternatives(input: Span) -> ParserResult<Pattern> {
+    let (mut input, first) = parse_single_pattern.located()(input)?;
+    let mut alternatives = vec![Spaced {
+        pre_space: Vec::new(),
+        value: first,
+        post_space: Vec::new(),
+    }];
+    while let (rest, Some((pre_bar, _, post_bar, alternative))) =
+        opt((ws0, parse_vertical_bar, ws0, parse_single_pattern.located()).tuple())(input)
+    {
+        alternatives.last_mut().unwrap().post_space = pre_bar;
+        alternatives.push(Spaced {
+            pre_space: post_bar,
+            value: alternative,
+            post_space: Vec::new(),
+        });
+        input = rest;
+    }
+    let pattern = match alternatives.len() {
+        // the location of a single pattern is the location of the whole pattern
+        1 => alternatives.pop().unwrap().value.value,
+        _ => Pattern::Alternatives(alternatives),
+    };
+    Ok((input, pattern))
+}
+
+fn parse_single_pattern(input: Span) -> ParserResult<Pattern> {
+    let (rest, (token, source_span)) = input.take_token();
+    match token.delocate() {
+        Some(Token::Ident("_")) => Ok((rest, Pattern::Wildcard)),
+        Some(Token::Mut) => {
+            let (input, space) = ws1(rest)?;
+            let (input, name) = parse_ident(input)?;
+            Ok((
+                input,
+                Pattern::Binding {
+                    mutable: Some(space),
+                    name: name.to_string(),
+                },
+            ))
+        }
+        Some(Token::Ident(_)) => parse_variant_pattern(input),
+        _ => parse_literal_pattern(input).map_err(|e| {
+            token
+                .as_parser_error(TokenKind::Ident | TokenKind::Mut, source_span)
+                .accumulate(e)
+        }),
+    }
+}
+
+fn parse_literal_pattern(input: Span) -> ParserResult<Pattern> {
+    let (input, start) = parse_pattern_literal(input)?;
+    let range = opt((ws0, parse_range, ws0, parse_pattern_literal).tuple())(input);
+    match range {
+        (input, Some((pre_dots, _, post_dots, end))) => Ok((
+            input,
+            Pattern::Range {
+                start,
+                pre_dots,
+                post_dots,
+                end,
+            },
+        )),
+        (input, None) => Ok((input, Pattern::Literal(start))),
+    }
+}
+
+/// A literal, a `-` right in front of a number makes it negative
+fn parse_pattern_literal(input: Span) -> ParserResult<Literal> {
+    let Ok((after_minus, _)) = parse_minus(input) else {
+        return parse_literal_token(input);
+    };
+    let (rest, (token, source_span)) = after_minus.take_token();
+    match token.as_ref().and_then(parse_literal) {
+        Some(Ok(Literal::Number(number))) => match negate_number(number) {
+            Ok(number) => Ok((rest, Literal::Number(number))),
+            Err(error) => {
+                Err(ParserError::InvalidLiteral(error).locate(input.source_span_until(&rest)))
+            }
+        },
+        Some(Err(error)) => Err(error),
+        _ => Err(token.as_parser_error(TokenKind::Number.into(), source_span)),
+    }
+}
+
+fn parse_literal_token(input: Span) -> ParserResult<Literal> {
+    let (rest, (token, source_span)) = input.take_token();
+    match token.as_ref().and_then(parse_literal) {
+        Some(literal) => literal.map(|literal| (rest, literal)),
+        None => Err(token.as_parser_error(
+            TokenKind::RawString | TokenKind::Number | TokenKind::String | TokenKind::Boolean,
+            source_span,
+        )),
+    }
+}
+
+/// A path to an enum variant and its values, a single name without values is a binding
+fn parse_variant_pattern(input: Span) -> ParserResult<Pattern> {
+    let (mut input, name) = parse_ident(input)?;
+    let mut path = vec![Spaced {
+        pre_space: Vec::new(),
+        value: name.to_string(),
+        post_space: Vec::new(),
+    }];
+    while let (rest, Some((pre_dot, _, post_dot, name))) =
+        opt((ws0, parse_dot, ws0, parse_ident).tuple())(input)
+    {
+        path.last_mut().unwrap().post_space = pre_dot;
+        path.push(Spaced {
+            pre_space: post_dot,
+            value: name.to_string(),
+            post_space: Vec::new(),
+        });
+        input = rest;
+    }
+
+    let tuple = |input| {
+        let (input, space) = ws0(input);
+        let (input, _) = parse_left_paren(input)?;
+        let (input, (values, _)) = comma_list(parse_pattern, parse_right_paren, false)(input)?;
+        Ok((input, VariantPattern::Tuple { space, values }))
+    };
+    let structure = |input| {
+        let (input, space) = ws0(input);
+        let (input, fields) = parse_mutable_destructure(input)?;
+        Ok((input, VariantPattern::Struct { space, fields }))
+    };
+    let (input, values) = match opt((tuple, structure).alt())(input) {
+        (input, Some(values)) => (input, values),
+        (_, None) if path.len() == 1 => {
+            let name = path.pop().unwrap().value;
+            return Ok((
+                input,
+                Pattern::Binding {
+                    mutable: None,
+                    name,
+                },
+            ));
+        }
+        (_, None) => (input, VariantPattern::Unit),
+    };
+    Ok((input, Pattern::Variant { path, values }))
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::{Number, NumberLiteral, StringLiteral};
+
+    use crate::utils::ParseString;
+
+    use super::*;
+
+    fn pattern(code: &str) -> Pattern {
+        parse_pattern.parse_string(code).unwrap().value
+    }
+
+    fn path(pattern: &Pattern) -> Vec<&str> {
+        match pattern {
+            Pattern::Variant { path, .. } => path.iter().map(|name| name.value.as_str()).collect(),
+            other => panic!("Expected a variant, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_simple_patterns() {
+        assert_eq!(pattern("_"), Pattern::Wildcard);
+        assert_eq!(
+            pattern("a"),
+            Pattern::Binding {
+                mutable: None,
+                name: "a".to_string()
+            }
+        );
+        assert!(matches!(
+            pattern("mut a"),
+            Pattern::Binding {
+                mutable: Some(_),
+                ..
+            }
+        ));
+        assert!(matches!(
+            pattern("'a'"),
+            Pattern::Literal(Literal::String(StringLiteral { .. }))
+        ));
+        assert!(matches!(
+            pattern("1 .. 10"),
+            Pattern::Range {
+                start: Literal::Number(NumberLiteral { .. }),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_negative_literals() {
+        let Pattern::Range { start, end, .. } = pattern("-10..-1") else {
+            panic!("Expected a range");
+        };
+        assert_eq!(start.to_string(), "-10");
+        assert_eq!(end.to_string(), "-1");
+        assert!(matches!(
+            pattern("-1.5"),
+            Pattern::Literal(Literal::Number(NumberLiteral {
+                value: Number::Float(value),
+                ..
+            })) if value == -1.5
+        ));
+        assert!(parse_pattern.parse_string("- 1").is_err());
+        assert!(parse_pattern.parse_string("-'a'").is_err());
+        assert!(parse_pattern.parse_string("-1u8").is_err());
+    }
+
+    #[test]
+    fn test_variant_patterns() {
+        let color = pattern("Color . Red");
+        assert_eq!(path(&color), vec!["Color", "Red"]);
+        assert!(matches!(
+            color,
+            Pattern::Variant {
+                values: VariantPattern::Unit,
+                ..
+            }
+        ));
+        let some = pattern("Some(Point { x, y as mut b })");
+        assert_eq!(path(&some), vec!["Some"]);
+        let Pattern::Variant {
+            values: VariantPattern::Tuple { values, .. },
+            ..
+        } = some
+        else {
+            panic!("Expected a tuple variant, got {:?}", some);
+        };
+        let inner = &values.values().next().unwrap().value;
+        assert!(matches!(
+            inner,
+            Pattern::Variant {
+                values: VariantPattern::Struct { .. },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_alternatives() {
+        let Pattern::Alternatives(alternatives) = pattern("1 | 2 |_") else {
+            panic!("Expected alternatives");
+        };
+        assert_eq!(alternatives.len(), 3);
+        assert_eq!(alternatives[2].value.value, Pattern::Wildcard);
+        assert!(alternatives[2].pre_space.is_empty());
+        // alternatives can be nested in variant values
+        let nested = pattern("Some(1 | 2)");
+        assert_eq!(path(&nested), vec!["Some"]);
+        // a bar without an alternative is left to the caller
+        assert!(matches!(pattern("a |"), Pattern::Binding { .. }));
+    }
+}
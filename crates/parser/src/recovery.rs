@@ -0,0 +1,299 @@
+use enumset::{enum_set_union, EnumSet};
+use fst::{File, Located, Statement, Whitespace0};
+use parser_core::{literal::parse_literal, *};
+
+use crate::{statement::parse_statement, utils::ws0, whitespace::WHITESPACE_KINDS};
+
+/// Tokens that can only start a statement, parsing can safely resume in front of them
+const STATEMENT_KEYWORDS: EnumSet<TokenKind> = enum_set_union!(
+    TokenKind::Hash,
+    TokenKind::Pub,
+    TokenKind::Fn,
+    TokenKind::Struct,
+    TokenKind::Enum,
+    TokenKind::Type,
+    TokenKind::Trait,
+    TokenKind::Impl,
+    TokenKind::Mod,
+    TokenKind::Import,
+    TokenKind::UseEnv,
+    TokenKind::Let,
+    TokenKind::Return,
+    TokenKind::Break,
+    TokenKind::Continue,
+);
+
+/// Parses a file, recovering from syntax errors instead of stopping at the first one.
+///
+/// When a statement fails to parse, the tokens up to the next statement boundary are kept
+/// in a `Statement::Error` node and parsing resumes after them. A boundary is a `;`, an
+/// unmatched `}` or a keyword that starts a statement, whichever comes first after the error.
+/// Closing braces left over from a skipped block and a semicolon left over right after a skipped
+/// statement are absorbed without reporting another error.
+pub fn parse_file_recovering<'a>(input: Span<'a>) -> (File, Vec<LocatedParserError>) {
+    let (mut input, pre_space) = ws0(input);
+    let mut statements = Vec::new();
+    let mut errors = Vec::new();
+    let mut unclosed_braces = 0;
+
+    while !input.tokens.is_empty() {
+        let (rest, statement) = match parse_statement(input) {
+            Ok(result) => result,
+            Err(_) if unclosed_braces > 0 && input.tokens[0].kind() == TokenKind::RightBrace => {
+                unclosed_braces -= 1;
+                skip_tokens(input, 1)
+            }
+            Err(_)
+                if follows_error(&statements) && input.tokens[0].kind() == TokenKind::Semicolon =>
+            {
+                skip_tokens(input, 1)
+            }
+            Err(error) => {
+                let end = statement_boundary(input, error.source_span.start.index);
+                errors.push(invalid_literal(&input.tokens[..end]).unwrap_or(error));
+                unclosed_braces += open_braces(&input.tokens[..end]);
+                skip_tokens(input, end)
+            }
+        };
+        let (rest, space) = ws0(rest);
+        statements.push((statement, space));
+        input = rest;
+    }
+
+    (
+        File {
+            pre_space,
+            statements,
+        },
+        errors,
+    )
+}
+
+fn follows_error(statements: &[(Located<Statement>, Whitespace0)]) -> bool {
+    statements
+        .last()
+        .is_some_and(|(statement, _)| matches!(statement.value, Statement::Error(_)))
+}
+
+/// The number of tokens from the start of `input` up to the statement boundary following `error_index`
+fn statement_boundary(input: Span, error_index: usize) -> usize {
+    let tokens = input.tokens;
+    let error_token = tokens
+        .iter()
+        .position(|token| token.source_span.end.index > error_index)
+        .unwrap_or(tokens.len());
+
+    let mut depth = 0usize;
+    let mut end = tokens.len();
+    for (i, token) in tokens.iter().enumerate().skip(error_token) {
+        match token.kind() {
+            TokenKind::LeftBrace => depth += 1,
+            TokenKind::RightBrace if depth == 0 => {
+                end = i + 1;
+                break;
+            }
+            TokenKind::RightBrace => depth -= 1,
+            TokenKind::Semicolon if depth == 0 => {
+                end = i + 1;
+                break;
+            }
+            kind if depth == 0 && i > 0 && STATEMENT_KEYWORDS.contains(kind) => {
+                end = i;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    // Trailing whitespace belongs after the error node, but at least one token is always skipped
+    while end > 1 && WHITESPACE_KINDS.contains(tokens[end - 1].kind()) {
+        end -= 1;
+    }
+    end.max(1)
+}
+
+/// The first malformed literal in the tokens of a skipped statement.
+///
+/// Optional parsers discard the error of a malformed literal, so the error that
+/// reaches the statement is often only a consequence of it, and may even be located
+/// in front of the literal when a parser backtracked over it.
+fn invalid_literal(tokens: &[LocatedToken]) -> Option<LocatedParserError> {
+    tokens.iter().find_map(|token| parse_literal(token)?.err())
+}
+
+/// The number of `{` in `tokens` that are not closed within `tokens`
+fn open_braces(tokens: &[LocatedToken]) -> usize {
+    tokens
+        .iter()
+        .fold(0isize, |depth, token| match token.kind() {
+            TokenKind::LeftBrace => depth + 1,
+            TokenKind::RightBrace => depth - 1,
+            _ => depth,
+        })
+        .max(0) as usize
+}
+
+fn skip_tokens<'a>(input: Span<'a>, count: usize) -> (Span<'a>, Located<Statement>) {
+    let skipped = &input.tokens[..count];
+    let rest = Span {
+        tokens: &input.tokens[count..],
+        start: skipped[count - 1].source_span.end,
+    };
+    let text = skipped.iter().map(|token| token.text).collect::<String>();
+    (
+        rest,
+        Located::new(Statement::Error(text), input.source_span_until(&rest)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::NumberSuffix;
+    use parser_core::literal::LiteralError;
+
+    use super::*;
+
+    fn parse_recovering(code: &str) -> (File, Vec<LocatedParserError>) {
+        let tokens = tokenize(code);
+        parse_file_recovering(create_span(&tokens))
+    }
+
+    fn error_texts(file: &File) -> Vec<&str> {
+        file.statements
+            .iter()
+            .filter_map(|(statement, _)| match &statement.value {
+                Statement::Error(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_file_has_no_errors() {
+        let (file, errors) = parse_recovering("let a = 5;\nfn b() { a }\n");
+        assert!(errors.is_empty());
+        assert_eq!(file.statements.len(), 2);
+        assert!(error_texts(&file).is_empty());
+    }
+
+    #[test]
+    fn test_reports_every_error() {
+        let (file, errors) =
+            parse_recovering("let a = ;\nlet b = 1;\nstruct {}\nlet c = (;\nfn d() {}\n");
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors[0].source_span.start.line, 0);
+        assert_eq!(errors[1].source_span.start.line, 2);
+        assert_eq!(errors[2].source_span.start.line, 3);
+        assert_eq!(
+            error_texts(&file),
+            vec!["let a = ;", "struct {}", "let c = (;"]
+        );
+        assert_eq!(file.statements.len(), 5);
+    }
+
+    #[test]
+    fn test_resumes_after_broken_block() {
+        let (file, errors) = parse_recovering("fn a() {\n    let x = ;\n    x\n}\nfn b() {}\n");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].source_span.start.line, 1);
+        assert_eq!(error_texts(&file), vec!["fn a() {\n    let x = ;", "}"]);
+        assert!(matches!(
+            file.statements.last().unwrap().0.value,
+            Statement::Function { .. }
+        ));
+    }
+
+    #[test]
+    fn test_reports_malformed_literals() {
+        let (_, errors) = parse_recovering("let a = 1;\nlet b = 0b12;\nlet c = 2e;\n");
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0].error,
+            ParserError::InvalidLiteral(LiteralError::InvalidDigit('2', 2))
+        ));
+        assert_eq!(errors[0].source_span.start.line, 1);
+        assert_eq!(errors[0].source_span.start.column, 8);
+        assert!(matches!(
+            errors[1].error,
+            ParserError::InvalidLiteral(LiteralError::MissingExponentDigits)
+        ));
+    }
+
+    #[test]
+    fn test_reports_literals_after_the_error() {
+        let (_, errors) = parse_recovering("let p = P { x: 2pt };\nlet f = 1e400;\n");
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0].error,
+            ParserError::InvalidLiteral(LiteralError::InvalidSuffix)
+        ));
+        assert_eq!(errors[0].source_span.start.column, 15);
+        assert!(matches!(
+            errors[1].error,
+            ParserError::InvalidLiteral(LiteralError::OutOfRange(NumberSuffix::F64))
+        ));
+    }
+
+    #[test]
+    fn test_reports_invalid_escapes() {
+        let (_, errors) = parse_recovering("let a = \"ok\\n\";\nlet b = \"tab\\x\";\n");
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            errors[0].error,
+            ParserError::InvalidLiteral(LiteralError::InvalidHexEscape)
+        ));
+        assert_eq!(errors[0].source_span.start.line, 1);
+        assert_eq!(errors[0].source_span.start.column, 12);
+    }
+
+    #[test]
+    fn test_error_nodes_keep_all_source() {
+        let code = "let a = ;\n// comment\nlet b = 1 +;\n}\n";
+        let (file, errors) = parse_recovering(code);
+        assert_eq!(errors.len(), 3);
+        let end = file.statements.iter().fold(0, |covered, (statement, _)| {
+            assert!(statement.source_span.start.index >= covered);
+            statement.source_span.end.index
+        });
+        assert_eq!(end, code.trim_end().len());
+    }
+
+    #[test]
+    fn test_std_files() {
+        // syntax these files use that the parser does not support yet
+        let unsupported = [
+            "env/console/mod.qp", // #requires_env(..) without brackets
+            "env/fs/mod.qp",      // import cimport("...")
+            "link/mod.qp",        // functions without a body
+            "lang/c/mod.qp",      // a.*.b
+            "core/compile.qp",    // `-` as a placeholder body
+        ];
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../std");
+        let mut directories = vec![root.clone()];
+        let mut count = 0;
+        while let Some(directory) = directories.pop() {
+            for entry in std::fs::read_dir(directory).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    directories.push(path);
+                    continue;
+                }
+                let relative = path.strip_prefix(&root).unwrap().to_str().unwrap();
+                let (_, errors) = parse_recovering(&std::fs::read_to_string(&path).unwrap());
+                if unsupported.contains(&relative) {
+                    assert!(
+                        !errors.is_empty(),
+                        "{relative} parses, remove it from the unsupported files"
+                    );
+                    assert!(errors
+                        .iter()
+                        .all(|error| !matches!(error.error, ParserError::InvalidLiteral(_))));
+                } else {
+                    assert!(errors.is_empty(), "{relative}: {errors:?}");
+                }
+                count += 1;
+            }
+        }
+        assert!(count > unsupported.len());
+    }
+}
@@ -1,5 +1,8 @@
+use fst::List;
 use parser_core::*;
 
+use crate::utils::ws0;
+
 use super::structure::{StrictSeparatedList, TrailingSeparatedList};
 
 /// Parses a separated list of elements with optional termination, returning a `TrailingSeparatedList`.
@@ -26,13 +29,26 @@ pub fn trailing_separated_list<
     move |mut input: Span<'a>| {
         let mut list = TrailingSeparatedList::new();
 
-        if require_one {
-            match parser(input).into_parser_result() {
-                Ok((rest, o)) => {
-                    input = rest;
-                    list.push_value(o).expect("Failed to push value in TrailingSeparatedList");
+        match parser(input).into_parser_result() {
+            Ok((rest, o)) => {
+                input = rest;
+                list.push_value(o).expect("Failed to push value in TrailingSeparatedList");
+            }
+            Err(e) => {
+                if require_one {
+                    return Err(e);
                 }
-                Err(e) => return Err(e),
+                // A list can't start with a separator, so an empty list must be terminated immediately
+                return match termination_parser(input).into_parser_result() {
+                    Ok((rest, o)) => {
+                        if consume_termination_tokens {
+                            Ok((rest, (list, o)))
+                        } else {
+                            Ok((input, (list, o)))
+                        }
+                    }
+                    Err(e2) => Err(e.accumulate(e2)),
+                };
             }
         };
 
@@ -44,7 +60,7 @@ pub fn trailing_separated_list<
                     // Safe to unwrap as the parser enforces correct alternation
                     list.push_separator(sep).expect("Failed to push separator in TrailingSeparatedList");
                 }
-                Err(_) => {
+                Err(e) => {
                     // Attempt to parse the termination
                     match termination_parser(input).into_parser_result() {
                         Ok((rest, o)) => {
@@ -54,9 +70,9 @@ pub fn trailing_separated_list<
                                 return Ok((input, (list, o)));
                             }
                         }
-                        Err(e) => {
-                            // Termination parsing failed; return the error
-                            return Err(e);
+                        Err(e2) => {
+                            // Termination parsing failed; report whichever error got further
+                            return Err(e.accumulate(e2));
                         }
                     }
                 }
@@ -69,7 +85,7 @@ pub fn trailing_separated_list<
                     // Safe to unwrap as the parser enforces correct alternation
                     list.push_value(o).expect("Failed to push value in TrailingSeparatedList");
                 }
-                Err(_) => {
+                Err(e) => {
                     // Attempt to parse the termination after a separator
                     match termination_parser(input).into_parser_result() {
                         Ok((rest, o)) => {
@@ -79,9 +95,9 @@ pub fn trailing_separated_list<
                                 return Ok((input, (list, o)));
                             }
                         }
-                        Err(e) => {
-                            // Termination parsing failed; return the error
-                            return Err(e);
+                        Err(e2) => {
+                            // Termination parsing failed; report whichever error got further
+                            return Err(e.accumulate(e2));
                         }
                     }
                 }
@@ -114,14 +130,27 @@ pub fn strict_separated_list<
     move |mut input: Span<'a>| {
         let mut list = StrictSeparatedList::new();
 
-        if require_one {
-            match parser(input).into_parser_result() {
-                Ok((rest, o)) => {
-                    input = rest;
-                    // Since the list is used correctly, unwrap is safe
-                    list.push_value(o).expect("Failed to push value in StrictSeparatedList");
+        match parser(input).into_parser_result() {
+            Ok((rest, o)) => {
+                input = rest;
+                // Since the list is used correctly, unwrap is safe
+                list.push_value(o).expect("Failed to push value in StrictSeparatedList");
+            }
+            Err(e) => {
+                if require_one {
+                    return Err(e);
                 }
-                Err(e) => return Err(e),
+                // A strict list can't start with a separator, so an empty list must be terminated immediately
+                return match termination_parser(input).into_parser_result() {
+                    Ok((rest, o)) => {
+                        if consume_termination_tokens {
+                            Ok((rest, (list, o)))
+                        } else {
+                            Ok((input, (list, o)))
+                        }
+                    }
+                    Err(e2) => Err(e.accumulate(e2)),
+                };
             }
         };
 
@@ -150,14 +179,14 @@ pub fn strict_separated_list<
                                     }
                                 }
                                 Err(e2) => {
-                                    // Termination parsing failed; return the error
-                                    return Err(e2);
+                                    // Termination parsing failed; report whichever error got further
+                                    return Err(e.accumulate(e2));
                                 }
                             }
                         }
                     }
                 }
-                Err(_) => {
+                Err(e) => {
                     // Attempt to parse the termination
                     match termination_parser(input).into_parser_result() {
                         Ok((rest, o)) => {
@@ -167,9 +196,9 @@ pub fn strict_separated_list<
                                 return Ok((input, (list, o)));
                             }
                         }
-                        Err(e) => {
-                            // Termination parsing failed; return the error
-                            return Err(e);
+                        Err(e2) => {
+                            // Termination parsing failed; report whichever error got further
+                            return Err(e.accumulate(e2));
                         }
                     }
                 }
@@ -177,3 +206,37 @@ pub fn strict_separated_list<
         }
     }
 }
+
+/// Parses the items of a comma separated fst `List` followed by the termination, which is always consumed.
+///
+/// The whitespace in front of every item, comma and the termination is kept in the list,
+/// the opening delimiter must already be consumed.
+#[inline]
+pub fn comma_list<'a, PO, TO>(
+    parser: impl Fn(Span<'a>) -> ParserResult<'a, PO>,
+    termination_parser: impl Fn(Span<'a>) -> ParserResult<'a, TO>,
+    require_one: bool,
+) -> impl Fn(Span<'a>) -> ParserResult<'a, (List<PO>, TO)> {
+    move |input| {
+        let (input, (list, (end_space, termination))) = trailing_separated_list(
+            |input| {
+                let (input, space) = ws0(input);
+                let (input, _) = parse_comma(input)?;
+                Ok((input, space))
+            },
+            |input| {
+                let (input, space) = ws0(input);
+                let (input, value) = parser(input)?;
+                Ok((input, (space, value)))
+            },
+            |input| {
+                let (input, space) = ws0(input);
+                let (input, termination) = termination_parser(input)?;
+                Ok((input, (space, termination)))
+            },
+            true,
+            require_one,
+        )(input)?;
+        Ok((input, (list.into_list(end_space), termination)))
+    }
+}
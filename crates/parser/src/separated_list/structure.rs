@@ -1,3 +1,4 @@
+use fst::{List, Spaced, Whitespace0};
 use thiserror::Error;
 
 pub enum ListElement<Value, Separator> {
@@ -51,44 +52,66 @@ impl<Value, Separator> TrailingSeparatedList<Value, Separator> {
         Ok(())
     }
 
-    pub fn is_empty_values(&self) -> bool {
-        match self.elements.first() {
-            None => true,
-            Some(ListElement::Separator(_)) => self.elements.len() == 1,
-            Some(ListElement::Value(_)) => false,
-        }
-    }
-
+    #[cfg(test)]
     pub fn len_values(&self) -> usize {
         match self.elements.first() {
             None => 0,
             Some(ListElement::Separator(_)) => self.elements.len() / 2,
-            Some(ListElement::Value(_)) => (self.elements.len() + 1) / 2,
+            Some(ListElement::Value(_)) => self.elements.len().div_ceil(2),
         }
     }
 
-    pub fn values(&self) -> impl Iterator<Item = &Value> {
-        let start_index = match self.elements.first() {
-            None => 0,
-            Some(ListElement::Separator(_)) => 1,
-            Some(ListElement::Value(_)) => 0,
-        };
-
-        self.elements
-            .iter()
-            .skip(start_index)
-            .step_by(2)
-            .map(|element| match element {
-                ListElement::Value(value) => value,
-                ListElement::Separator(_) => panic!("This shouldn't be possible"),
-            })
+    pub fn has_trailing_separator(&self) -> bool {
+        matches!(self.elements.last(), Some(ListElement::Separator(_)))
     }
+}
 
-    pub fn iter_elements(&self) -> impl Iterator<Item = &ListElement<Value, Separator>> {
-        self.elements.iter()
+impl<Value> TrailingSeparatedList<(Whitespace0, Value), Whitespace0> {
+    /// Converts a list whose values and separators were parsed together with the space in front of them
+    /// into an fst `List`, `end_space` is the space in front of the closing delimiter
+    pub fn into_list(self, end_space: Whitespace0) -> List<Value> {
+        let trailing_comma = self.has_trailing_separator();
+        let mut items = spaced_items(self.elements);
+        match items.last_mut() {
+            Some(last) if !trailing_comma => {
+                last.post_space = end_space;
+                List {
+                    items,
+                    trailing_comma,
+                    end_space: Vec::new(),
+                }
+            }
+            _ => List {
+                items,
+                trailing_comma,
+                end_space,
+            },
+        }
     }
 }
 
+/// Pairs up values with the space in front of them and the space in front of the following separator
+fn spaced_items<Value>(
+    elements: Vec<ListElement<(Whitespace0, Value), Whitespace0>>,
+) -> Vec<Spaced<Value>> {
+    let mut items: Vec<Spaced<Value>> = Vec::new();
+    for element in elements {
+        match element {
+            ListElement::Value((pre_space, value)) => items.push(Spaced {
+                pre_space,
+                value,
+                post_space: Vec::new(),
+            }),
+            ListElement::Separator(space) => {
+                if let Some(last) = items.last_mut() {
+                    last.post_space = space;
+                }
+            }
+        }
+    }
+    items
+}
+
 pub struct StrictSeparatedList<Value, Separator> {
     elements: Vec<ListElement<Value, Separator>>,
 }
@@ -135,27 +158,12 @@ impl<Value, Separator> StrictSeparatedList<Value, Separator> {
         self.elements.push(ListElement::Value(value));
         Ok(())
     }
+}
 
-    pub fn is_empty_values(&self) -> bool {
-        self.elements.len() == 0
-    }
-
-    pub fn len_values(&self) -> usize {
-        (self.elements.len() + 1) / 2
-    }
-
-    pub fn values(&self) -> impl Iterator<Item = &Value> {
-        self.elements
-            .iter()
-            .step_by(2)
-            .map(|element| match element {
-                ListElement::Value(value) => value,
-                ListElement::Separator(_) => panic!("This shouldn't be possible"),
-            })
-    }
-
-    pub fn iter_elements(&self) -> impl Iterator<Item = &ListElement<Value, Separator>> {
-        self.elements.iter()
+impl<Value> StrictSeparatedList<(Whitespace0, Value), Whitespace0> {
+    /// Converts the values into `Spaced` values, keeping the space in front of every separator
+    pub fn into_spaced_values(self) -> Vec<Spaced<Value>> {
+        spaced_items(self.elements)
     }
 }
 
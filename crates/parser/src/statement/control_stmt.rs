@@ -1,4 +1,4 @@
-use fst::{Expression, LabelExpression, Statement, Whitespace0, Whitespace1};
+use fst::{LabelExpression, SpacedLabel, Statement, Whitespace0};
 use parser_core::*;
 
 use crate::{
@@ -6,7 +6,17 @@ use crate::{
     utils::{opt, ws0, ws1},
 };
 
-use super::semicolon::opt_semicolon;
+fn parse_semi_space<'a>(input: Span<'a>) -> SafeParserResult<'a, Option<Whitespace0>> {
+    opt((ws0, parse_semicolon).tuple().map(|(space, _)| space))(input)
+}
+
+fn parse_spaced_label<'a>(input: Span<'a>) -> SafeParserResult<'a, SpacedLabel> {
+    let (input, label) = opt((ws1, parse_label)
+        .tuple()
+        .map(|(space, label)| (space, label.to_string())))(input);
+    let (input, semi_space) = parse_semi_space(input);
+    (input, SpacedLabel { label, semi_space })
+}
 
 fn parse_label_expression<'a>(input: Span<'a>) -> ParserResult<'a, LabelExpression> {
     if let Ok((input, pre_space)) = ws1(input) {
@@ -20,68 +30,59 @@ fn parse_label_expression<'a>(input: Span<'a>) -> ParserResult<'a, LabelExpressi
                         LabelExpression::WithExpression {
                             pre_space,
                             label: Some((label.to_string(), post_label_space)),
-                            expr: value,
+                            expr: Box::new(value),
                             semi_space,
                         },
                     ));
                 }
             }
-            let (input, semi_space) =
-                opt((ws0, parse_semicolon).tuple().map(|(space, _)| space))(input);
+            let (input, semi_space) = parse_semi_space(input);
+            return Ok((
+                input,
+                LabelExpression::NoExpression(SpacedLabel {
+                    label: Some((pre_space, label.to_string())),
+                    semi_space,
+                }),
+            ));
+        } else if let Ok((input, value)) = parse_expression(input) {
+            let (input, semi_space) = ws0(input);
+            let (input, _) = parse_semicolon(input)?;
             return Ok((
                 input,
-                LabelExpression::JustLabel {
+                LabelExpression::WithExpression {
                     pre_space,
-                    label: label.to_string(),
+                    expr: Box::new(value),
                     semi_space,
+                    label: None,
                 },
             ));
-        } else {
-            if let Ok((input, value)) = parse_expression(input) {
-                let (input, semi_space) = ws0(input);
-                let (input, _) = parse_semicolon(input)?;
-                return Ok((
-                    input,
-                    LabelExpression::WithExpression {
-                        pre_space,
-                        expr: value,
-                        semi_space,
-                        label: None,
-                    },
-                ));
-            }
         }
     }
-    let (input, semi_space) = opt((ws0, parse_semicolon).tuple().map(|(space, _)| space))(input);
-    Ok((input, LabelExpression::Nothing { semi_space }))
+    let (input, semi_space) = parse_semi_space(input);
+    Ok((
+        input,
+        LabelExpression::NoExpression(SpacedLabel {
+            label: None,
+            semi_space,
+        }),
+    ))
 }
 
 pub fn parse_break_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
     let (input, _) = parse_break(input)?;
-    let (input, (label, value)) = parse_label_expression(input)?;
-    Ok((input, Statement::Break { label, value }))
+    let (input, label_expression) = parse_label_expression(input)?;
+    Ok((input, Statement::Break(label_expression)))
 }
 
 pub fn parse_return_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
     let (input, _) = parse_return(input)?;
-    let (input, (label, value)) = parse_label_expression(input)?;
-    Ok((input, Statement::Return { label, value }))
+    let (input, label_expression) = parse_label_expression(input)?;
+    Ok((input, Statement::Return(label_expression)))
 }
 
 pub fn parse_continue_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
     // continue aren't allowed to have a value, but may have a label
     let (input, _) = parse_continue(input)?;
-    if let Ok((input, _)) = ws1(input) {
-        if let Ok((input, label)) = parse_label(input) {
-            let (input, _) = opt_semicolon(input);
-            return Ok((
-                input,
-                Statement::Continue {
-                    label: Some(label.to_string()),
-                },
-            ));
-        }
-    }
-    let (input, _) = opt_semicolon(input);
-    Ok((input, Statement::Continue { label: None }))
+    let (input, spaced_label) = parse_spaced_label(input);
+    Ok((input, Statement::Continue(spaced_label)))
 }
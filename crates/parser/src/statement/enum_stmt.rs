@@ -1,60 +1,59 @@
-use fst::{EnumValue, Statement};
+use fst::{EnumOption, EnumValue, Expression, List, Located, Statement};
 use parser_core::*;
 
 use crate::{
-    expression::parse_expression,
+    attribute::parse_attributes,
+    generics::parse_generic_parameters,
+    expression::parse_type_expression,
+    separated_list::parser::comma_list,
     utils::{opt, ws0},
+    visibility::parse_visibility,
 };
 
 use super::{semicolon::opt_semicolon, struct_stmt::parse_struct_block};
 
 pub fn parse_enum_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
+    let (input, attributes) = parse_attributes(input)?;
+    let (input, visibility) = parse_visibility(input);
     let (input, _) = parse_enum(input)?;
-    let (input, _) = ws0(input);
+    let (input, after_enum) = ws0(input);
     let (input, name) = parse_ident(input)?;
-    let (input, _) = ws0(input);
+    let (input, generics) = parse_generic_parameters(input)?;
+    let (input, after_name) = ws0(input);
     let (input, _) = parse_left_brace(input)?;
-    let (input, (options, _)) = separated_list(
-        (ws0, parse_comma).tuple(),
-        preceded(
-            ws0,
-            (
-                parse_ident.map(|s| s.to_string()),
-                opt((
-                    preceded((ws0, parse_left_paren).tuple(), parse_enum_arguments),
-                    parse_struct_block.map(|v| EnumValue::Struct(v)),
-                )
-                    .alt())
-                .map(|v| match v {
-                    Some(type_) => type_,
-                    None => EnumValue::Unit,
-                }),
-            )
-                .tuple(),
-        ),
-        (ws0, parse_right_brace).tuple(),
-        true,
-        true,
-        false,
-    )(input)?;
-    let (input, _) = opt_semicolon(input);
+    let (input, (options, _)) =
+        comma_list(parse_enum_option.located(), parse_right_brace, false)(input)?;
+    let (input, semi) = opt_semicolon(input);
     Ok((
         input,
         Statement::Enum {
+            attributes,
+            visibility,
+            after_enum,
             name: name.to_string(),
+            generics,
+            after_name,
             options,
+            semi,
         },
     ))
 }
 
-fn parse_enum_arguments<'a>(input: Span<'a>) -> ParserResult<'a, EnumValue> {
-    separated_list(
-        (ws0, parse_comma).tuple(),
-        preceded(ws0, parse_expression),
-        (ws0, parse_right_paren).tuple(),
-        true,
-        true,
-        false,
+fn parse_enum_option<'a>(input: Span<'a>) -> ParserResult<'a, EnumOption> {
+    let (input, name) = parse_ident(input)?;
+    let (input, value) = opt((
+        (ws0, parse_left_paren, parse_enum_arguments)
+            .tuple()
+            .map(|(space, _, values)| EnumValue::Tuple { space, values }),
+        (ws0, parse_struct_block)
+            .tuple()
+            .map(|(space, fields)| EnumValue::Struct { space, fields }),
     )
-    .map(|v| EnumValue::Tuple(v.0))(input)
+        .alt())(input);
+    Ok((input, (name.to_string(), value.unwrap_or(EnumValue::Unit))))
+}
+
+fn parse_enum_arguments<'a>(input: Span<'a>) -> ParserResult<'a, List<Located<Expression>>> {
+    let (input, (arguments, _)) = comma_list(parse_type_expression, parse_right_paren, false)(input)?;
+    Ok((input, arguments))
 }
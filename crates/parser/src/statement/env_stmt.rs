@@ -6,8 +6,15 @@ use super::semicolon::require_semicolon;
 
 pub fn parse_env_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
     let (input, _) = parse_use_env(input)?;
-    let (input, _) = ws0(input);
-    let (input, name) = parse_expression(input)?;
-    let (input, _) = require_semicolon(input)?;
-    Ok((input, Statement::Env(name)))
-}
\ No newline at end of file
+    let (input, after_use_env) = ws0(input);
+    let (input, expr) = parse_expression(input)?;
+    let (input, semi) = require_semicolon(input)?;
+    Ok((
+        input,
+        Statement::Env {
+            after_use_env,
+            expr,
+            semi,
+        },
+    ))
+}
@@ -1,69 +1,91 @@
 use crate::{
-    block::parse_block, expression::parse_expression,
-    function_parameters::parse_function_parameters, utils::{ws0, ws1},
+    attribute::parse_attributes,
+    generics::parse_generic_parameters,
+    block::parse_block,
+    expression::parse_type_expression,
+    function_parameters::parse_method_parameters,
+    utils::{opt, ws0, ws1},
+    unsafety::parse_unsafety,
+    visibility::parse_visibility,
+};
+use fst::{
+    BodySeparator, Closure, ClosureSignature, Expression, FunctionSignature, Located, ReturnType,
+    Statement,
 };
-use fst::{Closure, ClosureSignature, Expression, FunctionSignature, Statement};
 use parser_core::*;
 
 use super::semicolon::opt_semicolon;
 
 pub fn parse_function_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
+    let (input, attributes) = parse_attributes(input)?;
+    let (input, visibility) = parse_visibility(input);
+    let start = input;
     let (input, signature) = parse_fn_signature(input)?;
 
-    let (input, _) = ws0(input);
-    let (input, code) = parse_block(input)?;
+    let (input, body_space) = ws0(input);
+    let (input, code) = parse_block.located()(input)?;
+    let closure_span = start.source_span_until(&input);
 
-    let (input, _) = opt_semicolon(input);
+    let (input, semi) = opt_semicolon(input);
 
     Ok((
         input,
         Statement::Function {
+            attributes,
+            visibility,
+            unsafety: signature.unsafety,
+            after_fn: signature.after_fn,
             name: signature.name,
-            closure: Closure {
-                closure_signature: signature.closure_signature,
-                body: Expression::Block {
-                    environment: None,
-                    block: code,
+            generics: signature.generics,
+            after_name: signature.after_name,
+            closure: Box::new(Located::new(
+                Closure {
+                    closure_signature: signature.closure_signature,
+                    separator: BodySeparator::Space(body_space),
+                    body: code.map(|block| Expression::Block {
+                        environment: None,
+                        block,
+                    }),
                 },
-            },
+                closure_span,
+            )),
+            semi,
         },
     ))
 }
 
 pub fn parse_fn_signature<'a>(input: Span<'a>) -> ParserResult<'a, FunctionSignature> {
+    let (input, unsafety) = parse_unsafety(input);
     let (input, _) = parse_fn(input)?;
-    let (input, _) = ws1(input)?;
+    let (input, after_fn) = ws1(input)?;
     let (input, name) = parse_ident(input)?;
-    let (input, _) = ws0(input);
+    let (input, generics) = parse_generic_parameters(input)?;
+    let (input, after_name) = ws0(input);
 
     let (input, _) = parse_left_paren(input)?;
-    let (input, params) = parse_function_parameters(false, parse_right_paren)(input)?;
+    let (input, (receiver, params)) = parse_method_parameters(input)?;
 
-    let (input, _) = ws0(input);
+    let (input, return_type) = opt((ws0, parse_arrow, ws0, parse_type_expression).tuple().map(
+        |(pre_arrow, _, post_arrow, value_type)| ReturnType {
+            pre_arrow,
+            post_arrow,
+            value_type,
+        },
+    ))(input);
 
-    if let Ok((input, _)) = parse_arrow(input) {
-        let (input, _) = ws0(input);
-        let (input, return_type) = parse_expression(input)?;
-        Ok((
-            input,
-            FunctionSignature {
-                name: name.to_string(),
-                closure_signature: ClosureSignature {
-                    params,
-                    return_type: Some(return_type),
-                },
-            },
-        ))
-    } else {
-        Ok((
-            input,
-            FunctionSignature {
-                name: name.to_string(),
-                closure_signature: ClosureSignature {
-                    params,
-                    return_type: None,
-                },
+    Ok((
+        input,
+        FunctionSignature {
+            unsafety,
+            after_fn,
+            name: name.to_string(),
+            generics,
+            after_name,
+            closure_signature: ClosureSignature {
+                receiver,
+                params,
+                return_type,
             },
-        ))
-    }
+        },
+    ))
 }
@@ -1,28 +1,103 @@
-use crate::{block::parse_block, utils::{opt, ws0, ws1}};
-use fst::{Expression, Statement};
+use crate::{
+    attribute::parse_attributes,
+    block::parse_block,
+    expression::parse_type_expression,
+    generics::parse_generic_parameters,
+    utils::{opt, ws0, ws1},
+};
+use fst::Statement;
 use parser_core::*;
 
 use super::semicolon::opt_semicolon;
 
 pub fn parse_impl_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
+    let (input, attributes) = parse_attributes(input)?;
     let (input, _) = parse_impl(input)?;
-    let (input, _) = ws0(input);
-    let (input, trait_name) = opt((parse_ident, ws1, parse_for)
+    let (input, generics) = parse_generic_parameters(input)?;
+    let (input, after_impl) = ws0(input);
+    let (input, implemented) = opt((parse_type_expression, ws1, parse_for, ws1)
         .tuple()
-        .map(|v| v.0.to_string()))(input);
-    let (input, identifier) = parse_ident(input)?;
-    let (input, _) = ws0(input);
+        .map(|(implemented, pre_for, _, post_for)| (Box::new(implemented), pre_for, post_for)))(
+        input
+    );
+    let (input, target) = parse_type_expression(input)?;
+    let (input, after_target) = ws0(input);
     let (input, statements) = parse_block(input)?;
-    let (input, _) = opt_semicolon(input);
+    let (input, semi) = opt_semicolon(input);
 
     Ok((
         input,
         Statement::Impl {
-            target: identifier.to_string(),
-            implemented: trait_name.map(|v| Expression::Variable {
-                identifier: v.to_string(),
-            }),
+            attributes,
+            generics,
+            after_impl,
+            implemented,
+            target: Box::new(target),
+            after_target,
             statements,
+            semi,
         },
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use fst::{Expression, Located, Statement, UnaryOperation};
+
+    use crate::{statement::parse_statement, utils::ParseString};
+
+    /// Renders a type such as `Coerce<..>`, leaving out the generic arguments
+    fn type_name(expr: &Located<Expression>) -> String {
+        match &expr.value {
+            Expression::Variable { identifier } => identifier.clone(),
+            Expression::SingleOperation {
+                operation: UnaryOperation::GenericArguments { .. },
+                operand,
+                ..
+            } => format!("{}<..>", type_name(operand)),
+            other => panic!("Unexpected type {:?}", other),
+        }
+    }
+
+    fn impl_header(code: &str) -> (Option<String>, String) {
+        let statement = parse_statement.parse_string(code).unwrap();
+        let Statement::Impl {
+            implemented,
+            target,
+            ..
+        } = statement.value
+        else {
+            panic!("Expected an impl, got {:?}", statement.value);
+        };
+        (
+            implemented.map(|(implemented, _, _)| type_name(&implemented)),
+            type_name(&target),
+        )
+    }
+
+    #[test]
+    fn test_inherent_impl() {
+        assert_eq!(impl_header("impl A {}"), (None, "A".to_string()));
+        assert_eq!(
+            impl_header("impl<T> List<T> { fn f() {} }"),
+            (None, "List<..>".to_string())
+        );
+    }
+
+    #[test]
+    fn test_trait_impl() {
+        assert_eq!(
+            impl_header("impl Coerce<RangeIterator> for Range {}"),
+            (Some("Coerce<..>".to_string()), "Range".to_string())
+        );
+        assert_eq!(
+            impl_header("impl<T> Iterator<T> for List<T> {}"),
+            (Some("Iterator<..>".to_string()), "List<..>".to_string())
+        );
+    }
+
+    #[test]
+    fn test_missing_target() {
+        assert!(parse_statement.parse_string("impl A for {}").is_err());
+    }
+}
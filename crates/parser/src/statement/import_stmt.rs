@@ -1,42 +1,163 @@
-use fst::Statement;
+use fst::{ImportPath, ImportSegment, RelativeImport, Spaced, Statement, Using};
 use parser_core::*;
 
 use crate::{
     destructure::parse_mutable_extract,
     expression::parse_expression,
-    utils::{ws0, ws1},
+    separated_list::parser::comma_list,
+    utils::{opt, ws0, ws1},
 };
 
 use super::semicolon::{opt_semicolon, require_semicolon};
 
 pub fn parse_import_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
     let (input, _) = parse_import(input)?;
-    let (input, _) = ws0(input);
-    let (input, import_expression) = parse_expression(input)?;
-
-    match (ws1, parse_as).tuple()(input) {
-        Ok((input, _)) => {
-            let (input, _) = ws1(input)?;
-            let (input, extract) = parse_mutable_extract(input)?;
-            let (input, _) = opt_semicolon(input);
-            Ok((
-                input,
-                Statement::Import {
-                    importable: import_expression,
-                    extract: Some(extract),
-                },
-            ))
-        }
-        Err(_) => {
-            let (input, _) = require_semicolon(input)?;
-
-            Ok((
-                input,
-                Statement::Import {
-                    importable: import_expression,
-                    extract: None,
-                },
-            ))
-        }
+    let (input, after_import) = ws0(input);
+    let (input, path) = parse_import_path.located()(input)?;
+
+    let (input, extract) = opt(|input| {
+        let (input, (pre_as, _, post_as)) = (ws1, parse_as, ws1).tuple()(input)?;
+        let (input, extract) = parse_mutable_extract(input)?;
+        Ok((input, (pre_as, post_as, extract)))
+    })(input);
+    let (input, using) = opt((ws1, parse_using, ws1, parse_expression).tuple().map(
+        |(pre_using, _, post_using, schema)| Using {
+            pre_using,
+            post_using,
+            schema,
+        },
+    ))(input);
+
+    let (input, semi) = match (&extract, &using) {
+        (Some(_), None) => opt_semicolon(input),
+        _ => require_semicolon(input).map(|(input, semi)| (input, Some(semi)))?,
+    };
+    Ok((
+        input,
+        Statement::Import {
+            after_import,
+            path,
+            extract,
+            using,
+            semi,
+        },
+    ))
+}
+
+/// std.error
+/// ./*
+/// ../lib.{a, b}
+fn parse_import_path<'a>(input: Span<'a>) -> ParserResult<'a, ImportPath> {
+    let (input, relative) = match parse_current_directory(input) {
+        Ok((input, _)) => (input, RelativeImport::Current),
+        Err(_) => match many0(parse_parent_directory)(input) {
+            (input, parents) if !parents.is_empty() => (input, RelativeImport::Parent(parents.len())),
+            (input, _) => (input, RelativeImport::None),
+        },
+    };
+
+    let (mut input, first) = parse_import_segment(input)?;
+    let mut segments = vec![Spaced {
+        pre_space: Vec::new(),
+        value: first,
+        post_space: Vec::new(),
+    }];
+    // a glob or a list ends the path
+    while matches!(segments.last().unwrap().value, ImportSegment::Name(_)) {
+        let (rest, Some((pre_dot, _, post_dot, segment))) =
+            opt((ws0, parse_dot, ws0, parse_import_segment).tuple())(input)
+        else {
+            break;
+        };
+        segments.last_mut().unwrap().post_space = pre_dot;
+        segments.push(Spaced {
+            pre_space: post_dot,
+            value: segment,
+            post_space: Vec::new(),
+        });
+        input = rest;
+    }
+    Ok((input, ImportPath { relative, segments }))
+}
+
+fn parse_import_segment<'a>(input: Span<'a>) -> ParserResult<'a, ImportSegment> {
+    let list = |input| {
+        let (input, _) = parse_left_brace(input)?;
+        let (input, (names, _)) = comma_list(
+            parse_ident.map(|name| name.to_string()),
+            parse_right_brace,
+            true,
+        )(input)?;
+        Ok((input, ImportSegment::List(names)))
+    };
+    (
+        parse_ident.map(|name| ImportSegment::Name(name.to_string())),
+        parse_star.map(|_| ImportSegment::Glob),
+        list,
+    )
+        .alt()(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::{Expression, Located};
+
+    use crate::{statement::parse_statement, utils::ParseString};
+
+    use super::*;
+
+    fn import(code: &str) -> (ImportPath, Option<Located<Expression>>) {
+        let statement = parse_statement.parse_string(code).unwrap();
+        let Statement::Import { path, using, .. } = statement.value else {
+            panic!("Expected an import, got {:?}", statement.value);
+        };
+        (path.value, using.map(|using| using.schema))
+    }
+
+    fn segments(path: &ImportPath) -> Vec<String> {
+        path.segments
+            .iter()
+            .map(|segment| match &segment.value {
+                ImportSegment::Name(name) => name.clone(),
+                ImportSegment::Glob => "*".to_string(),
+                ImportSegment::List(names) => {
+                    format!("{{{}}}", names.values().cloned().collect::<Vec<_>>().join(", "))
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_import_paths() {
+        let (path, _) = import("import std.lang.rust.{rust_std, rs};");
+        assert_eq!(path.relative, RelativeImport::None);
+        assert_eq!(segments(&path), vec!["std", "lang", "rust", "{rust_std, rs}"]);
+        let (path, _) = import("import std . error;");
+        assert_eq!(segments(&path), vec!["std", "error"]);
+        let (path, _) = import("import std.*;");
+        assert_eq!(segments(&path), vec!["std", "*"]);
+    }
+
+    #[test]
+    fn test_relative_imports() {
+        let (path, using) = import("import ./* as docs using doc;");
+        assert_eq!(path.relative, RelativeImport::Current);
+        assert_eq!(segments(&path), vec!["*"]);
+        assert!(matches!(using.unwrap().value, Expression::Variable { .. }));
+        let (path, _) = import("import ../../lib.docref;");
+        assert_eq!(path.relative, RelativeImport::Parent(2));
+        assert_eq!(segments(&path), vec!["lib", "docref"]);
+    }
+
+    #[test]
+    fn test_invalid_imports() {
+        // nothing can follow a glob or a list
+        assert!(parse_statement.parse_string("import a.*.b;").is_err());
+        assert!(parse_statement.parse_string("import a.{b}.c;").is_err());
+        assert!(parse_statement.parse_string("import a.{};").is_err());
+        // the semicolon is only optional after an extract
+        assert!(parse_statement.parse_string("import a.b").is_err());
+        assert!(parse_statement.parse_string("import a as { b }").is_ok());
+        assert!(parse_statement.parse_string("import ./* using doc").is_err());
     }
 }
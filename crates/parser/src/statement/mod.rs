@@ -8,17 +8,19 @@ mod module_stmt;
 mod semicolon;
 mod struct_stmt;
 mod trait_stmt;
+mod type_alias_stmt;
 
 use control_stmt::{parse_break_statement, parse_continue_statement, parse_return_statement};
 use env_stmt::parse_env_statement;
-use fst::Statement;
+use fst::{Expression, Located, Statement};
 use function_stmt::parse_function_statement;
 use impl_stmt::parse_impl_statement;
 use module_stmt::parse_module_statement;
 use parser_core::*;
 use trait_stmt::parse_trait_statement;
+use type_alias_stmt::parse_type_alias_statement;
 
-use crate::utils::{opt, opt_bool};
+use crate::{utils::opt, visibility::parse_visibility};
 
 use self::{
     enum_stmt::parse_enum_statement, import_stmt::parse_import_statement,
@@ -27,7 +29,11 @@ use self::{
 
 use super::{expression::parse_expression, utils::ws0};
 
-pub fn parse_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
+pub fn parse_statement<'a>(input: Span<'a>) -> ParserResult<'a, Located<Statement>> {
+    parse_unlocated_statement.located()(input)
+}
+
+fn parse_unlocated_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
     match (
         parse_return_statement,
         parse_break_statement,
@@ -35,6 +41,7 @@ pub fn parse_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
         parse_function_statement,
         parse_struct_statement,
         parse_enum_statement,
+        parse_type_alias_statement,
         parse_trait_statement,
         parse_impl_statement,
         parse_import_statement,
@@ -44,20 +51,29 @@ pub fn parse_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
         .alt()(input)
     {
         Ok((input, statement)) => Ok((input, statement)),
-        Err(statement_parse_error) => match parse_expression(input) {
-            Ok((input, expression)) => {
-                let (input, has_semi) = opt_bool((ws0, parse_semicolon).tuple())(input);
-                Ok((
-                    input,
-                    Statement::Expression {
-                        expr: expression,
-                        semi: !has_semi,
-                    },
-                ))
-            }
+        Err(statement_parse_error) => match parse_expression_statement(input) {
+            Ok(result) => Ok(result),
             Err(expression_parse_error) => {
                 Err(statement_parse_error.accumulate(expression_parse_error))
             }
         },
     }
 }
+
+fn parse_expression_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
+    let (input, visibility) = parse_visibility(input);
+    let (rest, expression) = parse_expression(input)?;
+    if visibility.is_some() && !matches!(expression.value, Expression::Declaration { .. }) {
+        let (token, source_span) = input.take_token().1;
+        return Err(token.as_parser_error(TokenKind::Let.into(), source_span));
+    }
+    let (rest, semi) = opt((ws0, parse_semicolon).tuple().map(|(space, _)| space))(rest);
+    Ok((
+        rest,
+        Statement::Expression {
+            visibility,
+            expr: expression,
+            semi,
+        },
+    ))
+}
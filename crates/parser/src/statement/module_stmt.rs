@@ -1,22 +1,31 @@
 use fst::Statement;
 use parser_core::*;
 
-use crate::{block::parse_block, utils::ws0};
+use crate::{
+    attribute::parse_attributes, block::parse_block, utils::ws0, visibility::parse_visibility,
+};
 
 use super::semicolon::opt_semicolon;
 
 pub fn parse_module_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
+    let (input, attributes) = parse_attributes(input)?;
+    let (input, visibility) = parse_visibility(input);
     let (input, _) = parse_mod(input)?;
-    let (input, _) = ws0(input);
+    let (input, after_mod) = ws0(input);
     let (input, name) = parse_ident(input)?;
-    let (input, _) = ws0(input);
+    let (input, after_name) = ws0(input);
     let (input, statements) = parse_block(input)?;
-    let (input, _) = opt_semicolon(input);
+    let (input, semi) = opt_semicolon(input);
     Ok((
         input,
         Statement::Module {
+            attributes,
+            visibility,
+            after_mod,
             name: name.to_string(),
+            after_name,
             statements,
+            semi,
         },
     ))
 }
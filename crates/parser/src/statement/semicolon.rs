@@ -1,14 +1,17 @@
 use parser_core::*;
 
+use fst::Whitespace0;
+
 use crate::utils::{opt, ws0};
 
-pub fn require_semicolon<'a>(input: Span<'a>) -> ParserResult<'a, ()> {
-    let (input, _) = ws0(input);
+/// Parses a semicolon, returning the space in front of it
+pub fn require_semicolon<'a>(input: Span<'a>) -> ParserResult<'a, Whitespace0> {
+    let (input, space) = ws0(input);
     let (input, _) = parse_semicolon(input)?;
-    Ok((input, ()))
+    Ok((input, space))
 }
 
-pub fn opt_semicolon<'a>(input: Span<'a>) -> SafeParserResult<'a, bool> {
-    let (input, semi) = opt((ws0, parse_semicolon).tuple())(input);
-    (input, semi.is_some())
+/// Parses an optional semicolon, returning the space in front of it if it is present
+pub fn opt_semicolon<'a>(input: Span<'a>) -> SafeParserResult<'a, Option<Whitespace0>> {
+    opt(require_semicolon)(input)
 }
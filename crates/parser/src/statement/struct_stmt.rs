@@ -1,37 +1,58 @@
-use crate::{expression::parse_expression, utils::ws0};
-use fst::{Expression, Statement};
+use crate::{
+    attribute::parse_attributes,
+    generics::parse_generic_parameters,
+    field::parse_typed_field,
+    separated_list::parser::comma_list,
+    utils::ws0,
+    visibility::parse_visibility,
+};
+use fst::{Field, List, Statement, StructField};
 use parser_core::*;
 
 use super::semicolon::opt_semicolon;
 
-pub fn parse_struct_block<'a>(input: Span<'a>) -> ParserResult<'a, Vec<(String, Expression)>> {
+pub fn parse_struct_block<'a>(input: Span<'a>) -> ParserResult<'a, List<Field>> {
     let (input, _) = parse_left_brace(input)?;
-    let (input, (fields, _)) = separated_list(
-        (ws0, parse_comma).tuple(),
-        (ws0, parse_ident, ws0, parse_colon, ws0, parse_expression)
-            .tuple()
-            .map(|(_, ident, _, _, _, expr)| (ident.to_string(), expr)),
-        (ws0, parse_right_brace).tuple(),
-        true,
-        true,
-        false,
-    )(input)?;
+    let (input, (fields, _)) = comma_list(parse_typed_field, parse_right_brace, false)(input)?;
     Ok((input, fields))
 }
+
+/// Parses the fields of a struct declaration, which unlike those of enum options can be public
+fn parse_struct_fields<'a>(input: Span<'a>) -> ParserResult<'a, List<StructField>> {
+    let (input, _) = parse_left_brace(input)?;
+    let (input, (fields, _)) = comma_list(parse_struct_field, parse_right_brace, false)(input)?;
+    Ok((input, fields))
+}
+
+fn parse_struct_field<'a>(input: Span<'a>) -> ParserResult<'a, StructField> {
+    let (input, visibility) = parse_visibility(input);
+    let (input, field) = parse_typed_field(input)?;
+    Ok((input, StructField { visibility, field }))
+}
+
 pub fn parse_struct_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
+    let (input, attributes) = parse_attributes(input)?;
+    let (input, visibility) = parse_visibility(input);
     let (input, _) = parse_struct(input)?;
-    let (input, _) = ws0(input);
+    let (input, after_struct) = ws0(input);
     let (input, name) = parse_ident(input)?;
-    let (input, _) = ws0(input);
+    let (input, generics) = parse_generic_parameters(input)?;
+    let (input, after_name) = ws0(input);
 
-    let (input, fields) = parse_struct_block(input)?;
-    let (input, _) = opt_semicolon(input);
+    let (input, fields) = parse_struct_fields(input)?;
+    let (input, semi) = opt_semicolon(input);
 
     Ok((
         input,
         Statement::Struct {
+            attributes,
+            visibility,
+            after_struct,
             name: name.to_string(),
+            generics,
+            after_name,
             fields,
+            semi,
         },
     ))
 }
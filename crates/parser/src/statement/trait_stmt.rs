@@ -1,73 +1,82 @@
 use crate::{
-    expression::parse_expression,
-    utils::{opt, ws0},
+    attribute::parse_attributes, block::parse_braced_items,
+    function_parameters::parse_type_annotation, generics::parse_generic_parameters,
+    unsafety::parse_unsafety, utils::ws0, visibility::parse_visibility,
 };
-use fst::{PropertySignature, Signature, Statement};
+use fst::{Located, PropertySignature, Signature, Statement, Whitespace0};
 use parser_core::*;
 
 use super::{function_stmt::parse_fn_signature, semicolon::opt_semicolon};
 
 pub fn parse_trait_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
+    let (input, attributes) = parse_attributes(input)?;
+    let (input, visibility) = parse_visibility(input);
+    let (input, unsafety) = parse_unsafety(input);
     let (input, _) = parse_trait(input)?;
-    let (input, _) = ws0(input);
+    let (input, after_trait) = ws0(input);
     let (input, name) = parse_ident(input)?;
-    let (input, _) = ws0(input);
+    let (input, generics) = parse_generic_parameters(input)?;
+    let (input, after_name) = ws0(input);
     let (input, _) = parse_left_brace(input)?;
-    let (input, _) = ws0(input);
-    let (input, (signatures, _)) =
-        separated_list(ws0, parse_signature, parse_right_brace, true, true, false)(input)?;
-    let (input, _) = opt_semicolon(input);
+    let (input, (pre_space, signatures)) = parse_braced_items(parse_signature)(input)?;
+    let (input, semi) = opt_semicolon(input);
     Ok((
         input,
         Statement::Trait {
+            attributes,
+            visibility,
+            unsafety,
+            after_trait,
             name: name.to_string(),
-            signatures,
+            generics,
+            after_name,
+            pre_space,
+            signatures: signatures
+                .into_iter()
+                .map(|((signature, semi), space)| (signature, semi, space))
+                .collect(),
+            semi,
         },
     ))
 }
 
-fn parse_signature<'a>(input: Span<'a>) -> ParserResult<'a, Signature> {
+fn parse_signature<'a>(
+    input: Span<'a>,
+) -> ParserResult<'a, (Located<Signature>, Option<Whitespace0>)> {
     let (input, signature) = (
-        parse_fn_signature.map(|v| Signature::Function(v)),
+        parse_fn_signature.map(|v| Signature::Function(Box::new(v))),
         parse_property_signature,
     )
-        .alt()(input)?;
-    let (input, _) = ws0(input);
-    let (input, _) = opt(parse_semicolon)(input);
-    let (input, _) = ws0(input);
-    Ok((input, signature))
+        .alt()
+        .located()(input)?;
+    let (input, semi) = opt_semicolon(input);
+    Ok((input, (signature, semi)))
 }
 
 fn parse_property_signature<'a>(input: Span<'a>) -> ParserResult<'a, Signature> {
     let (input, (token, source_span)) = input.take_token();
     match token.delocate() {
         Some(Token::Ident(name)) => {
-            let (input, _) = ws0(input);
-            let (input, _) = parse_colon(input)?;
-            let (input, _) = ws0(input);
-            let (input, return_type) = parse_expression(input)?;
+            let (input, value_type) = parse_type_annotation(input)?;
             Ok((
                 input,
                 Signature::Property(PropertySignature {
                     name: name.to_string(),
-                    value_type: return_type,
-                    mutable: false,
+                    value_type,
+                    mutable: None,
                 }),
             ))
         }
         Some(Token::Mut) => {
-            let (input, _) = ws0(input);
+            let (input, after_mut) = ws0(input);
             let (input, name) = parse_ident(input)?;
-            let (input, _) = ws0(input);
-            let (input, _) = parse_colon(input)?;
-            let (input, _) = ws0(input);
-            let (input, return_type) = parse_expression(input)?;
+            let (input, value_type) = parse_type_annotation(input)?;
             Ok((
                 input,
                 Signature::Property(PropertySignature {
                     name: name.to_string(),
-                    value_type: return_type,
-                    mutable: true,
+                    value_type,
+                    mutable: Some(after_mut),
                 }),
             ))
         }
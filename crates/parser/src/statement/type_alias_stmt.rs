@@ -0,0 +1,80 @@
+use crate::{
+    attribute::parse_attributes, expression::parse_type_expression,
+    generics::parse_generic_parameters, utils::ws0, visibility::parse_visibility,
+};
+use fst::Statement;
+use parser_core::*;
+
+use super::semicolon::require_semicolon;
+
+/// type Name<T> = Option<T>;
+pub fn parse_type_alias_statement<'a>(input: Span<'a>) -> ParserResult<'a, Statement> {
+    let (input, attributes) = parse_attributes(input)?;
+    let (input, visibility) = parse_visibility(input);
+    let (input, _) = parse_type(input)?;
+    let (input, after_type) = ws0(input);
+    let (input, name) = parse_ident(input)?;
+    let (input, generics) = parse_generic_parameters(input)?;
+    let (input, pre_equals) = ws0(input);
+    let (input, _) = parse_assignment(input)?;
+    let (input, post_equals) = ws0(input);
+    let (input, value_type) = parse_type_expression(input)?;
+    let (input, semi) = require_semicolon(input)?;
+
+    Ok((
+        input,
+        Statement::TypeAlias {
+            attributes,
+            visibility,
+            after_type,
+            name: name.to_string(),
+            generics,
+            pre_equals,
+            post_equals,
+            value_type,
+            semi,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::{Expression, UnaryOperation};
+
+    use crate::{statement::parse_statement, utils::ParseString};
+
+    use super::*;
+
+    #[test]
+    fn test_type_aliases() {
+        let statement = parse_statement
+            .parse_string("pub type Pair<T> = Tuple<T, T>;")
+            .unwrap();
+        let Statement::TypeAlias {
+            visibility,
+            name,
+            generics,
+            value_type,
+            ..
+        } = statement.value
+        else {
+            panic!("Expected a type alias, got {:?}", statement.value);
+        };
+        assert!(visibility.is_some());
+        assert_eq!(name, "Pair");
+        assert_eq!(generics.unwrap().params.values().count(), 1);
+        assert!(matches!(
+            value_type.value,
+            Expression::SingleOperation {
+                operation: UnaryOperation::GenericArguments { .. },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_type_alias_requires_semicolon() {
+        assert!(parse_statement.parse_string("type A = B").is_err());
+        assert!(parse_statement.parse_string("type A = B | C ;").is_ok());
+    }
+}
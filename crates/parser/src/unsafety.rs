@@ -0,0 +1,71 @@
+use fst::Whitespace1;
+use parser_core::*;
+
+use crate::utils::{opt, ws1};
+
+/// Parses `unsafe` and the space after it in front of a function or a trait, `None` for safe ones
+pub fn parse_unsafety<'a>(input: Span<'a>) -> SafeParserResult<'a, Option<Whitespace1>> {
+    opt((parse_unsafe, ws1).tuple().map(|(_, space)| space))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::{Expression, Signature, Statement};
+
+    use crate::{statement::parse_statement, utils::ParseString};
+
+    fn is_unsafe(code: &str) -> bool {
+        let statement = parse_statement.parse_string(code).unwrap();
+        let unsafety = match statement.value {
+            Statement::Function { unsafety, .. } | Statement::Trait { unsafety, .. } => unsafety,
+            other => panic!("Expected a function or a trait, got {:?}", other),
+        };
+        unsafety.is_some()
+    }
+
+    #[test]
+    fn test_unsafe_items() {
+        assert!(is_unsafe("unsafe fn f() {}"));
+        assert!(is_unsafe("#[a] pub unsafe fn f() {}"));
+        assert!(is_unsafe("pub unsafe trait X64 {}"));
+        assert!(!is_unsafe("pub fn f() {}"));
+        assert!(!is_unsafe("trait A {}"));
+        assert!(parse_statement.parse_string("unsafe pub fn f() {}").is_err());
+    }
+
+    #[test]
+    fn test_unsafe_trait_signatures() {
+        let statement = parse_statement
+            .parse_string("trait A { unsafe fn f(); fn g(); }")
+            .unwrap();
+        let Statement::Trait { signatures, .. } = statement.value else {
+            panic!("Expected a trait, got {:?}", statement.value);
+        };
+        let unsafety = signatures
+            .iter()
+            .map(|(signature, _, _)| match &signature.value {
+                Signature::Function(signature) => signature.unsafety.is_some(),
+                Signature::Property(_) => false,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(unsafety, vec![true, false]);
+    }
+
+    #[test]
+    fn test_unsafe_blocks() {
+        let statement = parse_statement
+            .parse_string("let a = unsafe { open(path) };")
+            .unwrap();
+        let Statement::Expression { expr, .. } = statement.value else {
+            panic!("Expected an expression, got {:?}", statement.value);
+        };
+        let Expression::Declaration { initializer, .. } = expr.value else {
+            panic!("Expected a declaration, got {:?}", expr.value);
+        };
+        assert!(matches!(
+            initializer.unwrap().value.value,
+            Expression::Unsafe { .. }
+        ));
+        assert!(parse_statement.parse_string("unsafe a").is_err());
+    }
+}
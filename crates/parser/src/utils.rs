@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-use fst::Location;
+use fst::{Located, Location, SourceSpan, SpaceElement, Whitespace0};
 use enumset::EnumSet;
 use parser_core::*;
 
@@ -110,6 +110,23 @@ impl<O, F: for<'a> Fn(Span<'a>) -> ParserResult<'a, O>> ParseString<O> for F {
     }
 }
 
+/// Wraps a value in a `Located` with an empty span.
+/// Useful for building expected values in tests, since equality on `Located` ignores the span.
+pub fn located<T>(value: T) -> Located<T> {
+    let location = Location {
+        line: 0,
+        column: 0,
+        index: 0,
+    };
+    Located::new(
+        value,
+        SourceSpan {
+            start: location,
+            end: location,
+        },
+    )
+}
+
 #[inline]
 pub fn token_branch<'a, T>(
     values: &'a [T],
@@ -134,3 +151,8 @@ pub fn token_branch<'a, T>(
         }
     }
 }
+
+/// Whitespace made of a single space element, for building expected values in tests
+pub fn space(text: &str) -> Whitespace0 {
+    vec![SpaceElement::Space(text.to_string())]
+}
@@ -8,11 +8,14 @@ use crate::{
 
 pub fn parse_variable_creation<'a>(input: Span<'a>) -> ParserResult<'a, VariableCreation> {
     (
-        (opt((parse_mut, ws1).tuple()), parse_ident)
+        (
+            opt((parse_mut, ws1).tuple().map(|(_, space)| space)),
+            parse_ident,
+        )
             .tuple()
             .map(|(mutable, ident)| VariableCreation::Identifier {
                 name: ident.to_string(),
-                mutable: mutable.is_some(),
+                mutable,
             }),
         parse_mutable_destructure.map(|destructure| VariableCreation::Destructure { destructure }),
     )
@@ -0,0 +1,64 @@
+use fst::Whitespace1;
+use parser_core::*;
+
+use crate::utils::{opt, ws1};
+
+/// Parses `pub` and the space after it, `None` for private items
+pub fn parse_visibility<'a>(input: Span<'a>) -> SafeParserResult<'a, Option<Whitespace1>> {
+    opt((parse_pub, ws1).tuple().map(|(_, space)| space))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::{Statement, StructField};
+
+    use crate::{statement::parse_statement, utils::ParseString};
+
+    fn is_public(code: &str) -> bool {
+        let statement = parse_statement.parse_string(code).unwrap();
+        let visibility = match statement.value {
+            Statement::Expression { visibility, .. }
+            | Statement::Function { visibility, .. }
+            | Statement::Struct { visibility, .. }
+            | Statement::Enum { visibility, .. }
+            | Statement::TypeAlias { visibility, .. }
+            | Statement::Trait { visibility, .. }
+            | Statement::Module { visibility, .. } => visibility,
+            other => panic!("Expected an item, got {:?}", other),
+        };
+        visibility.is_some()
+    }
+
+    #[test]
+    fn test_public_items() {
+        assert!(is_public("pub fn f() {}"));
+        assert!(is_public("#[cte] pub fn f() {}"));
+        assert!(is_public("pub struct A {}"));
+        assert!(is_public("pub enum A {}"));
+        assert!(is_public("pub type A = B;"));
+        assert!(is_public("pub trait A {}"));
+        assert!(is_public("pub mod a {}"));
+        assert!(is_public("pub let a = 1;"));
+        assert!(!is_public("fn f() {}"));
+        assert!(!is_public("let a = 1;"));
+    }
+
+    #[test]
+    fn test_public_fields() {
+        let statement = parse_statement.parse_string("struct A { pub a: Int, b: Int }").unwrap();
+        let Statement::Struct { fields, .. } = statement.value else {
+            panic!("Expected a struct, got {:?}", statement.value);
+        };
+        let fields: Vec<&StructField> = fields.values().collect();
+        assert!(fields[0].visibility.is_some());
+        assert_eq!(fields[0].field.name, "a");
+        assert!(fields[1].visibility.is_none());
+    }
+
+    #[test]
+    fn test_only_declarations_are_public() {
+        let error = parse_statement.parse_string("pub a + b;").unwrap_err();
+        assert_eq!(error.source_span.start.index, 4);
+        assert!(parse_statement.parse_string("pub impl A {}").is_err());
+    }
+}
@@ -3,7 +3,7 @@ use fst::{SpaceElement, Whitespace0, Whitespace1};
 use parser_core::*;
 use vec1::{Size0Error, Vec1};
 
-const WHITESPACE_KINDS: EnumSet<TokenKind> = enum_set_union!(
+pub(crate) const WHITESPACE_KINDS: EnumSet<TokenKind> = enum_set_union!(
     TokenKind::LineComment,
     TokenKind::BlockComment,
     TokenKind::Space,
@@ -1,7 +1,35 @@
 use fst::*;
 
-trait PrintFSTNode {
+/// Prints FST nodes back into source code.
+///
+/// The FST keeps every piece of whitespace and every comment, so printing a parsed
+/// file reproduces its source exactly.
+pub trait PrintFSTNode {
     fn print_into(&self, buf: &mut String);
+
+    fn print(&self) -> String {
+        let mut buf = String::new();
+        self.print_into(&mut buf);
+        buf
+    }
+}
+
+impl<T: PrintFSTNode> PrintFSTNode for Located<T> {
+    fn print_into(&self, buf: &mut String) {
+        self.value.print_into(buf);
+    }
+}
+
+impl<T: PrintFSTNode> PrintFSTNode for Box<T> {
+    fn print_into(&self, buf: &mut String) {
+        self.as_ref().print_into(buf);
+    }
+}
+
+impl PrintFSTNode for String {
+    fn print_into(&self, buf: &mut String) {
+        buf.push_str(self);
+    }
 }
 
 impl PrintFSTNode for File {
@@ -22,6 +50,14 @@ impl PrintFSTNode for Whitespace0 {
     }
 }
 
+impl PrintFSTNode for Whitespace1 {
+    fn print_into(&self, buf: &mut String) {
+        for space in self {
+            space.print_into(buf);
+        }
+    }
+}
+
 impl PrintFSTNode for SpaceElement {
     fn print_into(&self, buf: &mut String) {
         match self {
@@ -32,15 +68,121 @@ impl PrintFSTNode for SpaceElement {
     }
 }
 
+impl<T: PrintFSTNode> PrintFSTNode for Spaced<T> {
+    fn print_into(&self, buf: &mut String) {
+        self.pre_space.print_into(buf);
+        self.value.print_into(buf);
+        self.post_space.print_into(buf);
+    }
+}
+
+impl<T: PrintFSTNode> PrintFSTNode for List<T> {
+    fn print_into(&self, buf: &mut String) {
+        for (i, item) in self.items.iter().enumerate() {
+            if i > 0 {
+                buf.push(',');
+            }
+            item.print_into(buf);
+        }
+        if self.trailing_comma {
+            buf.push(',');
+        }
+        self.end_space.print_into(buf);
+    }
+}
+
+/// Prints a list surrounded by its delimiters, `{ a, b }`
+fn print_delimited<T: PrintFSTNode>(open: char, list: &List<T>, close: char, buf: &mut String) {
+    buf.push(open);
+    list.print_into(buf);
+    buf.push(close);
+}
+
+impl PrintFSTNode for Block {
+    fn print_into(&self, buf: &mut String) {
+        buf.push('{');
+        self.pre_space.print_into(buf);
+        for (statement, space) in &self.statements {
+            statement.print_into(buf);
+            space.print_into(buf);
+        }
+        buf.push('}');
+    }
+}
+
+fn print_attributes(attributes: &[AttributeList], buf: &mut String) {
+    for list in attributes {
+        buf.push('#');
+        print_delimited('[', &list.attributes, ']', buf);
+        list.post_space.print_into(buf);
+    }
+}
+
+impl PrintFSTNode for Attribute {
+    fn print_into(&self, buf: &mut String) {
+        buf.push_str(&self.name);
+        if let Some((pre_arguments, arguments)) = &self.arguments {
+            pre_arguments.print_into(buf);
+            print_delimited('(', arguments, ')', buf);
+        }
+    }
+}
+
+fn print_generics(generics: &Option<GenericParameters>, buf: &mut String) {
+    if let Some(generics) = generics {
+        generics.pre_space.print_into(buf);
+        print_delimited('<', &generics.params, '>', buf);
+    }
+}
+
+impl PrintFSTNode for GenericParameter {
+    fn print_into(&self, buf: &mut String) {
+        buf.push_str(&self.name);
+        if let Some(bound) = &self.bound {
+            bound.print_into(buf);
+        }
+    }
+}
+
+fn print_unsafety(unsafety: &Option<Whitespace1>, buf: &mut String) {
+    if let Some(space) = unsafety {
+        buf.push_str("unsafe");
+        space.print_into(buf);
+    }
+}
+
+fn print_visibility(visibility: &Option<Whitespace1>, buf: &mut String) {
+    if let Some(space) = visibility {
+        buf.push_str("pub");
+        space.print_into(buf);
+    }
+}
+
+impl PrintFSTNode for StructField {
+    fn print_into(&self, buf: &mut String) {
+        print_visibility(&self.visibility, buf);
+        self.field.print_into(buf);
+    }
+}
+
+fn print_semi(semi: &Option<Whitespace0>, buf: &mut String) {
+    if let Some(semi) = semi {
+        semi.print_into(buf);
+        buf.push(';');
+    }
+}
+
 impl PrintFSTNode for Statement {
     fn print_into(&self, buf: &mut String) {
         match self {
-            Statement::Expression { expr, semi } => {
+            Statement::Expression {
+                visibility,
+                expr,
+                semi,
+            } => {
+                print_visibility(visibility, buf);
                 expr.print_into(buf);
-                if let Some(semi) = semi {
-                    semi.print_into(buf);
-                    buf.push(';');
-                }
+                print_semi(semi, buf);
             }
             Statement::Return(label_expression) => {
                 buf.push_str("return");
@@ -54,15 +196,1128 @@ impl PrintFSTNode for Statement {
                 buf.push_str("continue");
                 spaced_label.print_into(buf);
             }
-            Statement::Function { name, closure } => {
-                buf.push_str("function");
-                name.print_into(buf);
+            Statement::Function {
+                attributes,
+                visibility,
+                unsafety,
+                after_fn,
+                name,
+                generics,
+                after_name,
+                closure,
+                semi,
+            } => {
+                print_attributes(attributes, buf);
+                print_visibility(visibility, buf);
+                print_unsafety(unsafety, buf);
+                buf.push_str("fn");
+                after_fn.print_into(buf);
+                buf.push_str(name);
+                print_generics(generics, buf);
+                after_name.print_into(buf);
                 closure.print_into(buf);
+                print_semi(semi, buf);
             }
-            Statement::Struct { name, fields } => {
+            Statement::Struct {
+                attributes,
+                visibility,
+                after_struct,
+                name,
+                generics,
+                after_name,
+                fields,
+                semi,
+            } => {
+                print_attributes(attributes, buf);
+                print_visibility(visibility, buf);
                 buf.push_str("struct");
-                name.print_into(buf);
+                after_struct.print_into(buf);
+                buf.push_str(name);
+                print_generics(generics, buf);
+                after_name.print_into(buf);
+                print_delimited('{', fields, '}', buf);
+                print_semi(semi, buf);
+            }
+            Statement::Enum {
+                attributes,
+                visibility,
+                after_enum,
+                name,
+                generics,
+                after_name,
+                options,
+                semi,
+            } => {
+                print_attributes(attributes, buf);
+                print_visibility(visibility, buf);
+                buf.push_str("enum");
+                after_enum.print_into(buf);
+                buf.push_str(name);
+                print_generics(generics, buf);
+                after_name.print_into(buf);
+                print_delimited('{', options, '}', buf);
+                print_semi(semi, buf);
+            }
+            Statement::TypeAlias {
+                attributes,
+                visibility,
+                after_type,
+                name,
+                generics,
+                pre_equals,
+                post_equals,
+                value_type,
+                semi,
+            } => {
+                print_attributes(attributes, buf);
+                print_visibility(visibility, buf);
+                buf.push_str("type");
+                after_type.print_into(buf);
+                buf.push_str(name);
+                print_generics(generics, buf);
+                pre_equals.print_into(buf);
+                buf.push('=');
+                post_equals.print_into(buf);
+                value_type.print_into(buf);
+                semi.print_into(buf);
+                buf.push(';');
+            }
+            Statement::Trait {
+                attributes,
+                visibility,
+                unsafety,
+                after_trait,
+                name,
+                generics,
+                after_name,
+                pre_space,
+                signatures,
+                semi,
+            } => {
+                print_attributes(attributes, buf);
+                print_visibility(visibility, buf);
+                print_unsafety(unsafety, buf);
+                buf.push_str("trait");
+                after_trait.print_into(buf);
+                buf.push_str(name);
+                print_generics(generics, buf);
+                after_name.print_into(buf);
+                buf.push('{');
+                pre_space.print_into(buf);
+                for (signature, signature_semi, space) in signatures {
+                    signature.print_into(buf);
+                    print_semi(signature_semi, buf);
+                    space.print_into(buf);
+                }
+                buf.push('}');
+                print_semi(semi, buf);
+            }
+            Statement::Impl {
+                attributes,
+                generics,
+                after_impl,
+                implemented,
+                target,
+                after_target,
+                statements,
+                semi,
+            } => {
+                print_attributes(attributes, buf);
+                buf.push_str("impl");
+                print_generics(generics, buf);
+                after_impl.print_into(buf);
+                if let Some((implemented, pre_for, post_for)) = implemented {
+                    implemented.print_into(buf);
+                    pre_for.print_into(buf);
+                    buf.push_str("for");
+                    post_for.print_into(buf);
+                }
+                target.print_into(buf);
+                after_target.print_into(buf);
+                statements.print_into(buf);
+                print_semi(semi, buf);
+            }
+            Statement::Import {
+                after_import,
+                path,
+                extract,
+                using,
+                semi,
+            } => {
+                buf.push_str("import");
+                after_import.print_into(buf);
+                path.print_into(buf);
+                if let Some((pre_as, post_as, extract)) = extract {
+                    pre_as.print_into(buf);
+                    buf.push_str("as");
+                    post_as.print_into(buf);
+                    extract.print_into(buf);
+                }
+                if let Some(using) = using {
+                    using.pre_using.print_into(buf);
+                    buf.push_str("using");
+                    using.post_using.print_into(buf);
+                    using.schema.print_into(buf);
+                }
+                print_semi(semi, buf);
+            }
+            Statement::Module {
+                attributes,
+                visibility,
+                after_mod,
+                name,
+                after_name,
+                statements,
+                semi,
+            } => {
+                print_attributes(attributes, buf);
+                print_visibility(visibility, buf);
+                buf.push_str("mod");
+                after_mod.print_into(buf);
+                buf.push_str(name);
+                after_name.print_into(buf);
+                statements.print_into(buf);
+                print_semi(semi, buf);
+            }
+            Statement::Env {
+                after_use_env,
+                expr,
+                semi,
+            } => {
+                buf.push_str("use_env");
+                after_use_env.print_into(buf);
+                expr.print_into(buf);
+                semi.print_into(buf);
+                buf.push(';');
+            }
+            Statement::Error(text) => buf.push_str(text),
+        }
+    }
+}
+
+impl PrintFSTNode for ImportPath {
+    fn print_into(&self, buf: &mut String) {
+        match self.relative {
+            RelativeImport::None => {}
+            RelativeImport::Current => buf.push_str("./"),
+            RelativeImport::Parent(count) => buf.push_str(&"../".repeat(count)),
+        }
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                buf.push('.');
+            }
+            segment.print_into(buf);
+        }
+    }
+}
+
+impl PrintFSTNode for ImportSegment {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            ImportSegment::Name(name) => buf.push_str(name),
+            ImportSegment::Glob => buf.push('*'),
+            ImportSegment::List(names) => print_delimited('{', names, '}', buf),
+        }
+    }
+}
+
+impl PrintFSTNode for LabelExpression {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            LabelExpression::WithExpression {
+                pre_space,
+                label,
+                expr,
+                semi_space,
+            } => {
+                pre_space.print_into(buf);
+                if let Some((label, space)) = label {
+                    buf.push_str(label);
+                    space.print_into(buf);
+                }
+                expr.print_into(buf);
+                semi_space.print_into(buf);
+                buf.push(';');
+            }
+            LabelExpression::NoExpression(spaced_label) => spaced_label.print_into(buf),
+        }
+    }
+}
+
+impl PrintFSTNode for SpacedLabel {
+    fn print_into(&self, buf: &mut String) {
+        if let Some((space, label)) = &self.label {
+            space.print_into(buf);
+            buf.push_str(label);
+        }
+        print_semi(&self.semi_space, buf);
+    }
+}
+
+/// Prints a function or a parenthesized closure, other closures are printed by `Expression`
+impl PrintFSTNode for Closure {
+    fn print_into(&self, buf: &mut String) {
+        self.closure_signature.print_into(buf);
+        self.separator.print_into(buf);
+        self.body.print_into(buf);
+    }
+}
+
+impl PrintFSTNode for BodySeparator {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            BodySeparator::Space(space) => space.print_into(buf),
+            BodySeparator::Arrow(pre_arrow, post_arrow) => {
+                pre_arrow.print_into(buf);
+                buf.push_str("->");
+                post_arrow.print_into(buf);
+            }
+            BodySeparator::Do(pre_do, post_do) => {
+                pre_do.print_into(buf);
+                buf.push_str("do");
+                post_do.print_into(buf);
+            }
+        }
+    }
+}
+
+impl PrintFSTNode for ClosureSignature {
+    fn print_into(&self, buf: &mut String) {
+        buf.push('(');
+        if let Some(receiver) = &self.receiver {
+            receiver.print_into(buf);
+            if !self.params.items.is_empty() {
+                buf.push(',');
+            }
+        }
+        self.params.print_into(buf);
+        buf.push(')');
+        if let Some(return_type) = &self.return_type {
+            return_type.print_into(buf);
+        }
+    }
+}
+
+impl PrintFSTNode for Receiver {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            Receiver::Value => {}
+            Receiver::Reference(after_ampersand) => {
+                buf.push('&');
+                after_ampersand.print_into(buf);
+            }
+            Receiver::MutableReference(after_ampersand, after_mut) => {
+                buf.push('&');
+                after_ampersand.print_into(buf);
+                buf.push_str("mut");
+                after_mut.print_into(buf);
+            }
+        }
+        buf.push_str("self");
+    }
+}
+
+impl PrintFSTNode for Parameter {
+    fn print_into(&self, buf: &mut String) {
+        self.creation.print_into(buf);
+        if let Some(value_type) = &self.value_type {
+            value_type.print_into(buf);
+        }
+    }
+}
+
+impl PrintFSTNode for TypeAnnotation {
+    fn print_into(&self, buf: &mut String) {
+        self.pre_colon.print_into(buf);
+        buf.push(':');
+        self.post_colon.print_into(buf);
+        self.value_type.print_into(buf);
+    }
+}
+
+impl PrintFSTNode for ReturnType {
+    fn print_into(&self, buf: &mut String) {
+        self.pre_arrow.print_into(buf);
+        buf.push_str("->");
+        self.post_arrow.print_into(buf);
+        self.value_type.print_into(buf);
+    }
+}
+
+impl PrintFSTNode for Initializer {
+    fn print_into(&self, buf: &mut String) {
+        self.pre_assignment.print_into(buf);
+        buf.push('=');
+        self.post_assignment.print_into(buf);
+        self.value.print_into(buf);
+    }
+}
+
+impl PrintFSTNode for Field {
+    fn print_into(&self, buf: &mut String) {
+        buf.push_str(&self.name);
+        self.pre_colon.print_into(buf);
+        buf.push(':');
+        self.post_colon.print_into(buf);
+        self.value.print_into(buf);
+    }
+}
+
+impl<T: PrintFSTNode> PrintFSTNode for Else<T> {
+    fn print_into(&self, buf: &mut String) {
+        self.pre_else.print_into(buf);
+        buf.push_str("else");
+        self.post_else.print_into(buf);
+        self.value.print_into(buf);
+    }
+}
+
+impl PrintFSTNode for IfBlock {
+    fn print_into(&self, buf: &mut String) {
+        buf.push_str("if");
+        self.after_if.print_into(buf);
+        self.condition.print_into(buf);
+        self.before_block.print_into(buf);
+        self.block.print_into(buf);
+    }
+}
+
+impl PrintFSTNode for EnumOption {
+    fn print_into(&self, buf: &mut String) {
+        let (name, value) = self;
+        buf.push_str(name);
+        match value {
+            EnumValue::Tuple { space, values } => {
+                space.print_into(buf);
+                print_delimited('(', values, ')', buf);
+            }
+            EnumValue::Struct { space, fields } => {
+                space.print_into(buf);
+                print_delimited('{', fields, '}', buf);
+            }
+            EnumValue::Unit => {}
+        }
+    }
+}
+
+impl PrintFSTNode for Signature {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            Signature::Function(signature) => signature.print_into(buf),
+            Signature::Property(signature) => signature.print_into(buf),
+        }
+    }
+}
+
+impl PrintFSTNode for FunctionSignature {
+    fn print_into(&self, buf: &mut String) {
+        print_unsafety(&self.unsafety, buf);
+        buf.push_str("fn");
+        self.after_fn.print_into(buf);
+        buf.push_str(&self.name);
+        print_generics(&self.generics, buf);
+        self.after_name.print_into(buf);
+        self.closure_signature.print_into(buf);
+    }
+}
+
+impl PrintFSTNode for PropertySignature {
+    fn print_into(&self, buf: &mut String) {
+        if let Some(space) = &self.mutable {
+            buf.push_str("mut");
+            space.print_into(buf);
+        }
+        buf.push_str(&self.name);
+        self.value_type.print_into(buf);
+    }
+}
+
+impl PrintFSTNode for VariableCreation {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            VariableCreation::Identifier { name, mutable } => {
+                if let Some(space) = mutable {
+                    buf.push_str("mut");
+                    space.print_into(buf);
+                }
+                buf.push_str(name);
+            }
+            VariableCreation::Destructure { destructure } => {
+                print_delimited('{', destructure, '}', buf)
+            }
+        }
+    }
+}
+
+impl PrintFSTNode for Expression {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            Expression::Literal { value } => value.print_into(buf),
+            Expression::InterpolatedString { quote, parts } => {
+                buf.push(*quote);
+                for part in parts {
+                    match part {
+                        InterpolatedPart::Text { text, .. } => buf.push_str(text),
+                        InterpolatedPart::Expression(expression) => {
+                            buf.push('{');
+                            expression.print_into(buf);
+                            buf.push('}');
+                        }
+                    }
+                }
+                buf.push(*quote);
+            }
+            Expression::Variable { identifier } => buf.push_str(identifier),
+            Expression::Parenthesized {
+                pre_space,
+                expr,
+                post_space,
+            } => {
+                buf.push('(');
+                pre_space.print_into(buf);
+                expr.print_into(buf);
+                post_space.print_into(buf);
+                buf.push(')');
+            }
+            Expression::SingleOperation {
+                operation,
+                space,
+                operand,
+            } => {
+                if operation.is_prefix() {
+                    operation.print_into(buf);
+                    space.print_into(buf);
+                    operand.print_into(buf);
+                } else {
+                    operand.print_into(buf);
+                    space.print_into(buf);
+                    operation.print_into(buf);
+                }
+            }
+            Expression::Operation {
+                left,
+                left_space,
+                operator,
+                right_space,
+                right,
+            } => {
+                left.print_into(buf);
+                left_space.print_into(buf);
+                operator.print_into(buf);
+                right_space.print_into(buf);
+                right.print_into(buf);
+            }
+            Expression::Array { elements } => print_delimited('[', elements, ']', buf),
+            Expression::StructLiteral {
+                name,
+                pre_brace,
+                fields,
+            } => {
+                if let Some(name) = name {
+                    name.print_into(buf);
+                }
+                pre_brace.print_into(buf);
+                print_delimited('{', fields, '}', buf);
+            }
+            Expression::Declaration {
+                after_let,
+                creation,
+                value_type,
+                initializer,
+            } => {
+                buf.push_str("let");
+                after_let.print_into(buf);
+                creation.print_into(buf);
+                if let Some(value_type) = value_type {
+                    value_type.print_into(buf);
+                }
+                if let Some(initializer) = initializer {
+                    initializer.print_into(buf);
+                }
+            }
+            Expression::Closure { closure, delimiter } => match delimiter {
+                ParameterDelimiter::Parentheses => closure.print_into(buf),
+                ParameterDelimiter::Pipes | ParameterDelimiter::None => {
+                    let signature = &closure.closure_signature;
+                    if *delimiter == ParameterDelimiter::Pipes {
+                        buf.push('|');
+                        signature.params.print_into(buf);
+                        buf.push('|');
+                    } else {
+                        signature.params.print_into(buf);
+                    }
+                    if let Some(return_type) = &signature.return_type {
+                        return_type.print_into(buf);
+                    }
+                    closure.separator.print_into(buf);
+                    closure.body.print_into(buf);
+                }
+            },
+            Expression::Block { environment, block } => {
+                if let Some(environment) = environment {
+                    environment.print_into(buf);
+                    buf.push_str(": ");
+                }
+                block.print_into(buf);
+            }
+            Expression::Unsafe {
+                after_unsafe,
+                block,
+            } => {
+                buf.push_str("unsafe");
+                after_unsafe.print_into(buf);
+                block.print_into(buf);
+            }
+            Expression::Const { after_const, block } => {
+                buf.push_str("const");
+                after_const.print_into(buf);
+                block.print_into(buf);
+            }
+            Expression::Foreign { source } => buf.push_str(source),
+            Expression::Match {
+                after_match,
+                scrutinee,
+                before_arms,
+                arms,
+            } => {
+                buf.push_str("match");
+                after_match.print_into(buf);
+                scrutinee.print_into(buf);
+                before_arms.print_into(buf);
+                buf.push('{');
+                for (i, arm) in arms.items.iter().enumerate() {
+                    if i > 0 && !arms.items[i - 1].value.value.omits_comma {
+                        buf.push(',');
+                    }
+                    arm.print_into(buf);
+                }
+                if arms.trailing_comma {
+                    buf.push(',');
+                }
+                arms.end_space.print_into(buf);
+                buf.push('}');
+            }
+            Expression::If {
+                if_block,
+                else_if_blocks,
+                else_block,
+            } => {
+                if_block.print_into(buf);
+                for else_if_block in else_if_blocks {
+                    else_if_block.print_into(buf);
+                }
+                if let Some(else_block) = else_block {
+                    else_block.print_into(buf);
+                }
+            }
+            Expression::While {
+                label,
+                after_while,
+                condition,
+                before_body,
+                do_body,
+                body,
+                else_block,
+            } => {
+                print_label(label, buf);
+                buf.push_str("while");
+                after_while.print_into(buf);
+                condition.print_into(buf);
+                before_body.print_into(buf);
+                print_loop_body(body, do_body, buf);
+                if let Some(else_block) = else_block {
+                    else_block.print_into(buf);
+                }
+            }
+            Expression::Loop {
+                label,
+                after_loop,
+                body,
+            } => {
+                print_label(label, buf);
+                buf.push_str("loop");
+                after_loop.print_into(buf);
+                body.print_into(buf);
+            }
+            Expression::For {
+                label,
+                after_for,
+                destructure,
+                before_in,
+                after_in,
+                iterator,
+                before_body,
+                do_body,
+                body,
+                else_block,
+            } => {
+                print_label(label, buf);
+                buf.push_str("for");
+                after_for.print_into(buf);
+                print_delimited('{', destructure, '}', buf);
+                before_in.print_into(buf);
+                buf.push_str("in");
+                after_in.print_into(buf);
+                iterator.print_into(buf);
+                before_body.print_into(buf);
+                print_loop_body(body, do_body, buf);
+                if let Some(else_block) = else_block {
+                    else_block.print_into(buf);
+                }
             }
         }
     }
 }
+
+fn print_label(label: &Option<(String, Whitespace0)>, buf: &mut String) {
+    if let Some((label, space)) = label {
+        buf.push_str(label);
+        space.print_into(buf);
+    }
+}
+
+fn print_loop_body(body: &Located<Expression>, do_body: &Option<Whitespace0>, buf: &mut String) {
+    if let Some(space) = do_body {
+        buf.push_str("do");
+        space.print_into(buf);
+    }
+    body.print_into(buf);
+}
+
+impl PrintFSTNode for Literal {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            Literal::Number(number) => buf.push_str(&number.text),
+            Literal::String(string) | Literal::RawString(string) => buf.push_str(&string.text),
+            Literal::Boolean(boolean) => buf.push_str(if *boolean { "true" } else { "false" }),
+        }
+    }
+}
+
+impl PrintFSTNode for UnaryOperation {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            UnaryOperation::Not => buf.push('!'),
+            UnaryOperation::ErrorUnwrap => buf.push('?'),
+            UnaryOperation::Inline => buf.push('!'),
+            UnaryOperation::Spread => buf.push('*'),
+            UnaryOperation::Negate => buf.push('-'),
+            UnaryOperation::Positive => buf.push('+'),
+            UnaryOperation::Reference { mutable } => {
+                buf.push_str(if *mutable { "&mut" } else { "&" })
+            }
+            UnaryOperation::Dereference => buf.push('*'),
+            UnaryOperation::Call { arguments } => print_delimited('(', arguments, ')', buf),
+            UnaryOperation::GenericArguments { arguments } => {
+                print_delimited('<', arguments, '>', buf)
+            }
+            UnaryOperation::Get {
+                pre_space,
+                property,
+                post_space,
+            } => {
+                buf.push('[');
+                pre_space.print_into(buf);
+                property.print_into(buf);
+                post_space.print_into(buf);
+                buf.push(']');
+            }
+            UnaryOperation::ArrayOf { space } => {
+                buf.push('[');
+                space.print_into(buf);
+                buf.push(']');
+            }
+            UnaryOperation::Extract { dot_space, extract } => {
+                buf.push('.');
+                dot_space.print_into(buf);
+                extract.print_into(buf);
+            }
+        }
+    }
+}
+
+impl PrintFSTNode for StructLiteralField {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            StructLiteralField::Field(field) => field.print_into(buf),
+            StructLiteralField::Shorthand(name) => buf.push_str(&name.value),
+            StructLiteralField::Spread { after_dots, base } => {
+                buf.push_str("..");
+                after_dots.print_into(buf);
+                base.print_into(buf);
+            }
+        }
+    }
+}
+
+impl PrintFSTNode for MatchArm {
+    fn print_into(&self, buf: &mut String) {
+        self.pattern.print_into(buf);
+        if let Some(guard) = &self.guard {
+            guard.pre_if.print_into(buf);
+            buf.push_str("if");
+            guard.after_if.print_into(buf);
+            guard.condition.print_into(buf);
+        }
+        self.pre_arrow.print_into(buf);
+        buf.push_str("=>");
+        self.post_arrow.print_into(buf);
+        self.body.print_into(buf);
+    }
+}
+
+impl PrintFSTNode for Pattern {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            Pattern::Wildcard => buf.push('_'),
+            Pattern::Binding { mutable, name } => {
+                if let Some(space) = mutable {
+                    buf.push_str("mut");
+                    space.print_into(buf);
+                }
+                buf.push_str(name);
+            }
+            Pattern::Literal(literal) => literal.print_into(buf),
+            Pattern::Range {
+                start,
+                pre_dots,
+                post_dots,
+                end,
+            } => {
+                start.print_into(buf);
+                pre_dots.print_into(buf);
+                buf.push_str("..");
+                post_dots.print_into(buf);
+                end.print_into(buf);
+            }
+            Pattern::Variant { path, values } => {
+                for (i, name) in path.iter().enumerate() {
+                    if i > 0 {
+                        buf.push('.');
+                    }
+                    name.print_into(buf);
+                }
+                match values {
+                    VariantPattern::Tuple { space, values } => {
+                        space.print_into(buf);
+                        print_delimited('(', values, ')', buf);
+                    }
+                    VariantPattern::Struct { space, fields } => {
+                        space.print_into(buf);
+                        print_delimited('{', fields, '}', buf);
+                    }
+                    VariantPattern::Unit => {}
+                }
+            }
+            Pattern::Alternatives(alternatives) => {
+                for (i, alternative) in alternatives.iter().enumerate() {
+                    if i > 0 {
+                        buf.push('|');
+                    }
+                    alternative.print_into(buf);
+                }
+            }
+        }
+    }
+}
+
+impl PrintFSTNode for Operator {
+    fn print_into(&self, buf: &mut String) {
+        buf.push_str(match self {
+            Operator::Assignment => "=",
+            Operator::Range => "..",
+            Operator::And => "&&",
+            Operator::Or => "||",
+            Operator::Equals => "==",
+            Operator::NotEquals => "!=",
+            Operator::LessThan => "<",
+            Operator::LessThanOrEquals => "<=",
+            Operator::GreaterThan => ">",
+            Operator::GreaterThanOrEquals => ">=",
+            Operator::Add => "+",
+            Operator::Subtract => "-",
+            Operator::Multiply => "*",
+            Operator::WrappingAdd => "+%",
+            Operator::WrappingSubtract => "-%",
+            Operator::WrappingMultiply => "*%",
+            Operator::Divide => "/",
+            Operator::Modulo => "%",
+            Operator::Power => "**",
+            Operator::Pipe => "|>",
+            Operator::Union => "|",
+            Operator::Intersection => "&",
+            Operator::ExclusiveOr => "^",
+        })
+    }
+}
+
+impl<T: PrintFSTNode> PrintFSTNode for Alias<T> {
+    fn print_into(&self, buf: &mut String) {
+        self.pre_as.print_into(buf);
+        buf.push_str("as");
+        self.post_as.print_into(buf);
+        self.alias.print_into(buf);
+    }
+}
+
+impl<T: PrintFSTNode> PrintFSTNode for SubExtract<T> {
+    fn print_into(&self, buf: &mut String) {
+        self.pre_dot.print_into(buf);
+        buf.push('.');
+        self.post_dot.print_into(buf);
+        self.extract.print_into(buf);
+    }
+}
+
+impl PrintFSTNode for MutableDestructureProperty {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            MutableDestructureProperty::AliasedSubProperties {
+                property_name,
+                extract,
+                alias,
+            } => {
+                buf.push_str(property_name);
+                extract.print_into(buf);
+                alias.print_into(buf);
+            }
+            MutableDestructureProperty::Property {
+                property_name,
+                alias,
+            } => {
+                buf.push_str(property_name);
+                if let Some(alias) = alias {
+                    alias.print_into(buf);
+                }
+            }
+            MutableDestructureProperty::UnaliasedSubProperties {
+                property_name,
+                extract,
+            } => {
+                buf.push_str(property_name);
+                extract.print_into(buf);
+            }
+            MutableDestructureProperty::MutablePropertyChain { property_chain } => {
+                buf.push_str("mut");
+                for (i, property) in property_chain.iter().enumerate() {
+                    if i > 0 {
+                        buf.push('.');
+                    }
+                    property.print_into(buf);
+                }
+            }
+        }
+    }
+}
+
+impl PrintFSTNode for MutableAlias {
+    fn print_into(&self, buf: &mut String) {
+        if let Some(space) = &self.mutable {
+            buf.push_str("mut");
+            space.print_into(buf);
+        }
+        buf.push_str(&self.alias);
+    }
+}
+
+impl PrintFSTNode for MutableExtract {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            MutableExtract::Destructured(destructure) => print_delimited('{', destructure, '}', buf),
+            MutableExtract::DirectProperty(property) => property.print_into(buf),
+        }
+    }
+}
+
+impl PrintFSTNode for ImmutableDestructureProperty {
+    fn print_into(&self, buf: &mut String) {
+        buf.push_str(&self.property_name);
+        if let Some(extract) = &self.extract {
+            extract.print_into(buf);
+        }
+        if let Some(alias) = &self.alias {
+            alias.print_into(buf);
+        }
+    }
+}
+
+impl PrintFSTNode for ImmutableExtract {
+    fn print_into(&self, buf: &mut String) {
+        match self {
+            ImmutableExtract::Destructured(destructure) => print_delimited('{', destructure, '}', buf),
+            ImmutableExtract::DirectProperty(property) => property.print_into(buf),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{
+        core::{create_span, tokenize},
+        parse_file_recovering, simple_parse,
+    };
+
+    use super::*;
+
+    fn assert_round_trip(code: &str) {
+        let file = simple_parse(code).unwrap_or_else(|error| panic!("{}", error));
+        assert_eq!(file.print(), code);
+    }
+
+    #[test]
+    fn test_top_level_space_and_comments() {
+        assert_round_trip("// leading comment\n\nlet a = 5; // trailing\nlet mut b: Int = a + 1;\n");
+    }
+
+    #[test]
+    fn test_operators() {
+        assert_round_trip("a = b || c && d;");
+        assert_round_trip("a == b; a != b; a < b; a <= b; a > b; a >= b;");
+        assert_round_trip("a .. b; a | b & c ^ d; a |> b;");
+        assert_round_trip("a + b - c +% d -% e * f / g % h *% i ** j;");
+        assert_round_trip("(1 + 2) * (3);");
+    }
+
+    #[test]
+    fn test_unary_operations() {
+        assert_round_trip("!a; -b; +c; *d; &e; &mut f; g?; h!;");
+        assert_round_trip("i(1, 2); j(); k(1,); l { a: 1, b: 2 }; m[0];");
+        assert_round_trip("n.o; p.{ q, r as s, t.{ u } };");
+    }
+
+    #[test]
+    fn test_literals_and_arrays() {
+        assert_round_trip("\"text\"; 'c'; 0x1F; 1.5e3; [1, 2,]; []; [[a], b];");
+        assert_round_trip("\"a\\tb\"; r\"c:\\d\"; r##\"say \"#hi\"##;");
+        assert_round_trip("\"a{ b /* c */ }\\{{d(\"{e}\")}\"; '{f}';");
+    }
+
+    #[test]
+    fn test_declarations_and_closures() {
+        assert_round_trip("let { a, mut b, c as mut d, e.{ f } as g } = h;");
+        assert_round_trip("let f = (a, b) -> a + b; let g = (a: Int) -> Int do a; let h = () -> {};");
+        assert_round_trip("let f = |a, b| a + b; let g = | a: Int , |->Int { a }; let h = ||{};");
+    }
+
+    #[test]
+    fn test_control_flow() {
+        assert_round_trip("if a { b } else if c { d; } else { e }");
+        assert_round_trip("while a { break; } else { continue; }");
+        assert_round_trip("while a do b; loop { break; } for { a, b } in c do d;");
+        assert_round_trip("for { a } in b { continue; return d; return; }");
+    }
+
+    #[test]
+    fn test_items() {
+        assert_round_trip("fn f(a, b: Int) -> Int { return a + b; }\nfn g() {};");
+        assert_round_trip("#[static, cte]\n#[ requires_env (Console ,) ] fn f() {}\n#[a] mod b {}");
+        assert_round_trip("pub  let a = 1; #[a] pub fn f() {} pub struct A { pub a: Int, b: Int }");
+        assert_round_trip("struct A { a: Int, b: Int, }\nstruct B {};");
+        assert_round_trip("enum C { D, E(Int, Int), F { a: Int } }");
+        assert_round_trip("trait G { fn h(a) -> Int; i: Int mut j: Int }");
+        assert_round_trip("impl G for A { fn h(a) -> Int { 1 } }\nimpl A {}");
+        assert_round_trip("impl<T>  Coerce< List<T> >  for  Range<T>{}\nfn f() -> Int {}");
+    }
+
+    #[test]
+    fn test_unsafe() {
+        assert_round_trip("pub  unsafe  trait X64 {} unsafe fn f() { unsafe{ g() } }");
+        assert_round_trip("trait A { unsafe fn b(); } let c = unsafe { d };");
+    }
+
+    #[test]
+    fn test_struct_literals() {
+        assert_round_trip("let p = Point { x: 1 , y }; let q = geometry.Point{ ..p, };");
+        assert_round_trip("if a == b { c } else { Unit {} }; box {\n    subs: [text { text: a }],\n}");
+    }
+
+    #[test]
+    fn test_const() {
+        assert_round_trip("const  { assert(1 < 2) };\nlet a = const{ 1 + 2 };");
+    }
+
+    #[test]
+    fn test_type_aliases() {
+        assert_round_trip("pub  type Pair< T > =Tuple<T, T> ;\ntype A = B | C;");
+    }
+
+    #[test]
+    fn test_match() {
+        assert_round_trip("match  a{ Some( b )if b>0=> b , Color .Red|_ =>{ 0 }, }");
+        assert_round_trip("let a = match b {\n  1 .. 10 | 20 => c,\n  mut d => d,\n};");
+        assert_round_trip("match s { Shape.Circle { radius as mut r } => r }");
+        assert_round_trip("match a { -1 => { b } 0..-2=>if c { d }\n  _ => e }");
+    }
+
+    #[test]
+    fn test_receivers() {
+        assert_round_trip("impl A { fn a(self) {} fn b(&self, c) {} fn d( & mut  self , ) {} }");
+        assert_round_trip("trait E { fn f(&mut self) -> Future<Option<T>>; fn g(&self,a,); }");
+        assert_round_trip("mod m { let x = 1; };\nuse_env e;");
+        assert_round_trip("import a.b as { c, mut d };\nimport e;");
+    }
+
+    #[test]
+    fn test_imports() {
+        assert_round_trip("import ./* as docs  using doc;\nimport ../../lib .docref ;");
+        assert_round_trip("import std.lang.rust.{ rust_std,rs, } ;import std. *;");
+    }
+
+    #[test]
+    fn test_generics() {
+        assert_round_trip("fn f<T: Clone, U>(a: T) -> Option<U> { a }\nstruct A < T > { a: List<T,> }");
+        assert_round_trip("enum B<T> { C(T) } trait D<T: E + F> { fn g<H>(h: H) -> Future<Option<T>>; }");
+        assert_round_trip("impl<T> A {} let a: Map< K , V > = b < c; let f = (a: T) -> List<T> do a;");
+    }
+
+    #[test]
+    fn test_irregular_spacing_and_comments() {
+        assert_round_trip("let/* a */mut  a :Int=( 1+\n2 ) ;");
+        assert_round_trip("f( a ,b , ) ; g {a:1 , /* b */ b :2}; h [ 0 ] . { i ,j as  k } ?;");
+        assert_round_trip("let f = ( a : Int ,b )->  Int  do a; let g = ( )->{ };");
+        assert_round_trip("if a{b}else  if c {d}else{ e }");
+        assert_round_trip("while a  do b else c; for {mut a . b} in c{ d; } ");
+        assert_round_trip("fn  f ( a ) ->Int{ a } ;\nstruct A{ a :Int , } enum B { C ( Int ), D{ } }");
+        assert_round_trip("trait T {\n    fn f( ) ;\n    mut  a :Int\n}\nimpl T  for  A { }");
+        assert_round_trip("/* block /* nested */ comment */ mod m{ } import a as  { b } ;use_env e ;");
+        assert_round_trip("let a = rs  { print!(\"{}\", '}') } ;");
+        assert_round_trip("let a = { b : c [ ], d }; let e: F[] = g;");
+    }
+
+    #[test]
+    fn test_repository_files() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let mut paths = Vec::new();
+        for directory in ["example_files", "std", "docs"] {
+            collect_quip_files(&root.join(directory), &mut paths);
+        }
+        assert!(!paths.is_empty());
+        for path in paths {
+            // files with syntax errors still print back to their source
+            let code = std::fs::read_to_string(&path).unwrap();
+            let tokens = tokenize(&code);
+            let (file, _) = parse_file_recovering(create_span(&tokens));
+            assert_eq!(file.print(), code, "{}", path.display());
+        }
+    }
+
+    fn collect_quip_files(directory: &std::path::Path, paths: &mut Vec<std::path::PathBuf>) {
+        for entry in std::fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                collect_quip_files(&path, paths);
+            } else if path.extension().is_some_and(|extension| extension == "qp") {
+                paths.push(path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_recovered_errors() {
+        let code = "let a = ;\nfn b() {\n    let c = (;\n}\nlet d = 1;\n";
+        let tokens = tokenize(code);
+        let (file, errors) = parse_file_recovering(create_span(&tokens));
+        assert_eq!(errors.len(), 2);
+        assert_eq!(file.print(), code);
+    }
+}
@@ -52,6 +52,7 @@ pub fn fuzz_possibilities(mut on_found: impl FnMut(&Vec<LocatedToken<'static>>))
                             queue.push_back((new_tokens, expected));
                         }
                     }
+                    parser::core::ParserError::InvalidLiteral(_) => {}
                 },
             }
         }
@@ -64,6 +65,7 @@ fn get_sample_token(kind: TokenKind) -> Token<'static> {
         TokenKind::Number => Token::Number("42"),
         TokenKind::String => Token::String("example string"),
         TokenKind::RawString => Token::RawString("r#example raw string#"),
+        TokenKind::ForeignBlock => Token::ForeignBlock("rs { example() }"),
         TokenKind::Label => Token::Label("'example_label"),
         TokenKind::Boolean => Token::Boolean(true),
         TokenKind::Range => Token::Range,
@@ -99,8 +101,12 @@ fn get_sample_token(kind: TokenKind) -> Token<'static> {
         TokenKind::RightBracket => Token::RightBracket,
         TokenKind::LeftBrace => Token::LeftBrace,
         TokenKind::RightBrace => Token::RightBrace,
+        TokenKind::Hash => Token::Hash,
+        TokenKind::CurrentDirectory => Token::CurrentDirectory,
+        TokenKind::ParentDirectory => Token::ParentDirectory,
         TokenKind::Assignment => Token::Assignment,
         TokenKind::Arrow => Token::Arrow,
+        TokenKind::FatArrow => Token::FatArrow,
         TokenKind::VerticalBar => Token::VerticalBar,
         TokenKind::Ampersand => Token::Ampersand,
         TokenKind::Let => Token::Let,
@@ -120,9 +126,14 @@ fn get_sample_token(kind: TokenKind) -> Token<'static> {
         TokenKind::Mod => Token::Mod,
         TokenKind::Type => Token::Type,
         TokenKind::Fn => Token::Fn,
+        TokenKind::Pub => Token::Pub,
+        TokenKind::Unsafe => Token::Unsafe,
+        TokenKind::Const => Token::Const,
+        TokenKind::Match => Token::Match,
         TokenKind::Mut => Token::Mut,
         TokenKind::Import => Token::Import,
         TokenKind::As => Token::As,
+        TokenKind::Using => Token::Using,
         TokenKind::Do => Token::Do,
         TokenKind::UseEnv => Token::UseEnv,
         TokenKind::LineComment => Token::LineComment("// Example line comment"),
@@ -156,7 +167,11 @@ pub fn interesting_tokens() -> EnumSet<TokenKind> {
     set.insert(TokenKind::RightBracket);
     set.insert(TokenKind::LeftBrace);
     set.insert(TokenKind::RightBrace);
+    set.insert(TokenKind::Hash);
+    set.insert(TokenKind::CurrentDirectory);
+    set.insert(TokenKind::ParentDirectory);
     set.insert(TokenKind::Arrow);
+    set.insert(TokenKind::FatArrow);
     set.insert(TokenKind::Assignment);
     set.insert(TokenKind::Let);
     set.insert(TokenKind::If);
@@ -175,9 +190,14 @@ pub fn interesting_tokens() -> EnumSet<TokenKind> {
     set.insert(TokenKind::Mod);
     set.insert(TokenKind::Type);
     set.insert(TokenKind::Fn);
+    set.insert(TokenKind::Pub);
+    set.insert(TokenKind::Unsafe);
+    set.insert(TokenKind::Const);
+    set.insert(TokenKind::Match);
     set.insert(TokenKind::Mut);
     set.insert(TokenKind::Import);
     set.insert(TokenKind::As);
+    set.insert(TokenKind::Using);
     set.insert(TokenKind::Do);
     set.insert(TokenKind::UseEnv);
     set.insert(TokenKind::Space);
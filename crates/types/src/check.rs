@@ -0,0 +1,777 @@
+use std::collections::HashMap;
+
+use analysis::{
+    visit::{walk_expression, walk_file, walk_statement, Visitor},
+    Definition, Resolution, TypeAliases,
+};
+use fst::*;
+
+use crate::{types::Type, LocatedTypeError, TypeError};
+
+/// Infers the types of the variables of a file and reports the assignments of values that a
+/// variable does not accept.
+///
+/// A variable declared without a type or with `_` gets the type of its value and keeps it, a
+/// parameter without a type gets the type it is first used as. A variable declared as `any`
+/// accepts every value, and a function returning `any` returns the type of its body.
+pub fn check_types(file: &File, resolution: &Resolution) -> Vec<LocatedTypeError> {
+    let mut items = Items::default();
+    walk_file(&mut items, file);
+    let mut checker = Checker {
+        resolution,
+        aliases: TypeAliases::collect(file),
+        items: items.items,
+        variables: HashMap::new(),
+        functions: HashMap::new(),
+        self_type: None,
+        expanding: Vec::new(),
+        errors: Vec::new(),
+    };
+    walk_file(&mut checker, file);
+    checker.errors
+}
+
+/// Collects the functions, structs, enums and type aliases by the location of their statement
+#[derive(Default)]
+struct Items<'a> {
+    items: HashMap<SourceSpan, &'a Statement>,
+}
+
+impl<'a> Visitor<'a> for Items<'a> {
+    fn visit_statement(&mut self, statement: &'a Located<Statement>) {
+        if let Statement::Function { .. }
+        | Statement::Struct { .. }
+        | Statement::Enum { .. }
+        | Statement::TypeAlias { .. } = &statement.value
+        {
+            self.items.insert(statement.source_span, &statement.value);
+        }
+        walk_statement(self, statement);
+    }
+}
+
+struct Checker<'a, 'r> {
+    resolution: &'r Resolution,
+    aliases: TypeAliases<'a>,
+    items: HashMap<SourceSpan, &'a Statement>,
+    // the types of the variables by the location of their definition
+    variables: HashMap<SourceSpan, Type>,
+    // the return types of the functions that are checked already by the location of the function
+    functions: HashMap<SourceSpan, Type>,
+    // the type of `self` in the current `impl` block
+    self_type: Option<Type>,
+    // the type aliases that are being expanded, so that a cycle ends
+    expanding: Vec<&'a str>,
+    errors: Vec<LocatedTypeError>,
+}
+
+/// The type of an annotation, `None` when the type is left to be inferred as in `let a = 5;` or
+/// `let a: _ = 5;`
+fn annotated(annotation: &Option<TypeAnnotation>) -> Option<&Located<Expression>> {
+    let value_type = &annotation.as_ref()?.value_type;
+    match &value_type.value {
+        Expression::Variable { identifier } if identifier == "_" => None,
+        _ => Some(value_type),
+    }
+}
+
+fn literal_type(literal: &Literal) -> Type {
+    match literal {
+        Literal::Number(number) => match (&number.value, number.suffix) {
+            (Number::Float(_), _) => Type::Float,
+            (_, Some(suffix)) if suffix.is_float() => Type::Float,
+            _ => Type::Int,
+        },
+        Literal::String(_) | Literal::RawString(_) => Type::String,
+        Literal::Boolean(_) => Type::Bool,
+    }
+}
+
+fn builtin_type(name: &str) -> Type {
+    match name {
+        "int" => Type::Int,
+        "float" => Type::Float,
+        "string" => Type::String,
+        "bool" => Type::Bool,
+        "any" => Type::Any,
+        name => match NumberSuffix::from_name(name) {
+            Some(suffix) if suffix.is_float() => Type::Float,
+            Some(_) => Type::Int,
+            None => Type::Unknown,
+        },
+    }
+}
+
+impl<'a> Checker<'a, '_> {
+    /// The type a type expression such as `int | string` stands for
+    fn annotation(&mut self, expression: &'a Located<Expression>) -> Type {
+        match &expression.value {
+            Expression::Variable { identifier } => match self.resolution.definition(expression) {
+                Some(Definition::Builtin) => builtin_type(identifier),
+                Some(Definition::Item(source_span)) => {
+                    match self.items.get(&source_span).copied() {
+                        Some(Statement::Struct { name, .. } | Statement::Enum { name, .. }) => {
+                            Type::Named(name.clone())
+                        }
+                        Some(Statement::TypeAlias { name, .. }) => self.alias(name),
+                        _ => Type::Unknown,
+                    }
+                }
+                _ => Type::Unknown,
+            },
+            Expression::Operation {
+                left,
+                operator: Operator::Union,
+                right,
+                ..
+            } => {
+                let left = self.annotation(left);
+                left.union(self.annotation(right))
+            }
+            Expression::Parenthesized { expr, .. } => self.annotation(expr),
+            Expression::SingleOperation {
+                operation: UnaryOperation::ArrayOf { .. },
+                operand,
+                ..
+            } => Type::Array(Box::new(self.annotation(operand))),
+            _ => Type::Unknown,
+        }
+    }
+
+    fn alias(&mut self, name: &'a str) -> Type {
+        let Some(value_type) = self.aliases.expand(name) else {
+            return Type::Unknown;
+        };
+        if self.expanding.contains(&name) {
+            return Type::Unknown;
+        }
+        self.expanding.push(name);
+        let aliased = self.annotation(value_type);
+        self.expanding.pop();
+        aliased
+    }
+
+    fn variable_type(&self, variable: &Located<Expression>) -> Type {
+        match self.resolution.definition(variable) {
+            Some(Definition::Local(source_span)) => self
+                .variables
+                .get(&source_span)
+                .cloned()
+                .unwrap_or(Type::Unknown),
+            _ => Type::Unknown,
+        }
+    }
+
+    /// The variable an expression is, if its type is still to be inferred
+    fn uninferred_variable(&self, expression: &Located<Expression>) -> Option<SourceSpan> {
+        match &expression.value {
+            Expression::Variable { .. } => self.uninferred(expression.source_span),
+            _ => None,
+        }
+    }
+
+    /// The definition of the variable used at `source_span`, if its type is still to be inferred
+    fn uninferred(&self, source_span: SourceSpan) -> Option<SourceSpan> {
+        match self.resolution.definitions.get(&source_span) {
+            Some(Definition::Local(definition)) => self
+                .variables
+                .get(definition)
+                .is_some_and(|variable| *variable == Type::Unknown)
+                .then_some(*definition),
+            _ => None,
+        }
+    }
+
+    /// Checks that `value`, the type of `expression`, can be stored where `target` is expected,
+    /// a variable without a type yet gets the type it is expected to have
+    fn assign(
+        &mut self,
+        expression: &Located<Expression>,
+        value: &Type,
+        target: &Type,
+        source_span: SourceSpan,
+    ) {
+        let variable = self.uninferred_variable(expression);
+        self.store(variable, value, target, source_span);
+    }
+
+    /// Checks that `value` can be stored where `target` is expected, or gives `variable` the
+    /// type `target` when it has no type yet
+    fn store(
+        &mut self,
+        variable: Option<SourceSpan>,
+        value: &Type,
+        target: &Type,
+        source_span: SourceSpan,
+    ) {
+        if let Some(variable) = variable {
+            self.variables.insert(variable, target.clone());
+        } else if !value.is_assignable_to(target) {
+            self.errors.push(Located::new(
+                TypeError::Assignment {
+                    value: value.clone(),
+                    target: target.clone(),
+                },
+                source_span,
+            ));
+        }
+    }
+
+    /// `target = value`, a variable or an element of an array
+    fn assignment(
+        &mut self,
+        target: &'a Located<Expression>,
+        value: &'a Located<Expression>,
+        source_span: SourceSpan,
+    ) {
+        let value_type = self.expression(value);
+        let target_type = match &target.value {
+            Expression::Variable { .. } => {
+                if let Some(variable) = self.uninferred_variable(target) {
+                    self.variables.insert(variable, value_type);
+                    return;
+                }
+                self.variable_type(target)
+            }
+            Expression::SingleOperation {
+                operation: UnaryOperation::Get { property, .. },
+                operand,
+                ..
+            } => {
+                self.expression(property);
+                match self.expression(operand) {
+                    Type::Array(element) => *element,
+                    _ => Type::Unknown,
+                }
+            }
+            _ => self.expression(target),
+        };
+        self.assign(value, &value_type, &target_type, source_span);
+    }
+
+    fn declaration(
+        &mut self,
+        creation: &'a Located<VariableCreation>,
+        value_type: &'a Option<TypeAnnotation>,
+        initializer: &'a Option<Initializer>,
+    ) {
+        let value = match initializer {
+            Some(initializer) => self.expression(&initializer.value),
+            None => Type::Unknown,
+        };
+        let variable = match annotated(value_type) {
+            None => value,
+            Some(annotation) => {
+                let declared = self.annotation(annotation);
+                if let Some(initializer) = initializer {
+                    let source_span = initializer.value.source_span;
+                    self.assign(&initializer.value, &value, &declared, source_span);
+                }
+                declared
+            }
+        };
+        if let VariableCreation::Identifier { .. } = &creation.value {
+            self.variables.insert(creation.source_span, variable);
+        }
+    }
+
+    /// Declares the parameters and checks the body, the result is the type of the body
+    fn closure(&mut self, closure: &'a Closure, source_span: SourceSpan) -> Type {
+        let signature = &closure.closure_signature;
+        if signature.receiver.is_some() {
+            let self_type = self.self_type.clone().unwrap_or(Type::Unknown);
+            self.variables.insert(source_span, self_type);
+        }
+        for param in signature.params.values() {
+            let declared = match annotated(&param.value_type) {
+                Some(annotation) => self.annotation(annotation),
+                None => Type::Unknown,
+            };
+            if let VariableCreation::Identifier { .. } = &param.creation.value {
+                self.variables.insert(param.creation.source_span, declared);
+            }
+        }
+        self.expression(&closure.body)
+    }
+
+    fn function(&mut self, closure: &'a Located<Closure>, source_span: SourceSpan) {
+        let body = self.closure(&closure.value, closure.source_span);
+        // a function returning `any` or without a return type returns the type of its body
+        let returned = match &closure.value.closure_signature.return_type {
+            Some(return_type) => match self.annotation(&return_type.value_type) {
+                Type::Any | Type::Unknown => body,
+                declared => {
+                    let body_expression = &closure.value.body;
+                    self.assign(
+                        body_expression,
+                        &body,
+                        &declared,
+                        body_expression.source_span,
+                    );
+                    declared
+                }
+            },
+            None => body,
+        };
+        self.functions.insert(source_span, returned);
+    }
+
+    /// Checks the arguments of a call of a function of the file against the types of its
+    /// parameters, which are inferred from the body of the function when they have no type
+    fn call(
+        &mut self,
+        function: &'a Located<Expression>,
+        arguments: &'a List<Located<Expression>>,
+    ) {
+        let closure = match self.resolution.definition(function) {
+            Some(Definition::Item(source_span)) => match self.items.get(&source_span).copied() {
+                Some(Statement::Function { closure, .. }) => Some(&closure.value),
+                _ => None,
+            },
+            _ => None,
+        };
+        let params = closure.map(|closure| closure.closure_signature.params.values());
+        let mut params = params.into_iter().flatten();
+        for argument in arguments.values() {
+            let value = self.expression(argument);
+            let target = match params.next().map(|param| &param.creation) {
+                Some(
+                    creation @ Located {
+                        value: VariableCreation::Identifier { .. },
+                        ..
+                    },
+                ) => self
+                    .variables
+                    .get(&creation.source_span)
+                    .cloned()
+                    .unwrap_or(Type::Unknown),
+                _ => Type::Unknown,
+            };
+            self.assign(argument, &value, &target, argument.source_span);
+        }
+    }
+
+    fn struct_literal(
+        &mut self,
+        name: Option<&'a Located<Expression>>,
+        fields: &'a List<StructLiteralField>,
+    ) -> Type {
+        // anonymous structs have no declared fields to check against
+        let struct_type = name.map_or(Type::Unknown, |name| self.annotation(name));
+        let declared_fields = match name.and_then(|name| self.resolution.definition(name)) {
+            Some(Definition::Item(source_span)) => match self.items.get(&source_span).copied() {
+                Some(Statement::Struct { fields, .. }) => Some(fields),
+                _ => None,
+            },
+            _ => None,
+        };
+        let declared_field = |name: &str| {
+            declared_fields.and_then(|declared| {
+                declared
+                    .values()
+                    .find(|declared| declared.field.name == name)
+            })
+        };
+        for field in fields.values() {
+            match field {
+                StructLiteralField::Field(field) => {
+                    let value = self.expression(&field.value);
+                    if let Some(declared) = declared_field(&field.name) {
+                        let target = self.annotation(&declared.field.value);
+                        self.assign(&field.value, &value, &target, field.value.source_span);
+                    }
+                }
+                StructLiteralField::Spread { base, .. } => {
+                    self.expression(base);
+                }
+                // `Point { x }` stores the variable `x` in the field `x`
+                StructLiteralField::Shorthand(name) => {
+                    if let Some(declared) = declared_field(&name.value) {
+                        let target = self.annotation(&declared.field.value);
+                        let value = match self.resolution.definitions.get(&name.source_span) {
+                            Some(Definition::Local(definition)) => self
+                                .variables
+                                .get(definition)
+                                .cloned()
+                                .unwrap_or(Type::Unknown),
+                            _ => Type::Unknown,
+                        };
+                        let variable = self.uninferred(name.source_span);
+                        self.store(variable, &value, &target, name.source_span);
+                    }
+                }
+            }
+        }
+        struct_type
+    }
+
+    /// Checks the statements of a block, the result is the type of its last expression when it
+    /// is not followed by a semicolon
+    fn block(&mut self, block: &'a Block) -> Type {
+        let Some(((last, _), statements)) = block.statements.split_last() else {
+            return Type::Unknown;
+        };
+        for (statement, _) in statements {
+            self.visit_statement(statement);
+        }
+        match &last.value {
+            Statement::Expression {
+                expr, semi: None, ..
+            } => self.expression(expr),
+            _ => {
+                self.visit_statement(last);
+                Type::Unknown
+            }
+        }
+    }
+
+    fn operation(
+        &mut self,
+        left: &'a Located<Expression>,
+        operator: Operator,
+        right: &'a Located<Expression>,
+    ) -> Type {
+        let left = self.expression(left);
+        let right = self.expression(right);
+        match operator {
+            Operator::Equals
+            | Operator::NotEquals
+            | Operator::LessThan
+            | Operator::LessThanOrEquals
+            | Operator::GreaterThan
+            | Operator::GreaterThanOrEquals
+            | Operator::And
+            | Operator::Or => Type::Bool,
+            Operator::Add if left == Type::String && right == Type::String => Type::String,
+            Operator::Add
+            | Operator::Subtract
+            | Operator::Multiply
+            | Operator::WrappingAdd
+            | Operator::WrappingSubtract
+            | Operator::WrappingMultiply
+            | Operator::Divide
+            | Operator::Modulo
+            | Operator::Power
+                if left == right && matches!(left, Type::Int | Type::Float) =>
+            {
+                left
+            }
+            _ => Type::Unknown,
+        }
+    }
+
+    /// Checks an expression and infers its type
+    fn expression(&mut self, expression: &'a Located<Expression>) -> Type {
+        match &expression.value {
+            Expression::Literal { value } => literal_type(value),
+            Expression::InterpolatedString { .. } => {
+                walk_expression(self, expression);
+                Type::String
+            }
+            Expression::Variable { .. } => self.variable_type(expression),
+            Expression::Parenthesized { expr, .. } => self.expression(expr),
+            Expression::Array { elements } => {
+                let mut element_type = None;
+                for element in elements.values() {
+                    let value = self.expression(element);
+                    element_type = Some(match element_type {
+                        None => value,
+                        Some(Type::Unknown) => Type::Unknown,
+                        Some(_) if value == Type::Unknown => Type::Unknown,
+                        Some(element_type) => element_type.union(value),
+                    });
+                }
+                Type::Array(Box::new(element_type.unwrap_or(Type::Unknown)))
+            }
+            Expression::Operation {
+                left,
+                operator: Operator::Assignment,
+                right,
+                ..
+            } => {
+                self.assignment(left, right, expression.source_span);
+                Type::Unknown
+            }
+            Expression::Operation {
+                left,
+                operator,
+                right,
+                ..
+            } => self.operation(left, *operator, right),
+            Expression::SingleOperation {
+                operation, operand, ..
+            } => {
+                let operand_type = self.expression(operand);
+                match operation {
+                    UnaryOperation::Get { property, .. } => {
+                        self.expression(property);
+                        match operand_type {
+                            Type::Array(element) => *element,
+                            _ => Type::Unknown,
+                        }
+                    }
+                    UnaryOperation::Call { arguments } => {
+                        self.call(operand, arguments);
+                        match self.resolution.definition(operand) {
+                            Some(Definition::Item(source_span)) => self
+                                .functions
+                                .get(&source_span)
+                                .cloned()
+                                .unwrap_or(Type::Unknown),
+                            _ => Type::Unknown,
+                        }
+                    }
+                    UnaryOperation::Not => Type::Bool,
+                    UnaryOperation::Negate | UnaryOperation::Positive => operand_type,
+                    _ => Type::Unknown,
+                }
+            }
+            Expression::StructLiteral { name, fields, .. } => {
+                self.struct_literal(name.as_deref(), fields)
+            }
+            Expression::Declaration {
+                creation,
+                value_type,
+                initializer,
+                ..
+            } => {
+                self.declaration(creation, value_type, initializer);
+                Type::Unknown
+            }
+            Expression::Closure { closure, .. } => {
+                self.closure(closure, expression.source_span);
+                Type::Unknown
+            }
+            Expression::Block { environment, block } => {
+                if let Some(environment) = environment {
+                    self.expression(environment);
+                }
+                self.block(block)
+            }
+            Expression::Unsafe { block, .. } | Expression::Const { block, .. } => self.block(block),
+            _ => {
+                walk_expression(self, expression);
+                Type::Unknown
+            }
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Checker<'a, '_> {
+    fn visit_statement(&mut self, statement: &'a Located<Statement>) {
+        match &statement.value {
+            Statement::Function { closure, .. } => self.function(closure, statement.source_span),
+            Statement::Impl {
+                target, statements, ..
+            } => {
+                let self_type = self.annotation(target);
+                let outer = self.self_type.replace(self_type);
+                for (statement, _) in &statements.statements {
+                    self.visit_statement(statement);
+                }
+                self.self_type = outer;
+            }
+            _ => walk_statement(self, statement),
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &'a Located<Expression>) {
+        self.expression(expression);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use analysis::{import_names, resolve_names};
+    use parser::simple_parse;
+
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../../example_files/types.qp");
+
+    /// The errors as `(line, message)`, lines start at 1
+    fn errors(code: &str) -> Vec<(usize, String)> {
+        let file = simple_parse(code).unwrap_or_else(|error| panic!("{}", error));
+        let resolution = resolve_names(&file, &[], |path| import_names(&path.value));
+        check_types(&file, &resolution)
+            .into_iter()
+            .map(|error| (error.source_span.start.line + 1, error.value.to_string()))
+            .collect()
+    }
+
+    fn messages(code: &str) -> Vec<String> {
+        errors(code)
+            .into_iter()
+            .map(|(_, message)| message)
+            .collect()
+    }
+
+    #[test]
+    fn test_example() {
+        // the example marks the lines with an error with `// error: {message}`
+        let expected: Vec<(usize, String)> = EXAMPLE
+            .lines()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                let (_, message) = line.split_once("// error: ")?;
+                Some((i + 1, message.trim().to_string()))
+            })
+            .collect();
+        assert_eq!(expected.len(), 3);
+        assert_eq!(errors(EXAMPLE), expected);
+    }
+
+    #[test]
+    fn test_inference() {
+        assert!(messages("let a = 1;\na = 2;").is_empty());
+        assert_eq!(
+            messages("let a = 1.5;\na = 2;"),
+            vec!["cannot assign int to float"]
+        );
+        assert_eq!(
+            messages("let a: _ = [1, 'a'];\na = [true];"),
+            vec!["cannot assign bool[] to (int | string)[]"]
+        );
+        // a variable without a value gets the type of the first value assigned to it
+        assert_eq!(
+            messages("let a;\na = 'a';\na = 1;"),
+            vec!["cannot assign int to string"]
+        );
+        assert_eq!(
+            messages("let a: int = 1;\nlet b = a;\nb = 'a';"),
+            vec!["cannot assign string to int"]
+        );
+    }
+
+    #[test]
+    fn test_annotations() {
+        assert_eq!(
+            messages("let a: string = 1;"),
+            vec!["cannot assign int to string"]
+        );
+        assert!(messages("let a: any = 1;\na = [1];").is_empty());
+        assert!(messages("let a: float | bool = true;").is_empty());
+        assert_eq!(
+            messages("let a: int[] = [1];\na = ['a'];"),
+            vec!["cannot assign string[] to int[]"]
+        );
+        assert_eq!(
+            messages("type Id = int | string;\nlet a: Id = 1.5;"),
+            vec!["cannot assign float to int | string"]
+        );
+        assert_eq!(
+            messages("let a: u8 = 1;\na = 1f32;"),
+            vec!["cannot assign float to int"]
+        );
+    }
+
+    #[test]
+    fn test_structs_and_functions() {
+        let point = "struct Point { x: int, y: int }\n";
+        assert_eq!(
+            messages(&format!("{}let p = Point {{ x: 1, y: 'a' }};", point)),
+            vec!["cannot assign string to int"]
+        );
+        // a parameter without a type is inferred from its use
+        assert_eq!(
+            messages(&format!(
+                "{}fn f(x) {{ let p = Point {{ x: x, y: 1 }}; x = 'a'; }}",
+                point
+            )),
+            vec!["cannot assign string to int"]
+        );
+        // a function returning `any` returns the type of its body
+        assert_eq!(
+            messages(&format!(
+                "{}fn f(x: any) -> any {{ Point {{ x: x, y: x }} }}\nlet a: int = f(1);",
+                point
+            )),
+            vec!["cannot assign Point to int"]
+        );
+        assert_eq!(
+            errors(&format!(
+                "{}fn f() -> int {{ 1 }}\nlet a: string = f();",
+                point
+            )),
+            vec![(3, "cannot assign int to string".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_return_types() {
+        assert!(messages("fn f() -> int { 1 }").is_empty());
+        assert_eq!(
+            errors(
+                "fn f() -> int {
+    'a'
+}"
+            ),
+            vec![(1, "cannot assign string to int".to_string())]
+        );
+        assert!(messages("fn f() -> any { 'a' }").is_empty());
+    }
+
+    #[test]
+    fn test_arguments() {
+        let point = "struct Point { x: int, y: int }
+";
+        assert!(messages(
+            "fn f(x: int) { x }
+f(1);"
+        )
+        .is_empty());
+        assert_eq!(
+            errors(
+                "fn f(x: int) { x }
+f('a');"
+            ),
+            vec![(2, "cannot assign string to int".to_string())]
+        );
+        // a parameter without a type only accepts the type it is inferred as
+        assert_eq!(
+            errors(&format!(
+                "{}fn new(x, y) {{ Point {{ x: x, y: y }} }}
+new(1, 'a');",
+                point
+            )),
+            vec![(3, "cannot assign string to int".to_string())]
+        );
+        assert!(messages(
+            "fn f(x: any) { x }
+f('a');"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_shorthand_fields() {
+        let point = "struct Point { x: int, y: int }
+";
+        assert!(messages(&format!(
+            "{}let x = 1;
+let y = 2;
+Point {{ x, y }};",
+            point
+        ))
+        .is_empty());
+        assert_eq!(
+            errors(&format!(
+                "{}let x = 1;
+let y = 'a';
+Point {{ x, y }};",
+                point
+            )),
+            vec![(4, "cannot assign string to int".to_string())]
+        );
+        // a variable without a type is inferred from the field it is stored in
+        assert_eq!(
+            messages(&format!(
+                "{}let x: _;
+Point {{ x, y: 1 }};
+x = 'a';",
+                point
+            )),
+            vec!["cannot assign string to int"]
+        );
+    }
+}
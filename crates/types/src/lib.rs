@@ -0,0 +1,24 @@
+//! Type checking of a parsed file.
+//!
+//! Literals are inferred as `int`, `float`, `string` or `bool`, array literals as arrays of the
+//! union of their elements. `_` or a missing type asks for strict inference: the variable keeps
+//! the first type it is given. `any` asks for loose inference: the variable accepts every value.
+//! Assignments, call arguments, struct literal fields and the bodies of functions with a return
+//! type are checked, every type that can not be inferred is accepted anywhere.
+
+mod check;
+mod types;
+
+use fst::Located;
+use thiserror::Error;
+
+pub use check::check_types;
+pub use types::Type;
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TypeError {
+    #[error("cannot assign {value} to {target}")]
+    Assignment { value: Type, target: Type },
+}
+
+pub type LocatedTypeError = Located<TypeError>;
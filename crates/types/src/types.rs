@@ -0,0 +1,118 @@
+use std::fmt::{Display, Formatter};
+
+/// The type of a value or of what a variable accepts
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    Int,
+    Float,
+    String,
+    Bool,
+    /// Accepts every value, `let b: any = 5;` can later be assigned a string
+    Any,
+    /// `[1, 2, 3]` is an array of int
+    Array(Box<Type>),
+    /// `int | string`, with at least two members that are not unions themselves
+    Union(Vec<Type>),
+    /// A struct or enum by its name
+    Named(String),
+    /// A type that is not inferred yet, or that can not be inferred, such as the type of `_` or
+    /// of a parameter without a type before it is used. Nothing is reported for it.
+    Unknown,
+}
+
+impl Type {
+    /// The union of two types, unions are flattened and a type is only listed once
+    pub fn union(self, other: Type) -> Type {
+        let mut members = Vec::new();
+        for member in [self, other] {
+            match member {
+                Type::Union(types) => members.extend(types),
+                member => members.push(member),
+            }
+        }
+        if members.contains(&Type::Any) {
+            return Type::Any;
+        }
+        let mut unique: Vec<Type> = Vec::new();
+        for member in members {
+            if !unique.contains(&member) {
+                unique.push(member);
+            }
+        }
+        match unique.len() {
+            1 => unique.pop().unwrap(),
+            _ => Type::Union(unique),
+        }
+    }
+
+    /// Whether a value of this type can be stored in a variable of `target`
+    pub fn is_assignable_to(&self, target: &Type) -> bool {
+        match (self, target) {
+            (Type::Unknown, _) | (_, Type::Unknown) | (Type::Any, _) | (_, Type::Any) => true,
+            // every member of the value has to fit
+            (Type::Union(members), target) => {
+                members.iter().all(|member| member.is_assignable_to(target))
+            }
+            (value, Type::Union(members)) => {
+                members.iter().any(|member| value.is_assignable_to(member))
+            }
+            (Type::Array(value), Type::Array(target)) => value.is_assignable_to(target),
+            (value, target) => value == target,
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Int => write!(f, "int"),
+            Type::Float => write!(f, "float"),
+            Type::String => write!(f, "string"),
+            Type::Bool => write!(f, "bool"),
+            Type::Any => write!(f, "any"),
+            Type::Array(element) if matches!(**element, Type::Union(_)) => {
+                write!(f, "({})[]", element)
+            }
+            Type::Array(element) => write!(f, "{}[]", element),
+            Type::Union(members) => {
+                for (i, member) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "{}", member)?;
+                }
+                Ok(())
+            }
+            Type::Named(name) => write!(f, "{}", name),
+            Type::Unknown => write!(f, "_"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union() {
+        let union = Type::Int.union(Type::String).union(Type::Int);
+        assert_eq!(union, Type::Union(vec![Type::Int, Type::String]));
+        assert_eq!(union.to_string(), "int | string");
+        assert_eq!(Type::Int.union(Type::Any), Type::Any);
+        assert_eq!(Type::Float.union(Type::Float), Type::Float);
+        assert_eq!(Type::Array(Box::new(union)).to_string(), "(int | string)[]");
+    }
+
+    #[test]
+    fn test_assignable() {
+        let union = Type::Int.union(Type::String);
+        assert!(Type::Int.is_assignable_to(&union));
+        assert!(!union.is_assignable_to(&Type::Int));
+        assert!(!Type::Int.is_assignable_to(&Type::String));
+        assert!(!Type::Int.is_assignable_to(&Type::Float));
+        assert!(Type::String.is_assignable_to(&Type::Any));
+        assert!(Type::Any.is_assignable_to(&Type::Int));
+        let ints = Type::Array(Box::new(Type::Int));
+        assert!(ints.is_assignable_to(&Type::Array(Box::new(union))));
+    }
+}
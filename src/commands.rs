@@ -0,0 +1,213 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use analysis::{
+    check_exhaustiveness, check_type_aliases, check_unsafety, evaluate_const_blocks, resolve_names,
+    Resolution,
+};
+use format::format_str;
+use fst::File;
+use loader::{load_project, ModuleGraph, ModuleId, OsFileSystem};
+use parser::{core::tokenize, simple_parse};
+use types::check_types;
+
+/// An error that prevents a command from running at all
+pub struct CommandError(pub String);
+
+/// The result of a command, `Ok(false)` if the input has errors
+pub type CommandResult = Result<bool, CommandError>;
+
+fn read(path: &Path) -> Result<String, CommandError> {
+    std::fs::read_to_string(path)
+        .map_err(|error| CommandError(format!("could not read {}: {}", path.display(), error)))
+}
+
+fn write(path: &Path, content: &str) -> Result<(), CommandError> {
+    std::fs::write(path, content)
+        .map_err(|error| CommandError(format!("could not write {}: {}", path.display(), error)))
+}
+
+/// Collects the given files and the .qp files in the given directories
+fn collect_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>, CommandError> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            collect_directory(path, &mut files)?;
+        } else if path.exists() {
+            files.push(path.clone());
+        } else {
+            return Err(CommandError(format!("{} does not exist", path.display())));
+        }
+    }
+    Ok(files)
+}
+
+fn collect_directory(directory: &Path, files: &mut Vec<PathBuf>) -> Result<(), CommandError> {
+    let entries = std::fs::read_dir(directory).map_err(|error| {
+        CommandError(format!("could not read {}: {}", directory.display(), error))
+    })?;
+    let mut paths = entries
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| CommandError(format!("could not read {}: {}", directory.display(), error)))?;
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_directory(&path, files)?;
+        } else if path.extension().is_some_and(|extension| extension == "qp") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn report(path: &Path, error: &str) {
+    eprintln!("error in {}:\n{}\n", path.display(), error);
+}
+
+pub fn tokens(file: &Path) -> CommandResult {
+    let code = read(file)?;
+    for token in tokenize(&code) {
+        let span = token.source_span;
+        println!(
+            "{}:{}-{}:{}\t{:?}\t{:?}",
+            span.start.line + 1,
+            span.start.column + 1,
+            span.end.line + 1,
+            span.end.column + 1,
+            token.kind(),
+            token.text
+        );
+    }
+    Ok(true)
+}
+
+pub fn parse(file: &Path, json: bool) -> CommandResult {
+    let code = read(file)?;
+    match simple_parse(&code) {
+        Ok(parsed) if json => {
+            let json = serde_json::to_string_pretty(&parsed)
+                .map_err(|error| CommandError(error.to_string()))?;
+            println!("{}", json);
+            Ok(true)
+        }
+        Ok(parsed) => {
+            println!("{:#?}", parsed);
+            Ok(true)
+        }
+        Err(error) => {
+            report(file, &error);
+            Ok(false)
+        }
+    }
+}
+
+pub fn fmt(paths: &[PathBuf], check: bool) -> CommandResult {
+    let mut success = true;
+    for file in collect_files(paths)? {
+        let code = read(&file)?;
+        let formatted = match format_str(&code) {
+            Ok(formatted) => formatted,
+            Err(error) => {
+                report(&file, &error);
+                success = false;
+                continue;
+            }
+        };
+        if formatted == code {
+            continue;
+        }
+        if check {
+            println!("{} is not formatted", file.display());
+            success = false;
+        } else {
+            write(&file, &formatted)?;
+        }
+    }
+    Ok(success)
+}
+
+/// The errors the analysis passes and the type checker find in a file, with their location
+fn analyze(file: &File, resolution: Resolution) -> Vec<String> {
+    let mut errors = check_unsafety(file);
+    errors.extend(evaluate_const_blocks(file, &resolution));
+    errors.extend(check_exhaustiveness(file));
+    errors.extend(check_type_aliases(file));
+    let type_errors = check_types(file, &resolution);
+    errors.extend(resolution.errors);
+    let mut messages: Vec<String> = errors
+        .iter()
+        .map(|error| format!("{} at {}", error.value, error.source_span))
+        .collect();
+    messages.extend(
+        type_errors
+            .iter()
+            .map(|error| format!("{} at {}", error.value, error.source_span)),
+    );
+    messages
+}
+
+/// The std tree imports of `std` are loaded from, `QUIP_STD` or the one next to the sources of
+/// quip
+fn std_root() -> PathBuf {
+    std::env::var_os("QUIP_STD")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/std")))
+}
+
+/// The errors of a loaded module, from reading and parsing it, resolving its imports and the
+/// analysis passes
+fn module_errors(graph: &ModuleGraph, id: ModuleId) -> Vec<String> {
+    let module = &graph[id];
+    let parsed = match &module.file {
+        Ok(parsed) => parsed,
+        Err(error) => return vec![error.clone()],
+    };
+    let mut errors: Vec<String> = module
+        .errors
+        .iter()
+        .map(|error| format!("{} at {}", error.value, error.source_span))
+        .collect();
+    // the names of the imports that could not be resolved are not known
+    let imported: HashMap<_, _> = module
+        .imports
+        .iter()
+        .filter(|import| !import.imported.is_empty())
+        .map(|import| (import.source_span, graph.imported_names(import)))
+        .collect();
+    let prelude = graph.prelude_names(id);
+    let resolution = resolve_names(parsed, &prelude, |path| {
+        imported.get(&path.source_span).cloned()
+    });
+    errors.extend(analyze(parsed, resolution));
+    errors
+}
+
+/// Loads every file with the modules it imports and reports their errors, a module imported by
+/// several files is checked once
+pub fn check(paths: &[PathBuf]) -> CommandResult {
+    let files = collect_files(paths)?;
+    let std_root = std_root();
+    let mut checked = HashSet::new();
+    let mut failed = 0;
+    for file in &files {
+        let graph = load_project(&OsFileSystem, file, &std_root);
+        for id in graph.ids() {
+            let module = &graph[id];
+            if !checked.insert(module.path.clone()) {
+                continue;
+            }
+            let errors = module_errors(&graph, id);
+            for error in &errors {
+                report(&module.path, error);
+            }
+            if !errors.is_empty() {
+                failed += 1;
+            }
+        }
+    }
+    eprintln!("checked {} files, {} with errors", checked.len(), failed);
+    Ok(failed == 0)
+}
@@ -1,13 +1,73 @@
-use parser::simple_parse;
+mod commands;
 
+use std::{path::PathBuf, process::ExitCode};
 
-fn main() {
-    let test_content = std::fs::read_to_string("example_files/4.qp").expect("Failed to read file");
+use clap::{Parser, Subcommand};
+use commands::CommandError;
 
-    match simple_parse(&test_content) {
-        Ok(block) =>  {
-            println!("{:?}", block);
-        },
-        Err(error) => println!("{}", error),
+#[derive(Parser)]
+#[command(
+    name = "quip",
+    about = "The quip programming language",
+    long_about = "The quip programming language. There is no interpreter or compiler yet, \
+                  programs can be checked but not run."
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prints the tokens of a file
+    Tokens { file: PathBuf },
+    /// Parses a file and prints its syntax tree
+    Parse {
+        file: PathBuf,
+        /// Prints the tree as JSON
+        #[arg(long, conflicts_with = "debug")]
+        json: bool,
+        /// Prints the tree in its Rust debug representation, this is the default
+        #[arg(long)]
+        debug: bool,
+    },
+    /// Formats files in place, directories are searched for .qp files
+    Fmt {
+        /// Only reports the files that are not formatted
+        #[arg(long)]
+        check: bool,
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Reports the errors in files and the modules they import, directories are searched for
+    /// .qp files
+    ///
+    /// Types are checked within each module only, items imported from another module accept
+    /// values of any type.
+    Check {
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+}
+
+/// Exit codes:
+/// - 0 the command succeeded
+/// - 1 the input has errors or, for `fmt --check`, is not formatted
+/// - 2 the command could not be run, a file could not be read or the arguments are invalid
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Tokens { file } => commands::tokens(&file),
+        Command::Parse { file, json, .. } => commands::parse(&file, json),
+        Command::Fmt { check, paths } => commands::fmt(&paths, check),
+        Command::Check { paths } => commands::check(&paths),
+    };
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(CommandError(message)) => {
+            eprintln!("error: {}", message);
+            ExitCode::from(2)
+        }
     }
-}
\ No newline at end of file
+}